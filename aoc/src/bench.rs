@@ -0,0 +1,179 @@
+use std::time::Duration;
+use aoc_common::{Solver, Diagnostics, Json, JSON_SCHEMA_VERSION, print_diagnostics, make_error};
+use super::days;
+use super::options::RunOptions;
+
+/// Timing samples for one phase of a day: parsing or solving one part.
+struct Phase {
+    day : u32,
+
+    /// "parse", "part 1" or "part 2".
+    name : String,
+
+    samples : Vec<Duration>,
+
+    /// Error returned by the part, if any. Parts that fail are still timed.
+    /// If the input can't be read or parsed, the day has a single "parse"
+    /// phase with the error and no samples.
+    error : Option<String>
+}
+
+impl Phase {
+    fn new(day : u32, name : &str) -> Phase {
+        Phase{ day, name : name.to_string(), samples : Vec::new(), error : None }
+    }
+
+    // Returns the minimum, median and maximum of the samples, if any.
+    fn stats(&self) -> Option<(Duration, Duration, Duration)> {
+        let mut v = self.samples.clone();
+        v.sort_unstable();
+        let n = v.len();
+        if n == 0 {
+            return None;
+        }
+        let median = if n % 2 == 1 { v[n / 2] } else { (v[n / 2 - 1] + v[n / 2]) / 2 };
+        Some((v[0], median, v[n - 1]))
+    }
+
+    fn to_json(&self) -> Json {
+        let stats = self.stats();
+        let ns = |get : fn(&(Duration, Duration, Duration)) -> Duration| {
+            stats.as_ref().map(|s| get(s).as_nanos() as u64).into()
+        };
+        Json::object()
+            .with("day", self.day.into())
+            .with("phase", self.name.as_str().into())
+            .with("samples", self.samples.len().into())
+            .with("min_ns", ns(|s| s.0))
+            .with("median_ns", ns(|s| s.1))
+            .with("max_ns", ns(|s| s.2))
+            .with("error", self.error.clone().into())
+    }
+}
+
+/// Times the parse and solve phases of the days selected by options,
+/// repeating each day sample_count times, and prints a table or JSON.
+/// A day whose input can't be read or parsed is reported with an error,
+/// and the other days are still run, but the command fails.
+///
+/// The JSON output is an object with a "schema" member (the same version
+/// as for the "run" command) and a "phases" member, an array with one
+/// object per phase:
+///
+/// ```text
+/// {
+///   "day": 8,
+///   "phase": "part 1",          // "parse", "part 1" or "part 2"
+///   "samples": 5,
+///   "min_ns": 10300,            // null if there are no samples
+///   "median_ns": 10500,
+///   "max_ns": 12100,
+///   "error": null
+/// }
+/// ```
+pub fn bench_command(options : &RunOptions, sample_count : usize, json : bool) -> std::io::Result<()> {
+    let mut phases = Vec::new();
+    let mut failed = false;
+    for &solver in options.solvers {
+        match bench_day(solver, options, sample_count) {
+            Ok(day_phases) => phases.extend(day_phases),
+            Err(e) => {
+                let mut phase = Phase::new(solver.day(), "parse");
+                phase.error = Some(e.to_string());
+                phases.push(phase);
+                failed = true;
+            }
+        }
+    }
+
+    if json {
+        let items : Vec<Json> = phases.iter().map(|phase| phase.to_json()).collect();
+        let report = Json::object()
+            .with("schema", JSON_SCHEMA_VERSION.into())
+            .with("phases", Json::Array(items));
+        println!("{}", report);
+    }
+    else {
+        print_table(&phases);
+    }
+
+    if failed {
+        return Err(make_error("Some days failed."));
+    }
+    Ok(())
+}
+
+// Runs one day sample_count times, returning the timings for each phase.
+fn bench_day(solver : &dyn Solver, options : &RunOptions, sample_count : usize) -> std::io::Result<Vec<Phase>> {
+    let day = solver.day();
+    let lines = options.source.read_lines(&days::day_dir(day), solver.input_file())?;
+    let file = options.source.name(solver.input_file());
+
+    let mut parse = Phase::new(day, "parse");
+    let mut parts : Vec<Phase> = Vec::new();
+    for sample in 0..sample_count {
+        let mut diag = Diagnostics::new(options.mode, Some(&file));
+        let result = solver.run(&lines, options.part, &mut diag);
+
+        // Print warnings once rather than for every sample.
+        if sample == 0 {
+            print_diagnostics(&diag);
+        }
+
+        let result = result?;
+        parse.samples.push(result.parse_time);
+        for (i, part) in result.parts.into_iter().enumerate() {
+            if sample == 0 {
+                parts.push(Phase::new(day, &format!("part {}", part.part)));
+            }
+            if let Err(e) = &part.answer {
+                parts[i].error = Some(e.to_string());
+            }
+            parts[i].samples.push(part.elapsed);
+        }
+    }
+
+    let mut phases = vec![parse];
+    phases.extend(parts);
+    Ok(phases)
+}
+
+fn print_table(phases : &[Phase]) {
+    println!("{:>4}  {:<7}  {:>7}  {:>10}  {:>10}  {:>10}", "Day", "Phase", "Samples", "Min", "Median", "Max");
+    for phase in phases {
+        let times = match phase.stats() {
+            Some((min, median, max)) => [min, median, max].iter().map(|&d| format_duration(d)).collect(),
+            None => vec!["-".to_string(); 3]
+        };
+        print!(
+            "{:>4}  {:<7}  {:>7}  {:>10}  {:>10}  {:>10}",
+            phase.day,
+            phase.name,
+            phase.samples.len(),
+            times[0],
+            times[1],
+            times[2]
+        );
+        match &phase.error {
+            Some(e) => println!("  error: {}", e),
+            None => println!()
+        }
+    }
+}
+
+/// Formats a duration with units suited to its size, e.g., "12.3 ms".
+pub fn format_duration(d : Duration) -> String {
+    let ns = d.as_nanos() as f64;
+    if ns < 1e3 {
+        format!("{} ns", ns)
+    }
+    else if ns < 1e6 {
+        format!("{:.1} us", ns / 1e3)
+    }
+    else if ns < 1e9 {
+        format!("{:.1} ms", ns / 1e6)
+    }
+    else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
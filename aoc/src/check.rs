@@ -0,0 +1,99 @@
+use aoc_common::{Answer, Solver, InputSource, ParseMode, Diagnostics, ExpectedAnswers, DEFAULT_INPUT_NAME, print_diagnostics, make_error};
+use super::days;
+
+/// Outcome of checking one part against its expected answer.
+enum Status {
+    Pass,
+    Fail(String),
+    Mismatch(Answer, Answer)
+}
+
+// Number of parts with each status.
+#[derive(Default)]
+struct Totals {
+    passed : u32,
+    failed : u32,
+    mismatched : u32
+}
+
+// Implements the "check" command, which runs the days on every input in
+// the answers file and compares the answers with the expected answers.
+pub fn check_command(solvers : &[&dyn Solver], mode : ParseMode) -> std::io::Result<()> {
+    let path = days::answers_path();
+    let expected = ExpectedAnswers::load(&path.to_string_lossy())?;
+
+    let mut totals = Totals::default();
+    for &solver in solvers {
+        let day = solver.day();
+        let inputs = expected.inputs(day);
+        if inputs.is_empty() {
+            println!("Day {}: no expected answers", day);
+        }
+        for input in inputs {
+            check_input(solver, input, &expected, mode, &mut totals);
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} mismatched.",
+        totals.passed, totals.failed, totals.mismatched
+    );
+    if totals.failed != 0 || totals.mismatched != 0 {
+        return Err(make_error("Check failed."));
+    }
+    Ok(())
+}
+
+// Checks one day on one input, printing the status of each part with an
+// expected answer.
+fn check_input(solver : &dyn Solver, input : &str, expected : &ExpectedAnswers, mode : ParseMode, totals : &mut Totals) {
+    let day = solver.day();
+    let parts : Vec<(u32, &Answer)> = (1..=2)
+        .filter_map(|part| expected.get(day, input, part).map(|answer| (part, answer)))
+        .collect();
+    if parts.is_empty() {
+        return;
+    }
+
+    let source = if input == DEFAULT_INPUT_NAME {
+        InputSource::Default
+    }
+    else {
+        InputSource::Example(input.to_string())
+    };
+
+    // Read and parse the input once for both parts. An error here fails
+    // every part with an expected answer.
+    let mut diag = Diagnostics::new(mode, Some(&source.name(solver.input_file())));
+    let results = source.read_lines(&days::day_dir(day), solver.input_file())
+        .and_then(|lines| solver.run(&lines, None, &mut diag));
+    print_diagnostics(&diag);
+
+    for (part, expected_answer) in parts {
+        let status = match &results {
+            Err(e) => Status::Fail(e.to_string()),
+            Ok(results) => match results.parts.iter().find(|r| r.part == part).map(|r| &r.answer) {
+                Some(Ok(answer)) if answer == expected_answer => Status::Pass,
+                Some(Ok(answer)) => Status::Mismatch(expected_answer.clone(), answer.clone()),
+                Some(Err(e)) => Status::Fail(e.to_string()),
+                None => Status::Fail("Part not run.".to_string())
+            }
+        };
+
+        let label = format!("Day {} ({}) part {}", day, input, part);
+        match status {
+            Status::Pass => {
+                println!("{}: pass", label);
+                totals.passed += 1;
+            },
+            Status::Fail(message) => {
+                println!("{}: fail: {}", label, message);
+                totals.failed += 1;
+            },
+            Status::Mismatch(expected_answer, actual) => {
+                println!("{}: mismatch: expected {}, found {}", label, expected_answer, actual);
+                totals.mismatched += 1;
+            }
+        }
+    }
+}
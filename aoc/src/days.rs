@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use aoc_common::Solver;
+
+// Solutions for all the days, in order.
+pub static DAYS : &[&dyn Solver] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+];
+
+// Returns a slice of DAYS containing only the specified day.
+pub fn day_slice(day : u32) -> Option<&'static [&'static dyn Solver]> {
+    let index = DAYS.iter().position(|solver| solver.day() == day)?;
+    Some(&DAYS[index..=index])
+}
+
+// Returns the root directory of the workspace.
+pub fn workspace_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+// Returns the directory of the crate for the specified day.
+pub fn day_dir(day : u32) -> PathBuf {
+    workspace_dir().join(format!("day{}", day))
+}
+
+// Returns the path of the expected answers file, in the workspace root.
+pub fn answers_path() -> PathBuf {
+    workspace_dir().join("answers.toml")
+}
@@ -0,0 +1,48 @@
+use std::fs;
+use aoc_common::{Solver, Rng, ParseMode, Diagnostics, make_error};
+
+/// Implements the "gen" command, which writes a random input for a day to
+/// output (or stdout if None). If answers is true, the expected answers are
+/// written to stderr, in the format of the expected answers file.
+pub fn gen_command(solver : &dyn Solver, seed : u64, size : usize, output : Option<&str>, answers : bool) -> std::io::Result<()> {
+    let day = solver.day();
+    let mut rng = Rng::new(seed);
+    let generated = solver.generate(&mut rng, size).ok_or_else(
+        || make_error(&format!("Day {} has no input generator.", day))
+    )?;
+
+    let mut text = generated.lines.join("\n");
+    text.push('\n');
+    match output {
+        Some(path) => fs::write(path, text)?,
+        None => print!("{}", text)
+    }
+
+    if answers {
+        // Run the solver for parts whose answers are not known from the
+        // way the input was generated.
+        let mut computed = None;
+        if generated.answers.iter().any(|answer| answer.is_none()) {
+            let mut diag = Diagnostics::new(ParseMode::Strict, Some("<generated>"));
+            computed = Some(solver.run(&generated.lines, None, &mut diag)?);
+        }
+
+        for (i, known) in generated.answers.iter().enumerate() {
+            let part = i as u32 + 1;
+            match known {
+                Some(answer) => eprintln!("part{} = {}", part, answer),
+                None => {
+                    let result = computed.as_ref().and_then(
+                        |run| run.parts.iter().find(|r| r.part == part)
+                    );
+                    match result.map(|r| &r.answer) {
+                        Some(Ok(answer)) => eprintln!("part{} = {}  # computed by the solver", part, answer),
+                        Some(Err(e)) => eprintln!("# part{}: error: {}", part, e),
+                        None => eprintln!("# part{}: no answer", part)
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use std::time::{Duration, Instant};
+use aoc_common::{log, Json, ParseMode, Diagnostics, day_to_json, report_to_json, parse_output_format, print_diagnostics, make_error};
+use options::{RunOptions, parse_count};
+use parallel::Outcome;
+
+mod bench;
+mod check;
+mod days;
+mod generate;
+mod options;
+mod parallel;
+mod scaffold;
+mod serve;
+mod snapshot;
+mod watch;
+
+const USAGE : &str = "Usage:
+    aoc run (<day> [--input <path> | --stdin | --example <name>] | --all) [--part 1|2] [--strict]
+            [--format text|json] [--jobs <n>] [--timeout <seconds>]
+    aoc check [<day>] [--strict]
+    aoc snapshot [<day>] [--bless]
+    aoc bench (<day> [--input <path> | --stdin | --example <name>] | --all) [--part 1|2] [--strict]
+              [--samples <n>] [--format text|json]
+    aoc new <day>
+    aoc gen <day> [--seed <n>] [--size <n>] [--output <path>] [--answers]
+    aoc serve [--port <n>] [--strict] [--timeout <seconds>]
+    aoc watch <day> [--input <path> | --example <name>] [--part 1|2] [--strict] [--interval <ms>]
+
+Any command also accepts -v, -vv or -vvv to log info, debug or trace
+messages to stderr, or -q to log only errors. The AOC_LOG environment
+variable sets the level (error, warn, info, debug or trace) instead.";
+
+// Default time limit for each day for the "run" command, and for each
+// request for the "serve" command.
+const DEFAULT_TIMEOUT_SECS : u64 = 300;
+
+// Default number of times to run each day for the "bench" command.
+const DEFAULT_SAMPLE_COUNT : usize = 5;
+
+// Default time between checks for changes, in milliseconds, for the
+// "watch" command.
+const DEFAULT_WATCH_INTERVAL_MS : usize = 500;
+
+// Default port for the "serve" command.
+const DEFAULT_PORT : u16 = 8020;
+
+// Default seed and size for the "gen" command.
+const DEFAULT_SEED : u64 = 1;
+const DEFAULT_GEN_SIZE : usize = 100;
+
+fn main() {
+    // The logging options can appear anywhere, for any command.
+    let args : Vec<String> = std::env::args().skip(1).filter(|arg| !log::parse_log_option(arg)).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(args : &[String]) -> std::io::Result<()> {
+    match args.first().map(|s| s.as_str()) {
+        Some("run") => run_command(&args[1..]),
+        Some("check") => check_command(&args[1..]),
+        Some("snapshot") => snapshot_command(&args[1..]),
+        Some("bench") => bench_command(&args[1..]),
+        Some("new") => new_command(&args[1..]),
+        Some("gen") => gen_command(&args[1..]),
+        Some("serve") => serve_command(&args[1..]),
+        Some("watch") => watch_command(&args[1..]),
+        _ => Err(make_error(USAGE))
+    }
+}
+
+// Implements the "run" command.
+fn run_command(args : &[String]) -> std::io::Result<()> {
+    let mut json = false;
+    let mut job_count = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+    let options = RunOptions::parse(args, USAGE, |arg, iter| {
+        match arg {
+            "--format" => { json = parse_output_format(iter.next())?; },
+            "--jobs" => { job_count = parse_count(arg, iter.next())?; },
+            "--timeout" => { timeout = Duration::from_secs(parse_count(arg, iter.next())? as u64); },
+            _ => { return Ok(false); }
+        }
+        Ok(true)
+    })?;
+
+    let start = Instant::now();
+    let outcomes = parallel::run_days(&options, job_count, timeout, json);
+    let wall_time = start.elapsed();
+
+    if json {
+        let days : Vec<Json> = outcomes.iter().map(|outcome| day_json(outcome, options.mode)).collect();
+        println!("{}", report_to_json(days));
+    }
+    else if outcomes.len() == 1 {
+        print_day(&outcomes[0]);
+    }
+    else {
+        for outcome in &outcomes {
+            if let Outcome::Finished(_, diag) = &outcome.outcome {
+                print_diagnostics(diag);
+            }
+        }
+        parallel::print_summary(&outcomes, wall_time);
+    }
+
+    // Report all the days, then fail if any couldn't be run, so the exit
+    // status shows the failure.
+    let failed : Vec<String> = outcomes.iter()
+        .filter_map(|outcome| outcome.error().map(|e| format!("Day {}: {}", outcome.day, e)))
+        .collect();
+    match (failed.len(), outcomes.len()) {
+        (0, _) => Ok(()),
+        (_, 1) => Err(make_error(&failed[0])),
+        _ => Err(make_error("Some days failed."))
+    }
+}
+
+// Implements the "check" command.
+fn check_command(args : &[String]) -> std::io::Result<()> {
+    let mut solvers = days::DAYS;
+    let mut mode = ParseMode::Lenient;
+
+    for arg in args {
+        match arg.as_str() {
+            "--strict" => { mode = ParseMode::Strict; },
+            _ => {
+                let n = arg.parse::<u32>().map_err(|_| make_error(USAGE))?;
+                solvers = days::day_slice(n).ok_or_else(
+                    || make_error(&format!("Day {} is not implemented.", n))
+                )?;
+            }
+        }
+    }
+    check::check_command(solvers, mode)
+}
+
+// Implements the "snapshot" command.
+fn snapshot_command(args : &[String]) -> std::io::Result<()> {
+    let mut solvers = days::DAYS;
+    let mut bless = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--bless" => { bless = true; },
+            _ => {
+                let n = arg.parse::<u32>().map_err(|_| make_error(USAGE))?;
+                solvers = days::day_slice(n).ok_or_else(
+                    || make_error(&format!("Day {} is not implemented.", n))
+                )?;
+            }
+        }
+    }
+    snapshot::snapshot_command(solvers, bless)
+}
+
+// Implements the "bench" command.
+fn bench_command(args : &[String]) -> std::io::Result<()> {
+    let mut sample_count = DEFAULT_SAMPLE_COUNT;
+    let mut json = false;
+    let options = RunOptions::parse(args, USAGE, |arg, iter| {
+        match arg {
+            "--samples" => { sample_count = parse_count(arg, iter.next())?; },
+            "--format" => { json = parse_output_format(iter.next())?; },
+            _ => { return Ok(false); }
+        }
+        Ok(true)
+    })?;
+    bench::bench_command(&options, sample_count, json)
+}
+
+// Implements the "new" command.
+fn new_command(args : &[String]) -> std::io::Result<()> {
+    match args {
+        [day] => scaffold::new_command(day.parse::<u32>().map_err(|_| make_error(USAGE))?),
+        _ => Err(make_error(USAGE))
+    }
+}
+
+// Implements the "gen" command.
+fn gen_command(args : &[String]) -> std::io::Result<()> {
+    let mut solver = None;
+    let mut seed = DEFAULT_SEED;
+    let mut size = DEFAULT_GEN_SIZE;
+    let mut output = None;
+    let mut answers = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--seed" => {
+                seed = iter.next().and_then(|s| s.parse::<u64>().ok()).ok_or_else(
+                    || make_error("--seed must be followed by a number.")
+                )?;
+            },
+            "--size" => { size = parse_count(arg, iter.next())?; },
+            "--output" => {
+                output = Some(iter.next().ok_or_else(
+                    || make_error("--output must be followed by a path.")
+                )?.as_str());
+            },
+            "--answers" => { answers = true; },
+            _ => {
+                let n = arg.parse::<u32>().map_err(|_| make_error(USAGE))?;
+                solver = Some(days::day_slice(n).ok_or_else(
+                    || make_error(&format!("Day {} is not implemented.", n))
+                )?[0]);
+            }
+        }
+    }
+    let solver = solver.ok_or_else(|| make_error(USAGE))?;
+    generate::gen_command(solver, seed, size, output, answers)
+}
+
+// Implements the "serve" command.
+fn serve_command(args : &[String]) -> std::io::Result<()> {
+    let mut port = DEFAULT_PORT;
+    let mut mode = ParseMode::Lenient;
+    let mut timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--port" => {
+                port = iter.next().and_then(|s| s.parse::<u16>().ok()).ok_or_else(
+                    || make_error("--port must be followed by a port number.")
+                )?;
+            },
+            "--strict" => { mode = ParseMode::Strict; },
+            "--timeout" => { timeout = Duration::from_secs(parse_count(arg, iter.next())? as u64); },
+            _ => { return Err(make_error(USAGE)); }
+        }
+    }
+    serve::serve_command(port, mode, timeout)
+}
+
+// Implements the "watch" command.
+fn watch_command(args : &[String]) -> std::io::Result<()> {
+    let mut interval = DEFAULT_WATCH_INTERVAL_MS;
+    let options = RunOptions::parse(args, USAGE, |arg, iter| {
+        match arg {
+            "--interval" => { interval = parse_count(arg, iter.next())?; },
+            _ => { return Ok(false); }
+        }
+        Ok(true)
+    })?;
+    watch::watch_command(&options, Duration::from_millis(interval as u64))
+}
+
+// Prints the answers for one day.
+fn print_day(outcome : &parallel::DayOutcome) {
+    if let Outcome::Finished(result, diag) = &outcome.outcome {
+        print_diagnostics(diag);
+        if let Ok(result) = result {
+            for part in &result.parts {
+                match &part.answer {
+                    Ok(answer) => println!("Day {} part {}: {}", outcome.day, part.part, answer),
+                    Err(e) => println!("Day {} part {}: error: {}", outcome.day, part.part, e)
+                }
+            }
+        }
+    }
+}
+
+// Converts the outcome of running one day to JSON, including any error.
+fn day_json(outcome : &parallel::DayOutcome, mode : ParseMode) -> Json {
+    match &outcome.outcome {
+        Outcome::Finished(result, diag) => day_to_json(outcome.day, &outcome.input, result, diag),
+        _ => {
+            let error = make_error(&outcome.error().unwrap_or_default());
+            let diag = Diagnostics::new(mode, Some(&outcome.input));
+            day_to_json(outcome.day, &outcome.input, &Err(error), &diag)
+        }
+    }
+}
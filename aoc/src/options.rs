@@ -0,0 +1,78 @@
+use std::slice::Iter;
+use aoc_common::{Solver, InputSource, ParseMode, make_error};
+use super::days;
+
+/// Options shared by the commands that run days.
+pub struct RunOptions {
+    /// Days to run: either one day, or all of them with --all.
+    pub solvers : &'static [&'static dyn Solver],
+    pub part : Option<u32>,
+    pub source : InputSource,
+    pub mode : ParseMode
+}
+
+impl RunOptions {
+    /// Parses the arguments of a command that runs days:
+    ///
+    ///     (<day> [<input options>] | --all) [--part 1|2] [--strict]
+    ///
+    /// Other options are passed to other_option, which returns false if it
+    /// doesn't recognize an option, and may take the option's value from
+    /// the iterator.
+    pub fn parse<'a, F>(args : &'a [String], usage : &str, mut other_option : F) -> std::io::Result<RunOptions>
+    where F: FnMut(&str, &mut Iter<'a, String>) -> std::io::Result<bool> {
+        let mut solvers = None;
+        let mut all = false;
+        let mut part = None;
+        let mut source = InputSource::Default;
+        let mut mode = ParseMode::Lenient;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(s) = InputSource::from_arg(arg, &mut iter)? {
+                source = s;
+                continue;
+            }
+            match arg.as_str() {
+                "--all" => { all = true; },
+                "--strict" => { mode = ParseMode::Strict; },
+                "--part" => {
+                    part = match iter.next().map(|s| s.as_str()) {
+                        Some("1") => Some(1),
+                        Some("2") => Some(2),
+                        _ => { return Err(make_error("--part must be followed by 1 or 2.")); }
+                    };
+                },
+                _ => {
+                    if other_option(arg, &mut iter)? {
+                        continue;
+                    }
+                    let n = arg.parse::<u32>().map_err(|_| make_error(usage))?;
+                    solvers = Some(days::day_slice(n).ok_or_else(
+                        || make_error(&format!("Day {} is not implemented.", n))
+                    )?);
+                }
+            }
+        }
+
+        let solvers = match (solvers, all) {
+            (Some(solvers), false) => solvers,
+            (None, true) => {
+                if source != InputSource::Default {
+                    return Err(make_error("Input options can't be used with --all."));
+                }
+                days::DAYS
+            },
+            _ => { return Err(make_error(usage)); }
+        };
+        Ok(RunOptions{ solvers, part, source, mode })
+    }
+}
+
+/// Parses the value of an option that must be followed by a number.
+pub fn parse_count(name : &str, value : Option<&String>) -> std::io::Result<usize> {
+    match value.and_then(|s| s.parse::<usize>().ok()) {
+        Some(n) if n > 0 => Ok(n),
+        _ => Err(make_error(&format!("{} must be followed by a positive number.", name)))
+    }
+}
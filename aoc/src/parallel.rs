@@ -0,0 +1,263 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Once};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use aoc_common::{Solver, Diagnostics, RunResult};
+use super::bench::format_duration;
+use super::days;
+use super::options::RunOptions;
+
+/// How running a day ended.
+pub enum Outcome {
+    /// The solver returned, with the result and any warnings. The result
+    /// is an error if the input couldn't be read or parsed.
+    Finished(std::io::Result<RunResult>, Diagnostics),
+
+    /// The solver panicked, with the panic message.
+    Panicked(String),
+
+    /// The solver didn't finish within the timeout. Its thread is left
+    /// running, as threads can't be stopped.
+    TimedOut
+}
+
+/// The outcome of running one day.
+pub struct DayOutcome {
+    pub day : u32,
+
+    /// Name of the input, for reports.
+    pub input : String,
+
+    pub outcome : Outcome,
+
+    /// Time from starting the day to finishing or timing out.
+    pub elapsed : Duration
+}
+
+impl DayOutcome {
+    /// Returns the error for the day as a whole, if any.
+    pub fn error(&self) -> Option<String> {
+        match &self.outcome {
+            Outcome::Finished(Ok(_), _) => None,
+            Outcome::Finished(Err(e), _) => Some(e.to_string()),
+            Outcome::Panicked(message) => Some(format!("panicked: {}", message)),
+            Outcome::TimedOut => Some(format!("timed out after {:.1} s", self.elapsed.as_secs_f64()))
+        }
+    }
+}
+
+/// Runs the days selected by options on up to job_count threads at a time,
+/// and returns their outcomes in the same order as the days. A panic or
+/// timeout is reported as the outcome for its day, and the other days are
+/// still run. If details is true, each day's details are also returned.
+pub fn run_days(options : &RunOptions, job_count : usize, timeout : Duration, details : bool) -> Vec<DayOutcome> {
+    let solvers = options.solvers;
+    let mut outcomes : Vec<Option<DayOutcome>> = solvers.iter().map(|_| None).collect();
+
+    let (sender, receiver) = mpsc::channel();
+    let mut next = 0;
+    let mut running : Vec<(usize, Instant)> = Vec::new();
+    loop {
+        // Start days until all the threads are busy.
+        while running.len() < job_count && next < solvers.len() {
+            let sender = sender.clone();
+            let solver = solvers[next];
+            let index = next;
+            let source = options.source.clone();
+            let (part, mode) = (options.part, options.mode);
+            spawn_worker(solver.day(), move || {
+                let name = source.name(solver.input_file());
+                aoc_common::debug!("day {}: started on {}", solver.day(), name);
+                let mut diag = Diagnostics::new(mode, Some(&name));
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    let lines = source.read_lines(&days::day_dir(solver.day()), solver.input_file())?;
+                    if details {
+                        solver.run_detailed(&lines, part, &mut diag)
+                    }
+                    else {
+                        solver.run(&lines, part, &mut diag)
+                    }
+                }));
+                let outcome = match result {
+                    Ok(result) => Outcome::Finished(result, diag),
+                    Err(payload) => Outcome::Panicked(panic_message(payload.as_ref()))
+                };
+                // The receiver is gone if the day timed out and all the
+                // others have finished, so ignore any error.
+                let _ = sender.send((index, outcome));
+            });
+            running.push((index, Instant::now()));
+            next += 1;
+        }
+        if running.is_empty() {
+            break;
+        }
+
+        // Wait for a day to finish, or for the next timeout.
+        let deadline = running.iter().map(|&(_, start)| start + timeout).min().unwrap();
+        let wait = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(wait) {
+            Ok((index, outcome)) => {
+                // Ignore days that already timed out.
+                if let Some(i) = running.iter().position(|&(r, _)| r == index) {
+                    let (_, start) = running.remove(i);
+                    aoc_common::debug!("day {}: finished in {:?}", solvers[index].day(), start.elapsed());
+                    outcomes[index] = Some(day_outcome(solvers[index], options, outcome, start.elapsed()));
+                }
+            },
+            Err(_) => {
+                let now = Instant::now();
+                running.retain(|&(index, start)| {
+                    if now < start + timeout {
+                        return true;
+                    }
+                    aoc_common::info!("day {}: timed out", solvers[index].day());
+                    outcomes[index] = Some(day_outcome(solvers[index], options, Outcome::TimedOut, now - start));
+                    false
+                });
+            }
+        }
+    }
+
+    outcomes.into_iter().map(|outcome| outcome.unwrap()).collect()
+}
+
+// Prefix of the names of the threads that run days.
+const WORKER_PREFIX : &str = "aoc-day-";
+
+/// Runs f on a new thread for the day. A panic on the thread isn't printed,
+/// as the caller is expected to catch it and report it with the day's
+/// outcome; panics on other threads are printed as usual.
+pub fn spawn_worker<F>(day : u32, f : F) -> JoinHandle<()> where F : FnOnce() + Send + 'static {
+    static QUIET_WORKERS : Once = Once::new();
+    QUIET_WORKERS.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !thread::current().name().is_some_and(|name| name.starts_with(WORKER_PREFIX)) {
+                default_hook(info);
+            }
+        }));
+    });
+    thread::Builder::new()
+        .name(format!("{}{}", WORKER_PREFIX, day))
+        .spawn(f)
+        .expect("failed to spawn a thread")
+}
+
+fn day_outcome(solver : &dyn Solver, options : &RunOptions, outcome : Outcome, elapsed : Duration) -> DayOutcome {
+    DayOutcome{
+        day : solver.day(),
+        input : options.source.name(solver.input_file()),
+        outcome,
+        elapsed
+    }
+}
+
+// Returns the message of a panic, which is usually a string.
+pub fn panic_message(payload : &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    }
+    else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    }
+    else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Prints a table with the answer, time and status of each part of each
+/// day, followed by the total time.
+pub fn print_summary(outcomes : &[DayOutcome], wall_time : Duration) {
+    // Each row is the day, part, answer, time and status.
+    let mut rows : Vec<[String; 5]> = Vec::new();
+    for outcome in outcomes {
+        let day = outcome.day.to_string();
+        match (&outcome.outcome, outcome.error()) {
+            (Outcome::Finished(Ok(result), _), _) => {
+                for part in &result.parts {
+                    let (answer, status) = match &part.answer {
+                        Ok(answer) => (answer.to_string(), "ok".to_string()),
+                        Err(e) => ("-".to_string(), format!("error: {}", e))
+                    };
+                    rows.push([day.clone(), part.part.to_string(), answer, format_duration(part.elapsed), status]);
+                }
+            },
+            (_, error) => {
+                let status = error.unwrap_or_default();
+                rows.push([day, "-".to_string(), "-".to_string(), format_duration(outcome.elapsed), status]);
+            }
+        }
+    }
+
+    let answer_width = rows.iter().map(|row| row[2].len()).chain(Some("Answer".len())).max().unwrap();
+    println!("{:>4}  {:>4}  {:<w$}  {:>10}  Status", "Day", "Part", "Answer", "Time", w = answer_width);
+    for row in &rows {
+        println!("{:>4}  {:>4}  {:<w$}  {:>10}  {}", row[0], row[1], row[2], row[3], row[4], w = answer_width);
+    }
+
+    let failed = outcomes.iter().filter(|outcome| outcome.error().is_some()).count();
+    println!("{} days in {}, {} failed.", outcomes.len(), format_duration(wall_time), failed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{Answer, InputSource, ParseError, ParseMode, Solution};
+
+    // Solutions that finish, panic, or take too long in part 2.
+    struct Finishes;
+    struct Panics;
+    struct Sleeps;
+
+    macro_rules! test_solution {
+        ($t:ty, $day:expr, $part2:expr) => {
+            impl Solution for $t {
+                type Input = usize;
+                const DAY: u32 = $day;
+
+                fn parse(&self, lines: &[String], _diag: &mut Diagnostics) -> Result<usize, ParseError> {
+                    Ok(lines.len())
+                }
+                fn part1(&self, _input: &usize) -> std::io::Result<Answer> {
+                    Ok(Answer::from(1))
+                }
+                fn part2(&self, _input: &usize) -> std::io::Result<Answer> {
+                    $part2
+                }
+            }
+        };
+    }
+
+    test_solution!(Finishes, 1, Ok(Answer::from(2)));
+    test_solution!(Panics, 2, panic!("bad input"));
+    test_solution!(Sleeps, 3, {
+        thread::sleep(Duration::from_secs(10));
+        Ok(Answer::from(2))
+    });
+
+    static SOLVERS : &[&dyn Solver] = &[&Sleeps, &Panics, &Finishes];
+
+    #[test]
+    fn reports_panics_and_timeouts() {
+        let options = RunOptions{
+            solvers : SOLVERS,
+            part : None,
+            source : InputSource::File(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml").to_string()),
+            mode : ParseMode::Lenient
+        };
+        let outcomes = run_days(&options, 2, Duration::from_millis(200), false);
+
+        let days : Vec<u32> = outcomes.iter().map(|outcome| outcome.day).collect();
+        assert_eq!(days, vec![3, 2, 1]);
+        assert!(matches!(outcomes[0].outcome, Outcome::TimedOut));
+        assert_eq!(outcomes[1].error().as_deref(), Some("panicked: bad input"));
+        match &outcomes[2].outcome {
+            Outcome::Finished(Ok(result), _) => {
+                let answers : Vec<Answer> = result.parts.iter().map(|part| part.answer.as_ref().unwrap().clone()).collect();
+                assert_eq!(answers, vec![Answer::from(1), Answer::from(2)]);
+            },
+            _ => panic!("day 1 should finish")
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::Path;
+use aoc_common::{DEFAULT_INPUT_NAME, make_error};
+use super::days;
+
+// Contents of a new day's Cargo.toml, where {day} is the day number.
+const CARGO_TOML : &str = r#"[package]
+name = "day{day}"
+version = "0.1.0"
+authors = ["Niklas Borson <NIKLASB@microsoft.com>"]
+edition = "2018"
+
+# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+[dependencies]
+aoc-common = { path = "../common" }
+"#;
+
+// Contents of a new day's main.rs.
+const MAIN_RS : &str = r#"fn main() -> std::io::Result<()> {
+    aoc_common::run_main(&day{day}::Day{day}, env!("CARGO_MANIFEST_DIR"))
+}
+"#;
+
+/// Implements the "new" command, which creates the crate for a new day
+/// from template.rs and registers it in the workspace, the aoc runner,
+/// and the expected answers file.
+pub fn new_command(day : u32) -> std::io::Result<()> {
+    if !(1..=25).contains(&day) {
+        return Err(make_error("The day must be from 1 to 25."));
+    }
+    let root = days::workspace_dir();
+    let dir = days::day_dir(day);
+    if dir.exists() {
+        return Err(make_error(&format!("day{} already exists.", day)));
+    }
+
+    // Make all the edits to existing files before creating anything, so
+    // nothing is changed if the day is already registered.
+    let cargo_path = root.join("Cargo.toml");
+    let cargo_toml = insert_day_line(
+        &read(&cargo_path)?, day, |n| format!("    \"day{}\",", n)
+    ).map_err(|e| in_file(&cargo_path, &e))?;
+
+    let aoc_cargo_path = root.join("aoc").join("Cargo.toml");
+    let aoc_cargo_toml = insert_day_line(
+        &read(&aoc_cargo_path)?, day, |n| format!("day{} = {{ path = \"../day{}\" }}", n, n)
+    ).map_err(|e| in_file(&aoc_cargo_path, &e))?;
+
+    let days_path = root.join("aoc").join("src").join("days.rs");
+    let days_rs = insert_day_line(
+        &read(&days_path)?, day, |n| format!("    &day{}::Day{},", n, n)
+    ).map_err(|e| in_file(&days_path, &e))?;
+
+    let lib_rs = read(&root.join("template.rs"))?
+        .replace("DayN", &format!("Day{}", day))
+        .replace("const DAY: u32 = 0;", &format!("const DAY: u32 = {};", day));
+
+    // Create the crate.
+    let expand = |s : &str| s.replace("{day}", &day.to_string());
+    fs::create_dir_all(dir.join("src"))?;
+    fs::create_dir_all(dir.join("examples"))?;
+    fs::write(dir.join("Cargo.toml"), expand(CARGO_TOML))?;
+    fs::write(dir.join("src").join("main.rs"), expand(MAIN_RS))?;
+    fs::write(dir.join("src").join("lib.rs"), lib_rs)?;
+    fs::write(dir.join("input.txt"), "")?;
+    fs::write(dir.join("examples").join("example.txt"), "")?;
+
+    // Register it.
+    fs::write(&cargo_path, cargo_toml)?;
+    fs::write(&aoc_cargo_path, aoc_cargo_toml)?;
+    fs::write(&days_path, days_rs)?;
+    append_answers(day)?;
+
+    println!("Created day{}. Next:", day);
+    println!("  - paste the puzzle input into day{}/input.txt", day);
+    println!("  - paste the puzzle's example into day{}/examples/example.txt", day);
+    println!("  - implement day{}/src/lib.rs", day);
+    println!("  - fill in the expected answers in answers.toml");
+    Ok(())
+}
+
+// Inserts a line for the specified day into a list of lines with the same
+// format for other days, keeping the list in order by day. Returns an
+// error if the day is already in the list, or there is no list.
+fn insert_day_line<F>(text : &str, day : u32, format : F) -> Result<String, String>
+where F: Fn(u32) -> String {
+    let lines : Vec<&str> = text.lines().collect();
+
+    // Find the lines for other days by matching each line against the
+    // format for the day number in the line.
+    let mut insert_at = None;
+    let mut first = None;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(n) = day_number(line) {
+            if *line == format(n) {
+                if n == day {
+                    return Err(format!("day{} is already registered", day));
+                }
+                if first.is_none() {
+                    first = Some(i);
+                }
+                if n < day {
+                    insert_at = Some(i + 1);
+                }
+            }
+        }
+    }
+
+    let index = insert_at.or(first).ok_or_else(|| "no list of days found".to_string())?;
+    let new_line = format(day);
+    let mut result : Vec<&str> = lines[..index].to_vec();
+    result.push(&new_line);
+    result.extend_from_slice(&lines[index..]);
+    Ok(result.join("\n") + "\n")
+}
+
+// Returns the number following the first "day" in a line.
+fn day_number(line : &str) -> Option<u32> {
+    let i = line.find("day")? + 3;
+    let digits : String = line[i..].chars().take_while(|ch| ch.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+// Appends placeholder entries for a new day to the expected answers file.
+fn append_answers(day : u32) -> std::io::Result<()> {
+    let path = days::answers_path();
+    let mut text = read(&path)?;
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    for input in &[DEFAULT_INPUT_NAME, "example"] {
+        text.push_str(&format!("\n[day{}.{}]\n# part1 = \n# part2 = \n", day, input));
+    }
+    fs::write(&path, text)
+}
+
+fn read(path : &Path) -> std::io::Result<String> {
+    fs::read_to_string(path).map_err(|e| in_file(path, &e.to_string()))
+}
+
+fn in_file(path : &Path, message : &str) -> std::io::Error {
+    make_error(&format!("{}: {}", path.display(), message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace_line(n : u32) -> String {
+        format!("    \"day{}\",", n)
+    }
+
+    #[test]
+    fn inserts_in_order() {
+        let text = "[workspace]\nmembers = [\n    \"aoc\",\n    \"day1\",\n    \"day3\",\n]\n";
+        let result = insert_day_line(text, 2, workspace_line).unwrap();
+        assert_eq!(result, "[workspace]\nmembers = [\n    \"aoc\",\n    \"day1\",\n    \"day2\",\n    \"day3\",\n]\n");
+    }
+
+    #[test]
+    fn inserts_first_and_last() {
+        let text = "x\n    \"day2\",\n    \"day3\",\ny\n";
+        assert_eq!(insert_day_line(text, 1, workspace_line).unwrap(), "x\n    \"day1\",\n    \"day2\",\n    \"day3\",\ny\n");
+        assert_eq!(insert_day_line(text, 4, workspace_line).unwrap(), "x\n    \"day2\",\n    \"day3\",\n    \"day4\",\ny\n");
+    }
+
+    #[test]
+    fn compares_numbers_not_text() {
+        let text = "    \"day9\",\n    \"day10\",\n";
+        let result = insert_day_line(text, 11, workspace_line).unwrap();
+        assert_eq!(result, "    \"day9\",\n    \"day10\",\n    \"day11\",\n");
+    }
+
+    #[test]
+    fn ignores_lines_in_other_formats() {
+        // The dependency line mentions day1 but isn't in the list format.
+        let text = "day1 = { path = \"../day1\" }\n    &day1::Day1,\n";
+        let result = insert_day_line(text, 2, |n| format!("    &day{}::Day{},", n, n)).unwrap();
+        assert_eq!(result, "day1 = { path = \"../day1\" }\n    &day1::Day1,\n    &day2::Day2,\n");
+    }
+
+    #[test]
+    fn rejects_existing_day() {
+        let text = "    \"day1\",\n    \"day2\",\n";
+        assert_eq!(insert_day_line(text, 2, workspace_line), Err("day2 is already registered".to_string()));
+    }
+
+    #[test]
+    fn requires_a_list() {
+        assert_eq!(insert_day_line("[workspace]\n", 1, workspace_line), Err("no list of days found".to_string()));
+    }
+
+    #[test]
+    fn day_numbers() {
+        assert_eq!(day_number("    &day12::Day12,"), Some(12));
+        assert_eq!(day_number("aoc-common"), None);
+        assert_eq!(day_number("today"), None);
+    }
+}
@@ -0,0 +1,452 @@
+use std::io::{prelude::*, BufReader};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+use aoc_common::{Json, ParseMode, Diagnostics, Solver, day_to_json};
+use super::days;
+use super::parallel::{panic_message, spawn_worker};
+
+// Largest request body accepted, in bytes.
+const MAX_BODY_LEN : usize = 16 << 20;
+
+// Longest request line or header line accepted, in bytes, and most header
+// lines accepted.
+const MAX_LINE_LEN : usize = 8 << 10;
+const MAX_HEADER_COUNT : usize = 100;
+
+// Most connections handled at once. A solver that is still running after
+// its request timed out keeps its connection's place, as its thread can't
+// be stopped.
+const MAX_CONNECTIONS : usize = 64;
+
+// Time to wait for a client to send its request.
+const READ_TIMEOUT : Duration = Duration::from_secs(30);
+
+// Time to wait for a client to close the connection after an error.
+const DRAIN_TIMEOUT : Duration = Duration::from_secs(1);
+
+// Name of the input in responses and warnings.
+const INPUT_NAME : &str = "request";
+
+/// Serves requests on localhost at the specified port until the process
+/// is stopped. Each connection is handled on its own thread, and a solver
+/// that runs longer than the timeout is reported as failed.
+pub fn serve_command(port : u16, mode : ParseMode, timeout : Duration) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Listening on http://{}", listener.local_addr()?);
+    serve(listener, mode, timeout);
+    Ok(())
+}
+
+/// Accepts connections from the listener, and answers one request on each.
+///
+/// The only route is `POST /day/<n>/part/<p>`, where the body is the input.
+/// The response is the JSON object for the day in the `--format json`
+/// output (see `JSON_SCHEMA_VERSION`), with status 200 if the part was
+/// solved, or 422 if the input couldn't be parsed or the part failed.
+/// Other errors are an object with "status" and "error" members: 422 if
+/// the solver panicked, and 503 if it took longer than the timeout or
+/// there are already `MAX_CONNECTIONS` connections.
+pub fn serve(listener : TcpListener, mode : ParseMode, timeout : Duration) {
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        match stream {
+            Ok(mut stream) => {
+                if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    aoc_common::warn!("refused a connection: too many connections");
+                    let _ = write_response(&mut stream, &Response::error(503, "The server is busy. Try again later."));
+                    continue;
+                }
+                let slot = Arc::new(Slot(Arc::clone(&active)));
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, mode, timeout, &slot) {
+                        aoc_common::info!("connection failed: {}", e);
+                    }
+                });
+            },
+            Err(e) => aoc_common::warn!("accepting a connection failed: {}", e)
+        }
+    }
+}
+
+// A connection's place among the MAX_CONNECTIONS, shared by the threads
+// serving it, and given up when the last of them drops it.
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// An HTTP request, as far as the server cares.
+struct Request {
+    method : String,
+    path : String,
+    body : Vec<u8>
+}
+
+// A response with a JSON body.
+struct Response {
+    status : u16,
+    body : Json
+}
+
+impl Response {
+    fn error(status : u16, message : &str) -> Response {
+        let body = Json::object()
+            .with("status", u32::from(status).into())
+            .with("error", message.into());
+        Response{ status, body }
+    }
+}
+
+fn handle_connection(stream : TcpStream, mode : ParseMode, timeout : Duration, slot : &Arc<Slot>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    match read_request(&mut reader, &mut writer)? {
+        Ok(request) => {
+            aoc_common::debug!("{} {} ({} bytes)", request.method, request.path, request.body.len());
+            write_response(&mut writer, &route(&request, mode, timeout, slot))
+        },
+        Err(response) => {
+            write_response(&mut writer, &response)?;
+
+            // Closing the connection with part of the request unread would
+            // reset it, and the client could lose the response, so read
+            // (a limited amount of) the rest first. The response has been
+            // sent, so errors no longer matter.
+            writer.shutdown(Shutdown::Write)?;
+            reader.get_ref().set_read_timeout(Some(DRAIN_TIMEOUT))?;
+            let _ = std::io::copy(&mut reader.take(MAX_BODY_LEN as u64), &mut std::io::sink());
+            Ok(())
+        }
+    }
+}
+
+// Reads a request. Returns an error response if the request is malformed,
+// or an I/O error if the connection fails.
+fn read_request<R : BufRead, W : Write>(reader : &mut R, writer : &mut W) -> std::io::Result<Result<Request, Response>> {
+    // The request line is "<method> <path> <version>".
+    let line = match read_head_line(reader)? {
+        Some(line) => line,
+        None => { return Ok(Err(Response::error(414, &format!("The request line must be at most {} bytes.", MAX_LINE_LEN)))); }
+    };
+    let mut words = line.split_whitespace();
+    let (method, path) = match (words.next(), words.next(), words.next()) {
+        (Some(method), Some(path), Some(version)) if version.starts_with("HTTP/") => (method, path),
+        _ => { return Ok(Err(Response::error(400, "Malformed request line."))); }
+    };
+    let request = Request{ method : method.to_string(), path : path.to_string(), body : Vec::new() };
+
+    // Headers end with a blank line.
+    let mut content_length = None;
+    let mut expect_continue = false;
+    let mut header_count = 0;
+    loop {
+        let line = match read_head_line(reader)? {
+            Some(line) if line.is_empty() => { return Ok(Err(Response::error(400, "Incomplete request headers."))); },
+            Some(line) => line,
+            None => { return Ok(Err(Response::error(431, &format!("Header lines must be at most {} bytes.", MAX_LINE_LEN)))); }
+        };
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        header_count += 1;
+        if header_count > MAX_HEADER_COUNT {
+            return Ok(Err(Response::error(431, &format!("There must be at most {} headers.", MAX_HEADER_COUNT))));
+        }
+        let (name, value) = match line.find(':') {
+            Some(i) => (line[..i].trim().to_ascii_lowercase(), line[i + 1..].trim()),
+            None => { return Ok(Err(Response::error(400, "Malformed request header."))); }
+        };
+        match name.as_str() {
+            "content-length" => {
+                match value.parse::<usize>() {
+                    Ok(n) => { content_length = Some(n); },
+                    Err(_) => { return Ok(Err(Response::error(400, "Invalid Content-Length."))); }
+                }
+            },
+            "expect" => { expect_continue = value.eq_ignore_ascii_case("100-continue"); },
+            "transfer-encoding" => {
+                return Ok(Err(Response::error(501, "Transfer encodings are not supported.")));
+            },
+            _ => {}
+        }
+    }
+
+    if request.method != "POST" {
+        return Ok(Ok(request));
+    }
+    let len = match content_length {
+        Some(len) if len > MAX_BODY_LEN => {
+            return Ok(Err(Response::error(413, &format!("The input must be at most {} bytes.", MAX_BODY_LEN))));
+        },
+        Some(len) => len,
+        None => { return Ok(Err(Response::error(411, "Content-Length is required."))); }
+    };
+
+    // Clients such as curl wait for this before sending a large body.
+    if expect_continue {
+        writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+        writer.flush()?;
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    Ok(Ok(Request{ body, ..request }))
+}
+
+// Reads a line of the request line or headers, including its line ending.
+// Returns an empty line at the end of the input, or None if the line is
+// longer than MAX_LINE_LEN.
+fn read_head_line<R : BufRead>(reader : &mut R) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    let len = reader.by_ref().take(MAX_LINE_LEN as u64).read_line(&mut line)?;
+    if len == MAX_LINE_LEN && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+// Returns the response to a well-formed request.
+fn route(request : &Request, mode : ParseMode, timeout : Duration, slot : &Arc<Slot>) -> Response {
+    // The only route is /day/<n>/part/<p>.
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments : Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    let (day, part) = match segments.as_slice() {
+        ["day", day, "part", part] => (day.parse::<u32>().ok(), part.parse::<u32>().ok()),
+        _ => { return Response::error(404, "Not found. Use POST /day/<n>/part/<p>."); }
+    };
+    if request.method != "POST" {
+        return Response::error(405, "Use POST, with the input as the body.");
+    }
+    let solver = match day.and_then(days::day_slice) {
+        Some(solvers) => solvers[0],
+        None => { return Response::error(404, &format!("Day {} is not implemented.", segments[1])); }
+    };
+    let part = match part {
+        Some(part) if part == 1 || part == 2 => part,
+        _ => { return Response::error(404, "The part must be 1 or 2."); }
+    };
+    let text = match std::str::from_utf8(&request.body) {
+        Ok(text) => text,
+        Err(_) => { return Response::error(400, "The input must be UTF-8 text."); }
+    };
+    let lines : Vec<String> = text.lines().map(|line| line.to_string()).collect();
+    solve(solver, lines, part, mode, timeout, slot)
+}
+
+// Solves a part of a day on a worker thread, and returns the response. The
+// worker keeps the connection's slot until it finishes, even if it takes
+// longer than the timeout and the response has already been sent.
+fn solve(solver : &'static dyn Solver, lines : Vec<String>, part : u32, mode : ParseMode, timeout : Duration, slot : &Arc<Slot>) -> Response {
+    let (sender, receiver) = mpsc::channel();
+    let slot = Arc::clone(slot);
+    spawn_worker(solver.day(), move || {
+        let _slot = slot;
+        let mut diag = Diagnostics::new(mode, Some(INPUT_NAME));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| solver.run_detailed(&lines, Some(part), &mut diag)));
+        let _ = sender.send(result.map(|result| (result, diag)).map_err(|payload| panic_message(payload.as_ref())));
+    });
+
+    // A panic is caused by the input, as the solvers work on valid inputs,
+    // so it is reported like any other error, without stopping the server.
+    let (result, diag) = match receiver.recv_timeout(timeout) {
+        Ok(Ok(solved)) => solved,
+        Ok(Err(message)) => {
+            aoc_common::warn!("day {} part {} panicked: {}", solver.day(), part, message);
+            return Response::error(422, &format!("The solver panicked: {}", message));
+        },
+        Err(RecvTimeoutError::Timeout) => {
+            aoc_common::warn!("day {} part {} timed out", solver.day(), part);
+            return Response::error(503, &format!("The solver didn't finish within {} s.", timeout.as_secs_f64()));
+        },
+        Err(RecvTimeoutError::Disconnected) => {
+            return Response::error(500, "The solver stopped without a result.");
+        }
+    };
+
+    let solved = match &result {
+        Ok(result) => result.parts.iter().all(|part| part.answer.is_ok()),
+        Err(_) => false
+    };
+    let body = day_to_json(solver.day(), INPUT_NAME, &result, &diag);
+    Response{ status : if solved { 200 } else { 422 }, body }
+}
+
+fn write_response<W : Write>(writer : &mut W, response : &Response) -> std::io::Result<()> {
+    let body = response.body.to_string();
+    write!(writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status, reason_phrase(response.status), body.len(), body
+    )?;
+    writer.flush()
+}
+
+fn reason_phrase(status : u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        501 => "Not Implemented",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use aoc_common::{Answer, ParseError, Solution};
+
+    const TIMEOUT : Duration = Duration::from_secs(60);
+
+    // Starts a server on a free port, and returns its address.
+    fn start_server(mode : ParseMode) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, mode, TIMEOUT));
+        addr
+    }
+
+    // Sends a request, and returns the status and body of the response.
+    fn send(addr : SocketAddr, request : &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        (status, body.to_string())
+    }
+
+    fn post(addr : SocketAddr, path : &str, body : &str) -> (u16, String) {
+        send(addr, &format!("POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", path, body.len(), body))
+    }
+
+    const DAY1_EXAMPLE : &str = "1721\n979\n366\n299\n675\n1456\n";
+
+    #[test]
+    fn solves_posted_input() {
+        let addr = start_server(ParseMode::Lenient);
+        let (status, body) = post(addr, "/day/1/part/1", DAY1_EXAMPLE);
+        assert_eq!(status, 200);
+        assert!(body.contains("\"answer\":514579"), "{}", body);
+        assert!(body.contains("\"time_ns\":"), "{}", body);
+        assert!(!body.contains("\"part\":2"), "{}", body);
+
+        let (status, body) = post(addr, "/day/1/part/2", DAY1_EXAMPLE);
+        assert_eq!(status, 200);
+        assert!(body.contains("\"answer\":241861950"), "{}", body);
+
+        // Malformed lines are warnings, unless the server is strict.
+        let (status, body) = post(addr, "/day/1/part/1", "1721\nx\n299\n");
+        assert_eq!(status, 200);
+        assert!(body.contains("\"line\":2"), "{}", body);
+        let (status, body) = post(start_server(ParseMode::Strict), "/day/1/part/1", "1721\nx\n299\n");
+        assert_eq!(status, 422);
+        assert!(body.contains("request:2:1"), "{}", body);
+    }
+
+    #[test]
+    fn reports_bad_requests() {
+        let addr = start_server(ParseMode::Lenient);
+
+        // An input without a solution.
+        let (status, body) = post(addr, "/day/1/part/1", "1\n2\n");
+        assert_eq!(status, 422);
+        assert!(body.contains("\"answer\":null"), "{}", body);
+
+        let (status, body) = post(addr, "/day/99/part/1", "");
+        assert_eq!((status, body.contains("\"status\":404")), (404, true), "{}", body);
+        assert_eq!(post(addr, "/day/1/part/3", "").0, 404);
+        assert_eq!(post(addr, "/days", "").0, 404);
+        assert_eq!(send(addr, "GET /day/1/part/1 HTTP/1.1\r\n\r\n").0, 405);
+        assert_eq!(send(addr, "POST /day/1/part/1 HTTP/1.1\r\n\r\n").0, 411);
+        assert_eq!(send(addr, "nonsense\r\n\r\n").0, 400);
+        assert_eq!(send(addr, &format!("POST /day/1/part/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_LEN + 1)).0, 413);
+
+        // Overly long or many header lines.
+        assert_eq!(send(addr, &format!("POST /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_LEN))).0, 414);
+        assert_eq!(send(addr, &format!("POST /day/1/part/1 HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(MAX_LINE_LEN))).0, 431);
+        assert_eq!(send(addr, &format!("POST /day/1/part/1 HTTP/1.1\r\n{}\r\n", "X: a\r\n".repeat(MAX_HEADER_COUNT + 1))).0, 431);
+
+        // The server still works.
+        assert_eq!(post(addr, "/day/1/part/1", DAY1_EXAMPLE).0, 200);
+    }
+
+    // Solutions that panic, or take a while.
+    struct Panics;
+    struct Sleeps;
+
+    impl Solution for Panics {
+        type Input = ();
+        const DAY: u32 = 1;
+
+        fn parse(&self, _lines: &[String], _diag: &mut Diagnostics) -> Result<(), ParseError> {
+            Ok(())
+        }
+        fn part1(&self, _input: &()) -> std::io::Result<Answer> {
+            panic!("bad input")
+        }
+        fn part2(&self, _input: &()) -> std::io::Result<Answer> {
+            panic!("bad input")
+        }
+    }
+
+    impl Solution for Sleeps {
+        type Input = ();
+        const DAY: u32 = 2;
+
+        fn parse(&self, _lines: &[String], _diag: &mut Diagnostics) -> Result<(), ParseError> {
+            Ok(())
+        }
+        fn part1(&self, _input: &()) -> std::io::Result<Answer> {
+            thread::sleep(Duration::from_millis(500));
+            Ok(Answer::from(1))
+        }
+        fn part2(&self, _input: &()) -> std::io::Result<Answer> {
+            Ok(Answer::from(2))
+        }
+    }
+
+    static PANICS : &dyn Solver = &Panics;
+    static SLEEPS : &dyn Solver = &Sleeps;
+
+    #[test]
+    fn reports_panics_and_timeouts() {
+        let active = Arc::new(AtomicUsize::new(1));
+        let slot = Arc::new(Slot(Arc::clone(&active)));
+
+        let response = solve(PANICS, Vec::new(), 1, ParseMode::Lenient, TIMEOUT, &slot);
+        assert_eq!(response.status, 422);
+        assert!(response.body.to_string().contains("bad input"), "{}", response.body);
+
+        let response = solve(SLEEPS, Vec::new(), 2, ParseMode::Lenient, Duration::from_millis(100), &slot);
+        assert_eq!(response.status, 200);
+
+        // The solver keeps the connection's place until it finishes.
+        let response = solve(SLEEPS, Vec::new(), 1, ParseMode::Lenient, Duration::from_millis(100), &slot);
+        assert_eq!(response.status, 503);
+        drop(slot);
+        assert_eq!(active.load(Ordering::SeqCst), 1);
+        thread::sleep(Duration::from_secs(1));
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,268 @@
+use std::fs;
+use std::path::Path;
+use aoc_common::{Solver, InputSource, ParseMode, Diagnostics, DEFAULT_INPUT_NAME, list_examples, make_error};
+use super::days;
+
+// Name of the directory in each day's crate with its snapshots.
+const SNAPSHOTS_DIR : &str = "snapshots";
+
+// Maximum number of diff lines printed for a changed snapshot.
+const MAX_DIFF_LINES : usize = 20;
+
+// Number of snapshots with each status.
+#[derive(Default)]
+struct Totals {
+    passed : u32,
+    changed : u32,
+    added : u32,
+    removed : u32
+}
+
+/// Implements the "snapshot" command, which runs each day on its input and
+/// examples, and compares the full output with the snapshots in the day's
+/// `snapshots` directory. The output of each run is the answers, warnings
+/// and details, and each of the day's artifacts (such as the transitions
+/// of day 19) is a separate snapshot. Timings aren't included, so the
+/// output is the same on every run.
+///
+/// With bless, changed and new snapshots are written, and snapshots for
+/// inputs that no longer exist are deleted. Otherwise, any difference is
+/// printed and the command fails.
+pub fn snapshot_command(solvers : &[&dyn Solver], bless : bool) -> std::io::Result<()> {
+    let mut totals = Totals::default();
+    for &solver in solvers {
+        check_day(solver, bless, &mut totals)?;
+    }
+
+    println!(
+        "{} passed, {} changed, {} new, {} stale{}.",
+        totals.passed, totals.changed, totals.added, totals.removed,
+        if bless { " (updated)" } else { "" }
+    );
+    if !bless && totals.changed + totals.added + totals.removed != 0 {
+        return Err(make_error("Snapshots differ. Run with --bless to update them."));
+    }
+    Ok(())
+}
+
+// Compares the snapshots for one day, or updates them if bless is true.
+fn check_day(solver : &dyn Solver, bless : bool, totals : &mut Totals) -> std::io::Result<()> {
+    let day = solver.day();
+    let dir = days::day_dir(day);
+    let snapshot_dir = dir.join(SNAPSHOTS_DIR);
+
+    let mut outputs = snapshot_day(solver, &dir);
+    outputs.sort();
+    for (name, actual) in &outputs {
+        let path = snapshot_dir.join(name);
+        let label = format!("Day {} {}", day, name);
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == *actual => { totals.passed += 1; },
+            Ok(expected) => {
+                totals.changed += 1;
+                println!("{}: changed", label);
+                let diff = diff_lines(&expected, actual);
+                for line in diff.iter().take(MAX_DIFF_LINES) {
+                    println!("    {}", line);
+                }
+                if diff.len() > MAX_DIFF_LINES {
+                    println!("    ... and {} more", diff.len() - MAX_DIFF_LINES);
+                }
+            },
+            Err(_) => {
+                totals.added += 1;
+                println!("{}: new", label);
+            }
+        }
+        if bless {
+            fs::create_dir_all(&snapshot_dir)?;
+            fs::write(&path, actual)?;
+        }
+    }
+
+    // Snapshots that weren't produced are for inputs that were removed.
+    if let Ok(entries) = fs::read_dir(&snapshot_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !outputs.iter().any(|(output, _)| *output == name) {
+                totals.removed += 1;
+                println!("Day {} {}: stale", day, name);
+                if bless {
+                    fs::remove_file(entry.path())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs a day on its input and each of its examples, and returns the
+/// snapshot file names and contents. The snapshot of the day's input is
+/// named `input.txt`, and the snapshot of an example has the example's
+/// name. Artifacts are named `<input>.<artifact>.txt`.
+pub fn snapshot_day(solver : &dyn Solver, dir : &Path) -> Vec<(String, String)> {
+    let mut outputs = Vec::new();
+    for (name, source) in snapshot_inputs(dir) {
+        let lines = source.read_lines(dir, solver.input_file());
+        let input_name = source.name(solver.input_file());
+        outputs.push((format!("{}.txt", name), render_run(solver, &lines, None, &input_name)));
+
+        let mut diag = Diagnostics::new(ParseMode::Lenient, Some(&input_name));
+        let artifacts = lines.and_then(|lines| solver.artifacts(&lines, &mut diag));
+        for (artifact, text) in artifacts.unwrap_or_default() {
+            outputs.push((format!("{}.{}.txt", name, artifact), text));
+        }
+    }
+    outputs
+}
+
+// Returns the names of the inputs with snapshots, and their sources: the
+// day's input, then each of its examples.
+fn snapshot_inputs(dir : &Path) -> Vec<(String, InputSource)> {
+    let mut inputs = vec![(DEFAULT_INPUT_NAME.to_string(), InputSource::Default)];
+    for name in list_examples(dir) {
+        inputs.push((name.clone(), InputSource::Example(name)));
+    }
+    inputs
+}
+
+/// Returns the output of running a day (or only the specified part) on the
+/// lines of an input, without timings: each part's answer or error, then
+/// any warnings and details.
+pub fn render_run(solver : &dyn Solver, lines : &std::io::Result<Vec<String>>, part : Option<u32>, input_name : &str) -> String {
+    let mut diag = Diagnostics::new(ParseMode::Lenient, Some(input_name));
+    let result = match lines {
+        Ok(lines) => solver.run_detailed(lines, part, &mut diag),
+        Err(e) => Err(make_error(&e.to_string()))
+    };
+
+    let mut text = String::new();
+    match result {
+        Ok(result) => {
+            for part in &result.parts {
+                match &part.answer {
+                    Ok(answer) => text.push_str(&format!("Part {}: {}\n", part.part, answer)),
+                    Err(e) => text.push_str(&format!("Part {}: error: {}\n", part.part, e))
+                }
+            }
+            if let Some(details) = &result.details {
+                text.push_str(&format!("details: {}\n", details));
+            }
+        },
+        Err(e) => text.push_str(&format!("error: {}\n", e))
+    }
+    for e in diag.errors() {
+        text.push_str(&format!("warning: {}\n", e));
+    }
+    text
+}
+
+/// Returns the differences between two texts as lines prefixed by "-" for
+/// expected lines that are missing, and "+" for unexpected lines, based on
+/// a longest common subsequence of lines.
+pub fn diff_lines(expected : &str, actual : &str) -> Vec<String> {
+    let a : Vec<&str> = expected.lines().collect();
+    let b : Vec<&str> = actual.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of a[i..]
+    // and b[j..].
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        }
+        else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("-{}", a[i]));
+            i += 1;
+        }
+        else {
+            diff.push(format!("+{}", b[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_lines() {
+        assert!(diff_lines("a\nb\nc\n", "a\nb\nc\n").is_empty());
+        assert_eq!(diff_lines("a\nb\nc\n", "a\nx\nc\nd\n"), vec!["-b", "+x", "+d"]);
+        assert_eq!(diff_lines("a\nb\n", ""), vec!["-a", "-b"]);
+    }
+
+    #[test]
+    fn renders_runs() {
+        let solver = days::day_slice(1).unwrap()[0];
+        let lines = Ok(vec!["1721".to_string(), "x".to_string(), "299".to_string()]);
+        assert_eq!(
+            render_run(solver, &lines, None, "test.txt"),
+            "Part 1: 514579\nPart 2: error: No triple found that sums to 2020.\nwarning: test.txt:2:1: expected a number, found 'x'\n"
+        );
+        let lines = Err(make_error("missing"));
+        assert_eq!(render_run(solver, &lines, None, "test.txt"), "error: missing\n");
+        assert_eq!(render_run(solver, &lines, Some(1), "test.txt"), "error: missing\n");
+    }
+
+    // Parts that take too long in a debug build to run in every test: the
+    // 30 million turns of day 15, and the four-dimensional cubes of day 17.
+    const SLOW_PARTS : &[(u32, u32)] = &[(15, 2), (17, 2)];
+
+    fn slow_part(day : u32) -> Option<u32> {
+        SLOW_PARTS.iter().find(|&&(d, _)| d == day).map(|&(_, part)| part)
+    }
+
+    // Compares every day's snapshots, except that the slow parts are left
+    // out of the comparison (see slow_snapshots_match).
+    #[test]
+    fn snapshots_match() {
+        let mut totals = Totals::default();
+        for &solver in days::DAYS {
+            match slow_part(solver.day()) {
+                None => check_day(solver, false, &mut totals).unwrap(),
+                Some(slow) => {
+                    // Run the other part, and compare its output with the
+                    // snapshot without the slow part's answer.
+                    let dir = days::day_dir(solver.day());
+                    let other = 3 - slow;
+                    for (name, source) in snapshot_inputs(&dir) {
+                        let path = dir.join(SNAPSHOTS_DIR).join(format!("{}.txt", name));
+                        let expected : String = fs::read_to_string(&path).unwrap().lines()
+                            .filter(|line| !line.starts_with(&format!("Part {}:", slow)))
+                            .map(|line| format!("{}\n", line))
+                            .collect();
+                        let lines = source.read_lines(&dir, solver.input_file());
+                        let actual = render_run(solver, &lines, Some(other), &source.name(solver.input_file()));
+                        assert_eq!(actual, expected, "day {} {}: run aoc snapshot for details", solver.day(), name);
+                    }
+                }
+            }
+        }
+        assert_eq!((totals.changed, totals.added, totals.removed), (0, 0, 0), "run aoc snapshot for details");
+    }
+
+    // Compares the full snapshots of the days with slow parts, which takes
+    // a while in a debug build, so use:
+    // cargo test --release -p aoc -- --ignored
+    #[test]
+    #[ignore]
+    fn slow_snapshots_match() {
+        let mut totals = Totals::default();
+        for &(day, _) in SLOW_PARTS {
+            check_day(days::day_slice(day).unwrap()[0], false, &mut totals).unwrap();
+        }
+        assert_eq!((totals.changed, totals.added, totals.removed), (0, 0, 0), "run aoc snapshot for details");
+    }
+}
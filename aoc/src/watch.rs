@@ -0,0 +1,202 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use aoc_common::{Solver, Diagnostics, RunResult, print_diagnostics, make_error};
+use super::bench::format_duration;
+use super::days;
+use super::options::RunOptions;
+
+/// The results of one run of a day, to compare with the next run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    /// Error reading or parsing the input, if any.
+    error : Option<String>,
+
+    /// The part number, its answer or error, and the time it took.
+    parts : Vec<(u32, Result<String, String>, Duration)>,
+
+    warning_count : usize
+}
+
+impl Snapshot {
+    fn new(result : &std::io::Result<RunResult>, diag : &Diagnostics) -> Snapshot {
+        let warning_count = diag.errors().len();
+        match result {
+            Ok(result) => {
+                let parts = result.parts.iter().map(|part| {
+                    let answer = part.answer.as_ref().map(|a| a.to_string()).map_err(|e| e.to_string());
+                    (part.part, answer, part.elapsed)
+                }).collect();
+                Snapshot{ error : None, parts, warning_count }
+            },
+            Err(e) => Snapshot{ error : Some(e.to_string()), parts : Vec::new(), warning_count }
+        }
+    }
+}
+
+/// Runs the day selected by options, then polls its input file every
+/// interval, and runs it again whenever the file changes. After each run,
+/// prints how the answers and timings differ from the previous run. Also
+/// notes when the day's source changes, as that needs a rebuild. Runs
+/// until the process is stopped.
+pub fn watch_command(options : &RunOptions, interval : Duration) -> std::io::Result<()> {
+    if options.solvers.len() != 1 {
+        return Err(make_error("Only one day can be watched."));
+    }
+    let solver = options.solvers[0];
+    let dir = days::day_dir(solver.day());
+    let input_path = options.source.path(&dir, solver.input_file()).ok_or_else(
+        || make_error("Standard input can't be watched.")
+    )?;
+    let source_dir = dir.join("src");
+
+    println!("Watching {} (Ctrl+C to stop).", input_path.display());
+    let mut prev : Option<Snapshot> = None;
+    let mut input_stamp = None;
+    let mut source_stamp = newest_modified(&source_dir);
+    loop {
+        let stamp = file_stamp(&input_path);
+        if prev.is_none() || stamp != input_stamp {
+            input_stamp = stamp;
+            if prev.is_some() {
+                println!("{} changed:", input_path.display());
+            }
+            let next = run_once(solver, options);
+            for line in diff_snapshots(prev.as_ref(), &next) {
+                println!("  {}", line);
+            }
+            prev = Some(next);
+        }
+
+        let stamp = newest_modified(&source_dir);
+        if stamp != source_stamp {
+            source_stamp = stamp;
+            println!("The source of day {} changed. Rebuild and restart to use it.", solver.day());
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn run_once(solver : &dyn Solver, options : &RunOptions) -> Snapshot {
+    let name = options.source.name(solver.input_file());
+    let mut diag = Diagnostics::new(options.mode, Some(&name));
+    let result = options.source.read_lines(&days::day_dir(solver.day()), solver.input_file())
+        .and_then(|lines| solver.run(&lines, options.part, &mut diag));
+    print_diagnostics(&diag);
+    Snapshot::new(&result, &diag)
+}
+
+// Returns the modification time and length of a file, or None if it
+// doesn't exist.
+fn file_stamp(path : &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+// Returns the latest modification time of the files in a directory tree,
+// along with the number of files, so deleting a file is also a change.
+fn newest_modified(dir : &Path) -> Option<(SystemTime, usize)> {
+    let mut newest = None;
+    let mut count = 0;
+    let mut dirs : Vec<PathBuf> = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).ok()?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            }
+            else if let Some((modified, _)) = file_stamp(&path) {
+                count += 1;
+                newest = newest.max(Some(modified));
+            }
+        }
+    }
+    newest.map(|newest| (newest, count))
+}
+
+/// Returns lines describing how the next run differs from the previous
+/// one, or all of the next run if there is no previous one. Parts whose
+/// answers haven't changed are listed with their change in time.
+pub fn diff_snapshots(prev : Option<&Snapshot>, next : &Snapshot) -> Vec<String> {
+    let mut lines = Vec::new();
+    let prev_error = prev.and_then(|prev| prev.error.as_ref());
+    match (&next.error, prev_error) {
+        (Some(e), Some(prev_e)) if e == prev_e => lines.push("error: unchanged".to_string()),
+        (Some(e), _) => lines.push(format!("error: {}", e)),
+        (None, Some(_)) => lines.push("error: fixed".to_string()),
+        (None, None) => {}
+    }
+
+    for (part, answer, elapsed) in &next.parts {
+        let prev_part = prev.and_then(|prev| prev.parts.iter().find(|(p, _, _)| p == part));
+        let answer_text = |answer : &Result<String, String>| match answer {
+            Ok(answer) => answer.clone(),
+            Err(e) => format!("error: {}", e)
+        };
+        let line = match prev_part {
+            None => format!("Part {}: {} ({})", part, answer_text(answer), format_duration(*elapsed)),
+            Some((_, prev_answer, prev_elapsed)) => {
+                let time = format!("{} -> {}{}", format_duration(*prev_elapsed), format_duration(*elapsed), percent_change(*prev_elapsed, *elapsed));
+                if prev_answer == answer {
+                    format!("Part {}: {} (unchanged), {}", part, answer_text(answer), time)
+                }
+                else {
+                    format!("Part {}: {} -> {}, {}", part, answer_text(prev_answer), answer_text(answer), time)
+                }
+            }
+        };
+        lines.push(line);
+    }
+
+    let prev_warnings = prev.map_or(0, |prev| prev.warning_count);
+    if next.warning_count != prev_warnings {
+        lines.push(format!("Warnings: {} -> {}", prev_warnings, next.warning_count));
+    }
+    lines
+}
+
+// Formats the relative change from one time to another, such as " (-25%)".
+fn percent_change(prev : Duration, next : Duration) -> String {
+    if prev.as_nanos() == 0 {
+        return String::new();
+    }
+    let change = (next.as_secs_f64() / prev.as_secs_f64() - 1.0) * 100.0;
+    format!(" ({:+.0}%)", change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(answers : &[Result<&str, &str>], ms : u64) -> Snapshot {
+        let parts = answers.iter().enumerate().map(|(i, answer)| {
+            let answer = answer.map(|s| s.to_string()).map_err(|e| e.to_string());
+            (i as u32 + 1, answer, Duration::from_millis(ms))
+        }).collect();
+        Snapshot{ error : None, parts, warning_count : 0 }
+    }
+
+    #[test]
+    fn diffs_answers_and_timings() {
+        let first = snapshot(&[Ok("514579"), Ok("241861950")], 4);
+        assert_eq!(diff_snapshots(None, &first), vec!["Part 1: 514579 (4.0 ms)", "Part 2: 241861950 (4.0 ms)"]);
+
+        let second = snapshot(&[Ok("514579"), Err("no solution")], 2);
+        assert_eq!(diff_snapshots(Some(&first), &second), vec![
+            "Part 1: 514579 (unchanged), 4.0 ms -> 2.0 ms (-50%)",
+            "Part 2: 241861950 -> error: no solution, 4.0 ms -> 2.0 ms (-50%)"
+        ]);
+    }
+
+    #[test]
+    fn diffs_errors_and_warnings() {
+        let good = snapshot(&[Ok("1")], 1);
+        let bad = Snapshot{ error : Some("input:1:1: expected a number, found 'x'".to_string()), parts : Vec::new(), warning_count : 0 };
+        assert_eq!(diff_snapshots(Some(&good), &bad), vec!["error: input:1:1: expected a number, found 'x'"]);
+        assert_eq!(diff_snapshots(Some(&bad), &bad), vec!["error: unchanged"]);
+
+        let warned = Snapshot{ warning_count : 2, ..good.clone() };
+        assert_eq!(diff_snapshots(Some(&bad), &warned), vec!["error: fixed", "Part 1: 1 (1.0 ms)", "Warnings: 0 -> 2"]);
+    }
+}
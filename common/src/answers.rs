@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use super::{Answer, ParseError, parse_field, read_file};
+
+/// Name of the input in the answers file for a day's checked-in input file.
+/// Other inputs are named examples.
+pub const DEFAULT_INPUT_NAME : &str = "input";
+
+/// Expected answers, keyed by day, input name and part.
+///
+/// The answers are stored in a subset of TOML, with one table per day and
+/// input, and a key per part, e.g.:
+///
+/// ```toml
+/// [day1.input]
+/// part1 = 299299
+/// part2 = 287730716
+///
+/// [day1.example]
+/// part1 = 514579
+/// ```
+///
+/// Values are integers or double-quoted strings (with no escapes). A part
+/// with no key has no expected answer, such as a part that is not
+/// implemented.
+#[derive(Default)]
+pub struct ExpectedAnswers {
+    entries : BTreeMap<(u32, String), [Option<Answer>; 2]>
+}
+
+impl ExpectedAnswers {
+    pub fn new() -> ExpectedAnswers {
+        ExpectedAnswers{ entries : BTreeMap::new() }
+    }
+
+    /// Reads an answers file.
+    pub fn load(path: &str) -> std::io::Result<ExpectedAnswers> {
+        let lines = read_file(path)?;
+        ExpectedAnswers::parse(&lines).map_err(|mut e| {
+            e.file = Some(path.to_string());
+            e.into()
+        })
+    }
+
+    /// Parses the lines of an answers file.
+    pub fn parse(lines: &[String]) -> Result<ExpectedAnswers, ParseError> {
+        let mut answers = ExpectedAnswers::new();
+        let mut key = None;
+        for (i, line) in lines.iter().enumerate() {
+            let s = strip_comment(line).trim();
+            if s.is_empty() {
+                continue;
+            }
+            if s.starts_with('[') {
+                key = Some(parse_table_header(line, s).map_err(|e| e.at_line(i + 1))?);
+                continue;
+            }
+            let (day, input) = key.clone().ok_or_else(
+                || ParseError::at_field(line, s, "a table header").at_line(i + 1)
+            )?;
+            let (part, answer) = parse_key_value(line, s).map_err(|e| e.at_line(i + 1))?;
+            answers.set(day, &input, part, answer);
+        }
+        Ok(answers)
+    }
+
+    /// Returns the expected answer for a part, if any.
+    pub fn get(&self, day: u32, input: &str, part: u32) -> Option<&Answer> {
+        let parts = self.entries.get(&(day, input.to_string()))?;
+        parts.get(part.checked_sub(1)? as usize)?.as_ref()
+    }
+
+    /// Sets the expected answer for a part, which must be 1 or 2.
+    pub fn set(&mut self, day: u32, input: &str, part: u32, answer: Answer) {
+        let parts = self.entries.entry((day, input.to_string())).or_default();
+        parts[(part - 1) as usize] = Some(answer);
+    }
+
+    /// Returns the names of the inputs with expected answers for a day.
+    pub fn inputs(&self, day: u32) -> Vec<&str> {
+        self.entries.keys()
+            .filter(|(d, _)| *d == day)
+            .map(|(_, input)| input.as_str())
+            .collect()
+    }
+}
+
+// Removes a comment that starts with '#' outside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' => { in_string = !in_string; },
+            '#' if !in_string => { return &line[..i]; },
+            _ => {}
+        }
+    }
+    line
+}
+
+// Parses a table header of the form "[day<n>.<input>]".
+fn parse_table_header(line: &str, s: &str) -> Result<(u32, String), ParseError> {
+    let inner = s[1..].strip_suffix(']').ok_or_else(
+        || ParseError::end_of_line(line, "']'")
+    )?;
+    let i = inner.find('.').ok_or_else(
+        || ParseError::at_field(line, inner, "day<n>.<input>")
+    )?;
+    let day_field = inner[..i].trim();
+    let day = match day_field.strip_prefix("day") {
+        Some(n) => parse_field::<u32>(line, n, "a day number")?,
+        None => { return Err(ParseError::at_field(line, day_field, "'day'")); }
+    };
+    let input = inner[i + 1..].trim();
+    if input.is_empty() || !input.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-') {
+        return Err(ParseError::at_field(line, &inner[i + 1..], "an input name"));
+    }
+    Ok((day, input.to_string()))
+}
+
+// Parses a key-value pair of the form "part<n> = <value>".
+fn parse_key_value(line: &str, s: &str) -> Result<(u32, Answer), ParseError> {
+    let i = s.find('=').ok_or_else(|| ParseError::end_of_line(line, "'='"))?;
+    let key = s[..i].trim();
+    let part = match key {
+        "part1" => 1,
+        "part2" => 2,
+        _ => { return Err(ParseError::at_field(line, key, "'part1' or 'part2'")); }
+    };
+    let value = s[i + 1..].trim();
+    let answer = if let Some(quoted) = value.strip_prefix('"') {
+        let text = quoted.strip_suffix('"').ok_or_else(
+            || ParseError::end_of_line(line, "'\"'")
+        )?;
+        Answer::Text(text.to_string())
+    }
+    else {
+        Answer::Number(parse_field::<i64>(line, value, "an integer or string")?)
+    };
+    Ok((part, answer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<ExpectedAnswers, ParseError> {
+        let lines : Vec<String> = text.lines().map(|s| s.to_string()).collect();
+        ExpectedAnswers::parse(&lines)
+    }
+
+    #[test]
+    fn strip_comments() {
+        assert_eq!(strip_comment("part1 = 5 # comment"), "part1 = 5 ");
+        assert_eq!(strip_comment("part1 = \"#1\" # comment"), "part1 = \"#1\" ");
+        assert_eq!(strip_comment("# part1 = "), "");
+        assert_eq!(strip_comment("part1 = 5"), "part1 = 5");
+    }
+
+    #[test]
+    fn table_headers() {
+        assert_eq!(parse_table_header("[day7.input]", "[day7.input]"), Ok((7, "input".to_string())));
+        assert_eq!(parse_table_header("[ day15.example-2 ]", "[ day15.example-2 ]"), Ok((15, "example-2".to_string())));
+
+        let e = parse_table_header("[day7.input", "[day7.input").unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (12, "']'"));
+        let e = parse_table_header("[week7.input]", "[week7.input]").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (2, "week7"));
+        let e = parse_table_header("[dayx.input]", "[dayx.input]").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (5, "x"));
+        let e = parse_table_header("[day7.in put]", "[day7.in put]").unwrap_err();
+        assert_eq!(e.expected, "an input name");
+    }
+
+    #[test]
+    fn key_values() {
+        assert_eq!(parse_key_value("part1 = -12", "part1 = -12"), Ok((1, Answer::Number(-12))));
+        assert_eq!(parse_key_value("part2=\"abc\"", "part2=\"abc\""), Ok((2, Answer::Text("abc".to_string()))));
+
+        let e = parse_key_value("part3 = 1", "part3 = 1").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (1, "part3"));
+        let e = parse_key_value("part1 = \"abc", "part1 = \"abc").unwrap_err();
+        assert_eq!(e.expected, "'\"'");
+        let e = parse_key_value("part1 = 1x", "part1 = 1x").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (9, "1x"));
+        let e = parse_key_value("part1", "part1").unwrap_err();
+        assert_eq!(e.expected, "'='");
+    }
+
+    #[test]
+    fn parse_file() {
+        let answers = parse("# Expected answers\n\n[day1.input]\npart1 = 5\npart2 = \"x\"  # text\n\n[day1.example]\n# part1 = \npart2 = 7\n").unwrap();
+        assert_eq!(answers.get(1, "input", 1), Some(&Answer::Number(5)));
+        assert_eq!(answers.get(1, "input", 2), Some(&Answer::Text("x".to_string())));
+        assert_eq!(answers.get(1, "example", 1), None);
+        assert_eq!(answers.get(1, "example", 2), Some(&Answer::Number(7)));
+        assert_eq!(answers.get(1, "input", 3), None);
+        assert_eq!(answers.get(2, "input", 1), None);
+        assert_eq!(answers.inputs(1), vec!["example", "input"]);
+    }
+
+    #[test]
+    fn parse_errors_have_lines() {
+        let e = parse("part1 = 5").err().unwrap();
+        assert_eq!((e.line, e.expected.as_str()), (1, "a table header"));
+        let e = parse("[day1.input]\n\npart1 = x").err().unwrap();
+        assert_eq!((e.line, e.column), (3, 9));
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// An error in a puzzle input, with its location.
+///
+/// Parsers for a single line create errors with a column but no line
+/// number (line 0), and the caller adds the line number using at_line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Name of the input file, if known.
+    pub file: Option<String>,
+
+    /// One-based line number, or 0 if unknown.
+    pub line: usize,
+
+    /// One-based column (in characters), or 0 if unknown.
+    pub column: usize,
+
+    /// Description of what was expected.
+    pub expected: String,
+
+    /// The text that was found instead; empty means the end of the line.
+    pub found: String
+}
+
+impl ParseError {
+    /// Creates an error at the specified column of an unknown line.
+    pub fn new(column: usize, expected: &str, found: &str) -> ParseError {
+        ParseError{
+            file: None,
+            line: 0,
+            column,
+            expected: expected.to_string(),
+            found: found.to_string()
+        }
+    }
+
+    /// Creates an error for a field of a line, where field is a slice
+    /// of line (or an empty slice at the end of line).
+    pub fn at_field(line: &str, field: &str, expected: &str) -> ParseError {
+        ParseError::new(column_of(line, field), expected, field)
+    }
+
+    /// Creates an error at the end of a line.
+    pub fn end_of_line(line: &str, expected: &str) -> ParseError {
+        ParseError::new(line.chars().count() + 1, expected, "")
+    }
+
+    /// Sets the one-based line number.
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file.as_deref().unwrap_or("<input>"))?;
+        if self.line != 0 {
+            write!(f, ":{}", self.line)?;
+            if self.column != 0 {
+                write!(f, ":{}", self.column)?;
+            }
+        }
+        write!(f, ": expected {}, found ", self.expected)?;
+        if self.found.is_empty() {
+            write!(f, "end of line")
+        }
+        else {
+            write!(f, "'{}'", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for std::io::Error {
+    fn from(e: ParseError) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Returns the one-based column of field within line, where field is
+/// a slice of line.
+pub fn column_of(line: &str, field: &str) -> usize {
+    let offset = (field.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    if offset <= line.len() && line.is_char_boundary(offset) {
+        line[..offset].chars().count() + 1
+    }
+    else {
+        1
+    }
+}
+
+/// Parses a field of a line, where field is a slice of line. Whitespace
+/// around the field is ignored.
+pub fn parse_field<T: FromStr>(line: &str, field: &str, expected: &str) -> Result<T, ParseError> {
+    let trimmed = field.trim();
+    trimmed.parse::<T>().map_err(|_| ParseError::at_field(line, trimmed, expected))
+}
+
+/// How to handle malformed input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail on the first malformed line.
+    Strict,
+
+    /// Skip malformed lines, collecting a diagnostic for each one.
+    Lenient
+}
+
+/// Collects the errors found while parsing an input.
+pub struct Diagnostics {
+    mode: ParseMode,
+    file: Option<String>,
+    errors: Vec<ParseError>
+}
+
+impl Diagnostics {
+    /// Creates a collector for the specified mode, where file is the name of
+    /// the input file (if any) used in error messages.
+    pub fn new(mode: ParseMode, file: Option<&str>) -> Diagnostics {
+        Diagnostics{ mode, file: file.map(|s| s.to_string()), errors: Vec::new() }
+    }
+
+    pub fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    /// Reports malformed input. In strict mode, the error is returned so the
+    /// parser can fail using `?`. In lenient mode, the error is recorded and
+    /// Ok is returned, and the parser should skip the malformed input.
+    pub fn report(&mut self, e: ParseError) -> Result<(), ParseError> {
+        let e = self.locate(e);
+        match self.mode {
+            ParseMode::Strict => Err(e),
+            ParseMode::Lenient => {
+                self.errors.push(e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Sets the file name of an error, if it doesn't already have one.
+    pub fn locate(&self, mut e: ParseError) -> ParseError {
+        if e.file.is_none() {
+            e.file = self.file.clone();
+        }
+        e
+    }
+
+    /// Errors recorded in lenient mode.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_of_field() {
+        let line = "mem[8] = 11";
+        assert_eq!(column_of(line, &line[..3]), 1);
+        assert_eq!(column_of(line, &line[4..5]), 5);
+        assert_eq!(column_of(line, &line[9..]), 10);
+        assert_eq!(column_of(line, &line[line.len()..]), 12);
+    }
+
+    #[test]
+    fn column_of_counts_chars() {
+        // 'é' is two bytes but one column.
+        let line = "café: 12";
+        assert_eq!(column_of(line, &line[7..]), 7);
+    }
+
+    #[test]
+    fn column_of_unrelated_slice() {
+        let other = String::from("12");
+        assert_eq!(column_of("a: 12", &other), 1);
+    }
+
+    #[test]
+    fn parse_field_trims() {
+        let line = "a, 12 ,b";
+        assert_eq!(parse_field::<u32>(line, &line[2..6], "a number"), Ok(12));
+        let e = parse_field::<u32>(line, &line[7..], "a number").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (8, "b"));
+    }
+
+    #[test]
+    fn error_display() {
+        let e = ParseError::new(3, "a number", "x").at_line(2);
+        assert_eq!(e.to_string(), "<input>:2:3: expected a number, found 'x'");
+        let e = ParseError::end_of_line("ab", "':'").at_line(4);
+        assert_eq!(e.to_string(), "<input>:4:3: expected ':', found end of line");
+    }
+
+    #[test]
+    fn strict_report_fails() {
+        let mut diag = Diagnostics::new(ParseMode::Strict, Some("in.txt"));
+        let e = diag.report(ParseError::new(1, "a number", "x").at_line(5)).unwrap_err();
+        assert_eq!(e.file.as_deref(), Some("in.txt"));
+        assert!(diag.errors().is_empty());
+    }
+
+    #[test]
+    fn lenient_report_records() {
+        let mut diag = Diagnostics::new(ParseMode::Lenient, Some("in.txt"));
+        assert_eq!(diag.report(ParseError::new(1, "a number", "x").at_line(5)), Ok(()));
+        assert_eq!(diag.report(ParseError::new(2, "a number", "y").at_line(7)), Ok(()));
+        let lines : Vec<usize> = diag.errors().iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![5, 7]);
+        assert_eq!(diag.errors()[0].file.as_deref(), Some("in.txt"));
+    }
+
+    #[test]
+    fn locate_keeps_file() {
+        let diag = Diagnostics::new(ParseMode::Lenient, Some("in.txt"));
+        let mut e = ParseError::new(1, "a number", "x");
+        e.file = Some("other.txt".to_string());
+        assert_eq!(diag.locate(e).file.as_deref(), Some("other.txt"));
+    }
+}
@@ -0,0 +1,269 @@
+use std::ops::{Index, IndexMut};
+use super::{ParseError, Diagnostics};
+
+/// Directions to the eight neighbours of a cell, as (dx, dy), where y
+/// increases downward.
+pub const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1)
+];
+
+/// Directions to the four orthogonal neighbours of a cell, as (dx, dy).
+pub const ORTHOGONAL_DIRECTIONS: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// A rectangular grid of cells, stored by rows. Cells are indexed by
+/// (x, y), where (0, 0) is the top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with every cell set to value.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where T: Clone {
+        Grid{ cells: vec![value; width * height], width, height }
+    }
+
+    /// Creates a grid from its cells, in rows of the specified width. The
+    /// number of cells must be a multiple of the width.
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Grid<T> {
+        assert!(width != 0 && cells.len().is_multiple_of(width), "cells don't fill rows of width {}", width);
+        let height = cells.len() / width;
+        Grid{ cells, width, height }
+    }
+
+    /// Parses a map with one row of cells per line, where to_cell converts
+    /// a character to a cell, or returns None if it isn't valid. expected
+    /// describes the valid characters for error messages. Blank lines are
+    /// skipped. Rows that are a different width than the first row, or
+    /// that contain invalid characters, are reported to diag.
+    pub fn parse<F>(lines: &[String], diag: &mut Diagnostics, expected: &str, to_cell: F) -> Result<Grid<T>, ParseError>
+    where F: Fn(char) -> Option<T> {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        for (i, line) in lines.iter().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            match parse_row(line, width, expected, &to_cell) {
+                Ok(row) => {
+                    width = row.len();
+                    cells.extend(row);
+                },
+                Err(e) => diag.report(e.at_line(i + 1))?
+            }
+        }
+        if width == 0 {
+            return Err(ParseError::new(1, "a row of cells", "").at_line(1));
+        }
+        Ok(Grid::from_cells(width, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the cells, by rows.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Returns an iterator over the rows, as slices.
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, T> {
+        self.cells.chunks_exact(self.width)
+    }
+
+    /// Returns an iterator over the cells and their (x, y) positions.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, cell)| (i % width, i / width, cell))
+    }
+
+    /// Returns the cell at (x, y), or None if it is outside the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    /// Returns the cell at (x, y), or None if it is outside the grid.
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        self.index_of(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// Returns the cell at (x, y), where the grid repeats in every
+    /// direction.
+    pub fn get_wrapped(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self[(x, y)]
+    }
+
+    /// Returns an iterator over the neighbours of (x, y) in the specified
+    /// directions, such as `DIRECTIONS`, skipping any outside the grid.
+    pub fn neighbors<'a>(&'a self, x: usize, y: usize, directions: &'a [(isize, isize)]) -> impl Iterator<Item = &'a T> + 'a {
+        directions.iter().filter_map(move |&(dx, dy)| self.get(x as isize + dx, y as isize + dy))
+    }
+
+    /// Returns an iterator over the cells in a straight line from (x, y) in
+    /// direction (dx, dy), not including (x, y), up to the edge of the grid.
+    pub fn ray(&self, x: usize, y: usize, dx: isize, dy: isize) -> Ray<'_, T> {
+        assert!(dx != 0 || dy != 0, "a ray needs a direction");
+        Ray{ grid: self, x: x as isize, y: y as isize, dx, dy }
+    }
+
+    /// Returns a grid of the same size, with f applied to each cell.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where F: Fn(&T) -> U {
+        Grid{ cells: self.cells.iter().map(f).collect(), width: self.width, height: self.height }
+    }
+
+    /// Renders the grid as text, with one line per row, where to_char
+    /// converts each cell to a character.
+    pub fn render<F>(&self, to_char: F) -> String
+    where F: Fn(&T) -> char {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            s.extend(row.iter().map(&to_char));
+            s.push('\n');
+        }
+        s
+    }
+
+    fn index_of(&self, x: isize, y: isize) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some(y as usize * self.width + x as usize)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    /// Returns the cell at (x, y), which must be inside the grid.
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside the grid", x, y);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside the grid", x, y);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Iterator over the cells along a ray. See `Grid::ray`.
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    x: isize,
+    y: isize,
+    dx: isize,
+    dy: isize
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let cell = self.grid.get(self.x + self.dx, self.y + self.dy)?;
+        self.x += self.dx;
+        self.y += self.dy;
+        Some(cell)
+    }
+}
+
+// Parses one row of cells, where width is the width of the previous rows
+// or zero if this is the first row.
+fn parse_row<T, F>(line: &str, width: usize, expected: &str, to_cell: &F) -> Result<Vec<T>, ParseError>
+where F: Fn(char) -> Option<T> {
+    let mut row = Vec::new();
+    for (col, ch) in line.chars().enumerate() {
+        if width != 0 && col == width {
+            return Err(ParseError::new(col + 1, "end of line", &ch.to_string()));
+        }
+        match to_cell(ch) {
+            Some(cell) => row.push(cell),
+            None => { return Err(ParseError::new(col + 1, expected, &ch.to_string())); }
+        }
+    }
+    if width != 0 && row.len() != width {
+        return Err(ParseError::end_of_line(line, expected));
+    }
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseMode;
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(|line| line.to_string()).collect()
+    }
+
+    fn parse(s: &str, mode: ParseMode) -> Result<Grid<bool>, ParseError> {
+        let mut diag = Diagnostics::new(mode, None);
+        Grid::parse(&lines(s), &mut diag, "'#' or '.'", |ch| match ch {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None
+        })
+    }
+
+    #[test]
+    fn parses_and_renders() {
+        let grid = parse("#..\n.#.\n\n..#\n", ParseMode::Strict).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert!(grid[(1, 1)] && !grid[(1, 0)]);
+        assert_eq!(grid.render(|&cell| if cell { '#' } else { '.' }), "#..\n.#.\n..#\n");
+    }
+
+    #[test]
+    fn reports_bad_rows() {
+        let e = parse("#..\n.#\n", ParseMode::Strict).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+        let e = parse("#..\n.#..\n", ParseMode::Strict).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 4));
+        let e = parse("#..\n.x.\n", ParseMode::Strict).unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (2, 2, "x"));
+        assert!(parse("\n", ParseMode::Lenient).is_err());
+
+        // In lenient mode, bad rows are skipped.
+        let grid = parse("#..\n.x.\n..#\n", ParseMode::Lenient).unwrap();
+        assert_eq!(grid.height(), 2);
+    }
+
+    #[test]
+    fn checks_bounds_and_wraps() {
+        let grid = Grid::from_cells(3, (0..6).collect());
+        assert_eq!(grid.get(2, 1), Some(&5));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, -1), None);
+        assert_eq!(*grid.get_wrapped(4, -1), 4);
+        assert_eq!(*grid.get_wrapped(-1, 2), 2);
+    }
+
+    #[test]
+    fn finds_neighbors_and_rays() {
+        let grid = Grid::from_cells(3, (0..9).collect());
+        let mut corner: Vec<i32> = grid.neighbors(0, 0, &DIRECTIONS).copied().collect();
+        corner.sort_unstable();
+        assert_eq!(corner, vec![1, 3, 4]);
+        assert_eq!(grid.neighbors(1, 1, &DIRECTIONS).count(), 8);
+        assert_eq!(grid.neighbors(1, 1, &ORTHOGONAL_DIRECTIONS).sum::<i32>(), 1 + 3 + 5 + 7);
+
+        assert_eq!(grid.ray(0, 0, 1, 1).copied().collect::<Vec<_>>(), vec![4, 8]);
+        assert_eq!(grid.ray(2, 1, -1, 0).copied().collect::<Vec<_>>(), vec![4, 3]);
+        assert_eq!(grid.ray(1, 0, 0, -1).count(), 0);
+    }
+}
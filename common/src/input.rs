@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::{read_file, read_lines, make_error};
+
+/// Where to read a puzzle's input from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// The day's checked-in input file.
+    Default,
+
+    /// A file, relative to the current directory.
+    File(String),
+
+    /// Standard input.
+    Stdin,
+
+    /// A named example in the day's examples directory, i.e.,
+    /// `examples/<name>.txt` relative to the day's crate directory.
+    Example(String)
+}
+
+/// Usage text for the input options recognized by InputSource::from_arg.
+pub const INPUT_OPTIONS_USAGE : &str = "[--input <path> | --stdin | --example <name>]";
+
+impl InputSource {
+    /// If arg is an input option, returns the corresponding input source,
+    /// taking the option's value (if any) from the remaining args.
+    /// Returns None if arg is not an input option.
+    pub fn from_arg<'a, I>(arg: &str, args: &mut I) -> std::io::Result<Option<InputSource>>
+    where I: Iterator<Item = &'a String> {
+        let mut value = |name: &str| args.next().cloned().ok_or_else(
+            || make_error(&format!("{} must be followed by a value.", name))
+        );
+        match arg {
+            "--input" => Ok(Some(InputSource::File(value(arg)?))),
+            "--stdin" => Ok(Some(InputSource::Stdin)),
+            "--example" => Ok(Some(InputSource::Example(value(arg)?))),
+            _ => Ok(None)
+        }
+    }
+
+    /// Returns the name of the input used in error messages.
+    pub fn name(&self, input_file: &str) -> String {
+        match self {
+            InputSource::Default => input_file.to_string(),
+            InputSource::File(path) => path.clone(),
+            InputSource::Stdin => "<stdin>".to_string(),
+            InputSource::Example(name) => format!("examples/{}.txt", name)
+        }
+    }
+
+    /// Returns the path of the input file for a day whose crate is in dir,
+    /// or None for standard input.
+    pub fn path(&self, dir: &Path, input_file: &str) -> Option<PathBuf> {
+        match self {
+            InputSource::Default => Some(dir.join(input_file)),
+            InputSource::File(path) => Some(PathBuf::from(path)),
+            InputSource::Stdin => None,
+            InputSource::Example(name) => Some(examples_dir(dir).join(format!("{}.txt", name)))
+        }
+    }
+
+    /// Reads the input lines for a day whose crate is in dir, and whose
+    /// checked-in input file is named input_file.
+    pub fn read_lines(&self, dir: &Path, input_file: &str) -> std::io::Result<Vec<String>> {
+        match self {
+            InputSource::Default => read_path(&dir.join(input_file)),
+            InputSource::File(path) => read_path(Path::new(path)),
+            InputSource::Stdin => read_lines(std::io::stdin().lock()),
+            InputSource::Example(name) => {
+                let path = examples_dir(dir).join(format!("{}.txt", name));
+                if path.exists() {
+                    read_path(&path)
+                }
+                else {
+                    Err(make_error(&format!(
+                        "No example named '{}'. Examples: {}.",
+                        name,
+                        list_examples(dir).join(", ")
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Returns the examples directory of a day's crate.
+pub fn examples_dir(dir: &Path) -> PathBuf {
+    dir.join("examples")
+}
+
+/// Returns the names of the examples for a day, in sorted order.
+pub fn list_examples(dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(entries) = fs::read_dir(examples_dir(dir)) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                if let Some(stem) = path.file_stem() {
+                    names.push(stem.to_string_lossy().into_owned());
+                }
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+// Reads a file, including the path in the error message if it fails.
+fn read_path(path: &Path) -> std::io::Result<Vec<String>> {
+    read_file(&path.to_string_lossy()).map_err(
+        |e| make_error(&format!("{}: {}", path.display(), e))
+    )
+}
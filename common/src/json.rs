@@ -0,0 +1,170 @@
+use std::fmt;
+
+/// A JSON value, used to write machine-readable output.
+///
+/// Object members are kept in the order they are added, so the output
+/// has a stable layout.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>)
+}
+
+impl Json {
+    /// Creates an empty object.
+    pub fn object() -> Json {
+        Json::Object(Vec::new())
+    }
+
+    /// Adds a member to an object, returning the object so calls can be
+    /// chained. Panics if self is not an object.
+    pub fn with(mut self, name: &str, value: Json) -> Json {
+        match &mut self {
+            Json::Object(members) => members.push((name.to_string(), value)),
+            _ => panic!("Json::with called on a value that is not an object.")
+        }
+        self
+    }
+
+    /// Returns the value of an object member, if self is an object
+    /// containing the member.
+    pub fn get(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+macro_rules! json_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n: $t) -> Json {
+                    Json::Number(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+json_from_int!(i32, u32, i64, u64, usize);
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(opt: Option<T>) -> Json {
+        opt.map_or(Json::Null, |v| v.into())
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Json {
+        Json::Array(v.into_iter().map(|x| x.into()).collect())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::Float(x) => {
+                if x.is_finite() { write!(f, "{}", x) } else { write!(f, "null") }
+            },
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 { write!(f, ",")?; }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i != 0 { write!(f, ",")?; }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Writes a string literal, escaping characters as required by JSON.
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            _ if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            _ => write!(f, "{}", ch)?
+        }
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_strings() {
+        let s = Json::from("a\"b\\c\nd\re\tf\u{1}g");
+        assert_eq!(s.to_string(), r#""a\"b\\c\nd\re\tf\u0001g""#);
+    }
+
+    #[test]
+    fn keeps_non_ascii() {
+        assert_eq!(Json::from("café ✓").to_string(), "\"café ✓\"");
+    }
+
+    #[test]
+    fn escapes_member_names() {
+        let obj = Json::object().with("a\"b", Json::Null);
+        assert_eq!(obj.to_string(), r#"{"a\"b":null}"#);
+    }
+
+    #[test]
+    fn formats_values() {
+        let obj = Json::object()
+            .with("n", (-5).into())
+            .with("x", Json::Float(1.5))
+            .with("nan", Json::Float(f64::NAN))
+            .with("b", true.into())
+            .with("none", Option::<u32>::None.into())
+            .with("list", vec![1, 2].into());
+        assert_eq!(obj.to_string(), r#"{"n":-5,"x":1.5,"nan":null,"b":true,"none":null,"list":[1,2]}"#);
+        assert_eq!(obj.get("n"), Some(&Json::Number(-5)));
+        assert_eq!(obj.get("missing"), None);
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::io::{prelude::*, BufReader};
+use std::str::FromStr;
+
+mod answers;
+mod error;
+mod grid;
+mod input;
+mod json;
+pub mod log;
+mod number_theory;
+pub mod parser;
+mod random;
+mod report;
+mod solution;
+pub use answers::{ExpectedAnswers, DEFAULT_INPUT_NAME};
+pub use error::{ParseError, ParseMode, Diagnostics, column_of, parse_field};
+pub use grid::{Grid, Ray, DIRECTIONS, ORTHOGONAL_DIRECTIONS};
+pub use json::Json;
+pub use number_theory::{gcd, lcm, extended_gcd, mod_inverse, chinese_remainder, is_prime, find_k_sum, find_all_k_sums};
+pub use random::Rng;
+pub use report::{JSON_SCHEMA_VERSION, day_to_json, report_to_json};
+pub use input::{InputSource, INPUT_OPTIONS_USAGE, examples_dir, list_examples};
+pub use solution::{Answer, Solution, Generated, PartResult, RunResult, Solver, run_main, parse_output_format, print_diagnostics};
+
+/// Reads all the lines from a reader, such as a file or standard input.
+pub fn read_lines<R: BufRead>(reader: R) -> std::io::Result<Vec::<String>> {
+    let mut v = Vec::new();
+    for line in reader.lines() {
+        v.push(line?);
+    }
+    Ok(v)
+}
+
+/// Reads a text file into a vector of lines.
+pub fn read_file(path: &str) -> std::io::Result<Vec::<String>> {
+    read_lines(BufReader::new(fs::File::open(path)?))
+}
+
+/// Reads a text file with one number per line. Blank lines are skipped,
+/// and any other line that is not a number is an error.
+pub fn read_numbers<T: FromStr>(path: &str) -> std::io::Result<Vec::<T>> {
+    let mut diag = Diagnostics::new(ParseMode::Strict, Some(path));
+    Ok(parse_numbers(&read_file(path)?, &mut diag)?)
+}
+
+/// Reads a text file comprising records separated by blank lines.
+/// Each record is returned as a vector of its (non-blank) lines.
+pub fn read_records(path: &str) -> std::io::Result<Vec::<Vec::<String>>> {
+    let lines = read_file(path)?;
+    Ok(split_records(&lines).iter().map(|rec| rec.lines.to_vec()).collect())
+}
+
+/// Parses lines with one number per line. Blank lines are skipped, and
+/// other lines that are not numbers are reported to diag.
+pub fn parse_numbers<T: FromStr>(lines: &[String], diag: &mut Diagnostics) -> Result<Vec::<T>, ParseError> {
+    let mut v = Vec::new();
+    for (i, s) in lines.iter().enumerate() {
+        if !s.trim().is_empty() {
+            match parse_field::<T>(s, s, "a number") {
+                Ok(n) => v.push(n),
+                Err(e) => diag.report(e.at_line(i + 1))?
+            }
+        }
+    }
+    Ok(v)
+}
+
+/// A group of consecutive non-blank lines.
+pub struct Record<'a> {
+    /// Zero-based index of the first line of the record.
+    pub start: usize,
+    pub lines: &'a [String]
+}
+
+impl<'a> Record<'a> {
+    /// Returns the one-based line number of the i-th line in the record.
+    pub fn line_number(&self, i: usize) -> usize {
+        self.start + i + 1
+    }
+}
+
+/// Groups lines into records separated by one or more blank lines.
+pub fn split_records(lines: &[String]) -> Vec::<Record<'_>> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            if start < i {
+                records.push(Record{ start, lines: &lines[start..i] });
+            }
+            start = i + 1;
+        }
+    }
+
+    // Add the last record if the input doesn't end with a blank line.
+    if start < lines.len() {
+        records.push(Record{ start, lines: &lines[start..] });
+    }
+    records
+}
+
+/// Creates an I/O error with the specified message.
+pub fn make_error(message: &str) -> std::io::Error {
+    std::io::Error::other(message)
+}
+
+/// Converts an Option to a Result, treating None as invalid input.
+pub fn to_result<T>(opt : Option<T>) -> std::io::Result<T> {
+    opt.ok_or_else(|| make_error("Invalid input."))
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Name of the environment variable that sets the log level, such as
+/// `AOC_LOG=debug`. The -v and -q options override it.
+pub const LOG_ENV_VAR: &str = "AOC_LOG";
+
+/// Usage text for the options parsed by `parse_log_option`.
+pub const LOG_OPTIONS_USAGE: &str = "[-v | -vv | -vvv | -q]";
+
+/// True if trace messages are compiled in, which requires the `trace`
+/// feature of this crate. Otherwise the `trace!` macro generates no code.
+pub const TRACE_ENABLED: bool = cfg!(feature = "trace");
+
+/// The severity of a log message. Messages less severe than the current
+/// level are not written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace
+}
+
+impl Level {
+    /// Returns the level with the specified name, such as "warn".
+    pub fn from_name(name: &str) -> Option<Level> {
+        match name.to_ascii_lowercase().as_str() {
+            "error" => Some(Level::Error),
+            "warn" | "warning" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None
+        }
+    }
+
+    fn from_u8(n: u8) -> Level {
+        match n {
+            1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            _ => Level::Trace
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Error => "error",
+            Level::Warn => "warning",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace"
+        };
+        f.write_str(name)
+    }
+}
+
+// The current level, shared by all threads. 0 means it hasn't been set,
+// so it is read from the environment on first use.
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the current log level, which is Warn unless set otherwise by
+/// `set_level` or the `AOC_LOG` environment variable.
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => {
+            let level = std::env::var(LOG_ENV_VAR).ok()
+                .and_then(|s| Level::from_name(&s))
+                .unwrap_or(Level::Warn);
+            // Don't overwrite a level set by another thread meanwhile.
+            let _ = LEVEL.compare_exchange(0, level as u8, Ordering::Relaxed, Ordering::Relaxed);
+            Level::from_u8(LEVEL.load(Ordering::Relaxed))
+        },
+        n => Level::from_u8(n)
+    }
+}
+
+/// Sets the log level.
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns true if messages of the specified level are written.
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// Sets the log level if arg is one of the logging options: -q for errors
+/// only, or -v, -vv or -vvv for info, debug or trace messages. Returns
+/// false if arg isn't a logging option.
+pub fn parse_log_option(arg: &str) -> bool {
+    let level = match arg {
+        "-q" | "--quiet" => Level::Error,
+        "-v" | "--verbose" => Level::Info,
+        "-vv" => Level::Debug,
+        "-vvv" => Level::Trace,
+        _ => { return false; }
+    };
+    set_level(level);
+    true
+}
+
+/// Writes a message to stderr if its level is enabled. Use the `error!`,
+/// `warn!`, `info!`, `debug!` and `trace!` macros instead of calling this
+/// directly.
+pub fn write(level: Level, args: fmt::Arguments) {
+    if enabled(level) {
+        eprintln!("{}: {}", level, args);
+    }
+}
+
+/// Logs an error message to stderr.
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Error, format_args!($($arg)*)) };
+}
+
+/// Logs a warning to stderr, unless -q is specified.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+
+/// Logs an informational message to stderr, if -v is specified.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+
+/// Logs a debugging message to stderr, if -vv is specified.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
+
+/// Logs a trace message to stderr, if -vvv is specified and the `trace`
+/// feature of aoc-common is enabled. Without the feature, the arguments
+/// are type-checked but no code is generated, so it can be used in hot
+/// loops.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::TRACE_ENABLED {
+            $crate::log::write($crate::log::Level::Trace, format_args!($($arg)*))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_levels_and_options() {
+        assert_eq!(Level::from_name("DEBUG"), Some(Level::Debug));
+        assert_eq!(Level::from_name("warning"), Some(Level::Warn));
+        assert_eq!(Level::from_name("loud"), None);
+        assert!(Level::Error < Level::Trace);
+
+        assert!(parse_log_option("-vv"));
+        assert_eq!(level(), Level::Debug);
+        assert!(enabled(Level::Info) && !enabled(Level::Trace));
+        assert!(parse_log_option("-q"));
+        assert!(!enabled(Level::Warn));
+        assert!(!parse_log_option("-x"));
+        assert_eq!(level(), Level::Error);
+        set_level(Level::Warn);
+    }
+}
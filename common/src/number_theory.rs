@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, Sub};
+
+/// Returns the greatest common divisor of a and b, which is 0 only if both
+/// are 0.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let mut a = a;
+    let mut b = b;
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Returns the least common multiple of a and b, or None if it overflows.
+/// The least common multiple of 0 and any number is 0.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Returns (g, x, y) such that a * x + b * y = g, where g is the greatest
+/// common divisor of a and b. The arguments must not be i64::MIN.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_wide(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+/// Returns the inverse of a modulo m, i.e., x in 0..m such that a * x is 1
+/// modulo m, or None if a and m aren't coprime or m isn't positive.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd_wide((a as i128).rem_euclid(m as i128), m as i128);
+    if g == 1 {
+        Some(x.rem_euclid(m as i128) as i64)
+    }
+    else {
+        None
+    }
+}
+
+/// Solves a system of congruences x = a (mod n), given as (a, n) pairs,
+/// where the moduli are positive but need not be coprime. Returns (x, m),
+/// where x is the smallest non-negative solution and m is the least common
+/// multiple of the moduli, so the solutions are x + k * m. Returns None if
+/// there is no solution, a modulus is 0, or m overflows a u64.
+pub fn chinese_remainder(congruences: &[(i64, u64)]) -> Option<(u64, u64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for &(a, n) in congruences {
+        if n == 0 {
+            return None;
+        }
+        let n = n as i128;
+        let a = (a as i128).rem_euclid(n);
+
+        // Find k such that x + m * k = a (mod n), i.e., m * k = a - x (mod n).
+        // There is a solution only if gcd(m, n) divides a - x.
+        let (g, p, _) = extended_gcd_wide(m, n);
+        let diff = a - x;
+        if diff % g != 0 {
+            return None;
+        }
+
+        // The new modulus is lcm(m, n) = m * (n / g).
+        let n_g = n / g;
+        let lcm = m.checked_mul(n_g).filter(|&lcm| lcm <= u64::MAX as i128)?;
+        let k = mul_mod((diff / g).rem_euclid(n_g) as u128, p.rem_euclid(n_g) as u128, n_g as u128);
+
+        // Since x < m and k < n / g, x + m * k < lcm, which fits.
+        x += m * k as i128;
+        m = lcm;
+    }
+    Some((x as u64, m as u64))
+}
+
+/// Returns true if n is prime, using a Miller-Rabin test with bases that
+/// make it deterministic for all u64 values.
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for &p in &BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // Write n - 1 as d * 2^s, where d is odd.
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    'bases: for &a in &BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x as u128, x as u128, n as u128);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Finds k values in sorted that add up to target, where each value can be
+/// used as many times as it occurs. Returns the first combination found,
+/// in ascending order, or None if there is none or k is 0. The sum of any
+/// two values must not overflow.
+pub fn find_k_sum<T>(sorted: &[T], k: usize, target: T) -> Option<Vec<T>>
+where T: Copy + Ord + Add<Output = T> + Sub<Output = T> {
+    match k {
+        0 => None,
+        1 => sorted.binary_search(&target).ok().map(|_| vec![target]),
+        2 => {
+            // Move inward from both ends until the sum is found.
+            let mut lo = 0;
+            let mut hi = sorted.len();
+            while hi >= 2 && lo < hi - 1 {
+                let sum = sorted[lo] + sorted[hi - 1];
+                if sum == target {
+                    return Some(vec![sorted[lo], sorted[hi - 1]]);
+                }
+                if sum < target {
+                    lo += 1;
+                }
+                else {
+                    hi -= 1;
+                }
+            }
+            None
+        },
+        _ => {
+            // Try each value as the smallest, skipping repeats, and look
+            // for the rest among the values after it.
+            for i in 0..sorted.len() {
+                if i > 0 && sorted[i] == sorted[i - 1] {
+                    continue;
+                }
+                let a = sorted[i];
+                if let Some(mut rest) = find_k_sum(&sorted[i + 1..], k - 1, target - a) {
+                    rest.insert(0, a);
+                    return Some(rest);
+                }
+            }
+            None
+        }
+    }
+}
+
+/// Finds every combination of k values that add up to target, in
+/// lexicographic order, each in ascending order. Unlike `find_k_sum`, the
+/// values can be in any order. Each value can be used as many times as it
+/// occurs, so repeated values don't produce repeated combinations. The sum
+/// of any k values must not overflow.
+///
+/// Pairs are found with a hash lookup of the complement of each value, in
+/// O(n) time for n distinct values (after sorting them). Larger k use
+/// meet-in-the-middle: the combinations of the k / 2 smallest values of
+/// each solution are indexed by sum, and looked up by the complement of
+/// each combination of the rest, in O(n^(k - k / 2)) time, plus the size
+/// of the output.
+pub fn find_all_k_sums<T>(values: &[T], k: usize, target: T) -> Vec<Vec<T>>
+where T: Copy + Ord + Hash + Add<Output = T> + Sub<Output = T> {
+    let counts = Counts::new(values);
+    let mut combos = match k {
+        0 => Vec::new(),
+        1 => {
+            if counts.count(target) > 0 { vec![vec![target]] } else { Vec::new() }
+        },
+        2 => find_all_pairs(&counts, target),
+        _ => meet_in_the_middle(&counts, k, target)
+    };
+    combos.sort_unstable();
+    combos
+}
+
+// The distinct values, in ascending order, and how many times each occurs.
+struct Counts<T> {
+    distinct: Vec<T>,
+    counts: HashMap<T, usize>
+}
+
+impl<T> Counts<T>
+where T: Copy + Ord + Hash {
+    fn new(values: &[T]) -> Counts<T> {
+        let mut counts = HashMap::new();
+        for &n in values {
+            *counts.entry(n).or_insert(0) += 1;
+        }
+        let mut distinct: Vec<T> = counts.keys().copied().collect();
+        distinct.sort_unstable();
+        Counts{ distinct, counts }
+    }
+
+    fn count(&self, n: T) -> usize {
+        self.counts.get(&n).copied().unwrap_or(0)
+    }
+
+    // Calls f with each combination of size values, in ascending order,
+    // using each value at most as many times as it occurs.
+    fn for_each_combination<F>(&self, size: usize, f: &mut F)
+    where F: FnMut(&[T]) {
+        let mut combo = Vec::with_capacity(size);
+        self.extend_combination(&mut combo, size, 0, f);
+    }
+
+    fn extend_combination<F>(&self, combo: &mut Vec<T>, size: usize, start: usize, f: &mut F)
+    where F: FnMut(&[T]) {
+        if combo.len() == size {
+            f(combo);
+            return;
+        }
+        for i in start..self.distinct.len() {
+            let n = self.distinct[i];
+            let used = combo.iter().rev().take_while(|&&m| m == n).count();
+            if used < self.count(n) {
+                combo.push(n);
+                // The next value can be the same one, if it occurs again.
+                self.extend_combination(combo, size, i, f);
+                combo.pop();
+            }
+        }
+    }
+}
+
+// Returns the sum of one or more values.
+fn sum_of<T>(values: &[T]) -> T
+where T: Copy + Add<Output = T> {
+    values[1..].iter().fold(values[0], |sum, &n| sum + n)
+}
+
+// Finds the pairs a <= b with a + b = target by looking up the complement
+// of each value.
+fn find_all_pairs<T>(counts: &Counts<T>, target: T) -> Vec<Vec<T>>
+where T: Copy + Ord + Hash + Sub<Output = T> {
+    let mut pairs = Vec::new();
+    for &a in &counts.distinct {
+        let b = target - a;
+        if (b > a && counts.count(b) > 0) || (b == a && counts.count(a) >= 2) {
+            pairs.push(vec![a, b]);
+        }
+    }
+    pairs
+}
+
+// Finds the combinations of k >= 3 values by splitting each into its
+// k / 2 smallest values (the left half) and the rest (the right half).
+fn meet_in_the_middle<T>(counts: &Counts<T>, k: usize, target: T) -> Vec<Vec<T>>
+where T: Copy + Ord + Hash + Add<Output = T> + Sub<Output = T> {
+    let left_size = k / 2;
+
+    // Index the left halves by sum. The halves are stored one after
+    // another in a single vector, and the index holds their offsets.
+    let mut halves = Vec::new();
+    let mut left_by_sum: HashMap<T, Vec<usize>> = HashMap::new();
+    counts.for_each_combination(left_size, &mut |left| {
+        left_by_sum.entry(sum_of(left)).or_default().push(halves.len());
+        halves.extend_from_slice(left);
+    });
+
+    // Match each right half with the left halves that complete the sum,
+    // whose values are all less than or equal to the right half's.
+    let mut combos = Vec::new();
+    counts.for_each_combination(k - left_size, &mut |right| {
+        let complement = target - sum_of(right);
+        if let Some(offsets) = left_by_sum.get(&complement) {
+            let min = right[0];
+            for &offset in offsets {
+                let left = &halves[offset..offset + left_size];
+                let max = left[left_size - 1];
+                if max > min {
+                    continue;
+                }
+
+                // If the halves share a value, it must occur often enough.
+                if max == min {
+                    let used = left.iter().chain(right).filter(|&&n| n == min).count();
+                    if used > counts.count(min) {
+                        continue;
+                    }
+                }
+                combos.push(left.iter().chain(right).copied().collect());
+            }
+        }
+    });
+    combos
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        let next = (r, old_r - q * r);
+        old_r = next.0;
+        r = next.1;
+        let next = (x, old_x - q * x);
+        old_x = next.0;
+        x = next.1;
+        let next = (y, old_y - q * y);
+        old_y = next.0;
+        y = next.1;
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    }
+    else {
+        (old_r, old_x, old_y)
+    }
+}
+
+fn mul_mod(a: u128, b: u128, m: u128) -> u64 {
+    ((a * b) % m) as u64
+}
+
+fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+    while exp != 0 {
+        if exp & 1 != 0 {
+            result = mul_mod(result as u128, base as u128, m as u128);
+        }
+        base = mul_mod(base as u128, base as u128, m as u128);
+        exp >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn computes_bezout_coefficients() {
+        for &(a, b) in &[(240, 46), (-240, 46), (17, -5), (0, 7), (7, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i64);
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn computes_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 0), None);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        // Day 13 example: 17,x,13,19 departs at t + i for each bus.
+        assert_eq!(chinese_remainder(&[(0, 17), (-2, 13), (-3, 19)]), Some((3417, 17 * 13 * 19)));
+        assert_eq!(chinese_remainder(&[]), Some((0, 1)));
+
+        // Moduli that aren't coprime.
+        assert_eq!(chinese_remainder(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(chinese_remainder(&[(1, 4), (2, 6)]), None);
+        assert_eq!(chinese_remainder(&[(1, 0)]), None);
+
+        // Large moduli, including ones whose least common multiple doesn't fit.
+        let p = 18446744073709551557; // largest u64 prime
+        assert_eq!(chinese_remainder(&[(-1, p)]), Some((p - 1, p)));
+        assert_eq!(chinese_remainder(&[(-1, p), (3, p)]), None);
+        assert_eq!(chinese_remainder(&[(-1, p), (1, 2)]), None);
+        assert_eq!(chinese_remainder(&[(-1, 1 << 32), (0, (1 << 32) - 1)]), Some((0xFFFF_FFFF, 0xFFFF_FFFF_0000_0000)));
+    }
+
+    #[test]
+    fn tests_primality() {
+        let slow = |n: u64| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d));
+        for n in 0..2000 {
+            assert_eq!(is_prime(n), slow(n), "{}", n);
+        }
+        assert!(is_prime((1 << 61) - 1));
+        assert!(is_prime(18446744073709551557));
+        assert!(!is_prime(561));             // Carmichael number
+        assert!(!is_prime(3215031751));      // strong pseudoprime to bases 2, 3, 5 and 7
+        assert!(!is_prime(u64::MAX));
+    }
+
+    #[test]
+    fn finds_k_sums() {
+        let v = [-5, 1, 3, 3, 8, 10];
+        assert_eq!(find_k_sum(&v, 1, 8), Some(vec![8]));
+        assert_eq!(find_k_sum(&v, 2, 6), Some(vec![3, 3]));
+        assert_eq!(find_k_sum(&v, 2, 5), Some(vec![-5, 10]));
+        assert_eq!(find_k_sum(&v, 2, 16), None);
+        assert_eq!(find_k_sum(&v, 3, 4), Some(vec![-5, 1, 8]));
+        assert_eq!(find_k_sum(&v, 4, 24), Some(vec![3, 3, 8, 10]));
+        assert_eq!(find_k_sum(&v, 7, 20), None);
+        assert_eq!(find_k_sum(&v, 0, 0), None);
+        assert_eq!(find_k_sum::<i32>(&[], 2, 0), None);
+    }
+
+    // Finds the combinations by trying every subset of positions.
+    fn brute_force_k_sums(values: &[i64], k: usize, target: i64) -> Vec<Vec<i64>> {
+        let mut combos = Vec::new();
+        for mask in 0u32..(1 << values.len()) {
+            if mask.count_ones() as usize == k {
+                let mut combo: Vec<i64> = (0..values.len()).filter(|&i| mask & (1 << i) != 0).map(|i| values[i]).collect();
+                if combo.iter().sum::<i64>() == target {
+                    combo.sort_unstable();
+                    combos.push(combo);
+                }
+            }
+        }
+        combos.sort_unstable();
+        combos.dedup();
+        combos
+    }
+
+    #[test]
+    fn finds_all_k_sums() {
+        let example = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(find_all_k_sums(&example, 2, 2020), vec![vec![299, 1721]]);
+        assert_eq!(find_all_k_sums(&example, 3, 2020), vec![vec![366, 675, 979]]);
+        assert!(find_all_k_sums(&example, 0, 0).is_empty());
+        assert_eq!(find_all_k_sums(&example, 1, 366), vec![vec![366]]);
+        assert_eq!(find_all_k_sums(&[1, 2, 3, 4, 5, 6], 2, 7), vec![vec![1, 6], vec![2, 5], vec![3, 4]]);
+
+        let values = [5, -3, 2, 2, 8, 0, 7, 2, -1, 10, 3, 3];
+        for k in 1..=6 {
+            for target in -4..=25 {
+                let all = find_all_k_sums(&values, k, target);
+                assert_eq!(all, brute_force_k_sums(&values, k, target), "k = {}, target = {}", k, target);
+
+                // The first is the one find_k_sum finds.
+                let mut sorted = values.to_vec();
+                sorted.sort_unstable();
+                assert_eq!(all.first().cloned(), find_k_sum(&sorted, k, target), "k = {}, target = {}", k, target);
+            }
+        }
+    }
+}
@@ -0,0 +1,450 @@
+use std::str::FromStr;
+use super::ParseError;
+
+/// A range of a line being parsed, as byte offsets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize
+}
+
+/// Why a parser failed: what it expected, and the span of what it found
+/// instead (empty at the end of the line).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub span: Span,
+    pub expected: String,
+
+    // True if the failure came from inside a `cut`, so alternatives
+    // shouldn't be tried.
+    committed: bool
+}
+
+impl Failure {
+    pub fn new(span: Span, expected: &str) -> Failure {
+        Failure{ span, expected: expected.to_string(), committed: false }
+    }
+
+    /// Converts the failure to an error for the line it occurred in.
+    pub fn to_error(&self, line: &str) -> ParseError {
+        let column = line[..self.span.start].chars().count() + 1;
+        ParseError::new(column, &self.expected, &line[self.span.start..self.span.end])
+    }
+
+    // Combines the failures of two alternatives, keeping the one that got
+    // further, or both expectations if they failed at the same place.
+    fn or(self, other: Failure) -> Failure {
+        if self.span.start > other.span.start {
+            self
+        }
+        else if self.span.start < other.span.start {
+            other
+        }
+        else {
+            let span = Span{ start: self.span.start, end: self.span.end.max(other.span.end) };
+            Failure{ span, expected: format!("{} or {}", self.expected, other.expected), committed: other.committed }
+        }
+    }
+}
+
+/// The position of a parser within a line.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    line: &'a str,
+    pos: usize
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a cursor at the start of a line.
+    pub fn new(line: &'a str) -> Cursor<'a> {
+        Cursor{ line, pos: 0 }
+    }
+
+    /// Returns the byte offset of the cursor in the line.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the rest of the line after the cursor.
+    pub fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    /// Returns the span from the cursor to a later cursor.
+    pub fn span_to(&self, end: Cursor<'a>) -> Span {
+        Span{ start: self.pos, end: end.pos }
+    }
+
+    /// Returns a failure for the next char_count characters (or fewer at the
+    /// end of the line).
+    pub fn failure(&self, char_count: usize, expected: &str) -> Failure {
+        let len = self.rest().char_indices().nth(char_count).map_or(self.rest().len(), |(i, _)| i);
+        Failure::new(Span{ start: self.pos, end: self.pos + len }, expected)
+    }
+
+    fn advance(&self, len: usize) -> Cursor<'a> {
+        Cursor{ line: self.line, pos: self.pos + len }
+    }
+}
+
+/// The result of a parser: the value and the cursor after it, or a failure.
+pub type PResult<'a, T> = Result<(T, Cursor<'a>), Failure>;
+
+/// Something that parses a T from the start of a cursor. Any function or
+/// closure taking a cursor and returning a PResult is a parser.
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Cursor<'a>) -> PResult<'a, T>;
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where F: Fn(Cursor<'a>) -> PResult<'a, T> {
+    fn parse(&self, input: Cursor<'a>) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Parses a whole line with the parser, which must consume all of it.
+pub fn parse_all<'a, T>(line: &'a str, parser: impl Parser<'a, T>) -> Result<T, ParseError> {
+    terminated(parser, end_of_line()).parse(Cursor::new(line))
+        .map(|(value, _)| value)
+        .map_err(|failure| failure.to_error(line))
+}
+
+/// Matches the specified text.
+pub fn literal<'a>(text: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Cursor<'a>| {
+        if input.rest().starts_with(text) {
+            Ok((&input.rest()[..text.len()], input.advance(text.len())))
+        }
+        else {
+            Err(input.failure(text.chars().count(), &format!("'{}'", text)))
+        }
+    }
+}
+
+/// Matches the end of the line.
+pub fn end_of_line<'a>() -> impl Parser<'a, ()> {
+    move |input: Cursor<'a>| {
+        if input.rest().is_empty() {
+            Ok(((), input))
+        }
+        else {
+            Err(input.failure(usize::MAX, "end of line"))
+        }
+    }
+}
+
+/// Matches a character for which pred is true.
+pub fn char_if<'a, P>(pred: P, expected: &'static str) -> impl Parser<'a, char>
+where P: Fn(char) -> bool {
+    move |input: Cursor<'a>| {
+        match input.rest().chars().next() {
+            Some(ch) if pred(ch) => Ok((ch, input.advance(ch.len_utf8()))),
+            _ => Err(input.failure(1, expected))
+        }
+    }
+}
+
+/// Matches any character.
+pub fn any_char<'a>(expected: &'static str) -> impl Parser<'a, char> {
+    char_if(|_| true, expected)
+}
+
+/// Matches zero or more characters for which pred is true.
+pub fn take_while<'a, P>(pred: P) -> impl Parser<'a, &'a str>
+where P: Fn(char) -> bool {
+    move |input: Cursor<'a>| {
+        let rest = input.rest();
+        let len = rest.char_indices().find(|&(_, ch)| !pred(ch)).map_or(rest.len(), |(i, _)| i);
+        Ok((&rest[..len], input.advance(len)))
+    }
+}
+
+/// Matches one or more characters for which pred is true.
+pub fn take_while1<'a, P>(pred: P, expected: &'static str) -> impl Parser<'a, &'a str>
+where P: Fn(char) -> bool {
+    let parser = take_while(pred);
+    move |input: Cursor<'a>| {
+        match parser.parse(input)? {
+            ("", _) => Err(input.failure(1, expected)),
+            result => Ok(result)
+        }
+    }
+}
+
+/// Matches the text up to (but not including) the first occurrence of
+/// delim, which must be in the rest of the line.
+pub fn take_until<'a>(delim: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Cursor<'a>| {
+        match input.rest().find(delim) {
+            Some(len) => Ok((&input.rest()[..len], input.advance(len))),
+            None => Err(input.advance(input.rest().len()).failure(0, &format!("'{}'", delim)))
+        }
+    }
+}
+
+/// Matches the rest of the line.
+pub fn rest<'a>() -> impl Parser<'a, &'a str> {
+    take_while(|_| true)
+}
+
+/// Skips any whitespace.
+pub fn spaces<'a>() -> impl Parser<'a, ()> {
+    map(take_while(char::is_whitespace), |_| ())
+}
+
+/// Skips any whitespace before the parser.
+pub fn token<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    preceded(spaces(), parser)
+}
+
+/// Matches an unsigned decimal number of type T.
+pub fn unsigned<'a, T: FromStr>(expected: &'static str) -> impl Parser<'a, T> {
+    number_with(take_while1(|ch| ch.is_ascii_digit(), expected), expected)
+}
+
+/// Matches a decimal number of type T, with an optional sign.
+pub fn signed<'a, T: FromStr>(expected: &'static str) -> impl Parser<'a, T> {
+    let digits = recognize(pair(
+        optional(char_if(|ch| ch == '+' || ch == '-', expected)),
+        take_while1(|ch| ch.is_ascii_digit(), expected)
+    ));
+    number_with(digits, expected)
+}
+
+// Converts the text matched by a parser to a number, failing if it is out
+// of range.
+fn number_with<'a, T: FromStr>(digits: impl Parser<'a, &'a str>, expected: &'static str) -> impl Parser<'a, T> {
+    move |input: Cursor<'a>| {
+        let (s, next) = digits.parse(input)?;
+        match s.parse::<T>() {
+            Ok(n) => Ok((n, next)),
+            Err(_) => Err(Failure::new(input.span_to(next), expected))
+        }
+    }
+}
+
+/// Applies f to the value of a parser.
+pub fn map<'a, T, U, F>(parser: impl Parser<'a, T>, f: F) -> impl Parser<'a, U>
+where F: Fn(T) -> U {
+    move |input: Cursor<'a>| {
+        let (value, next) = parser.parse(input)?;
+        Ok((f(value), next))
+    }
+}
+
+/// Fails, at the span the parser matched, unless pred is true for the
+/// value of the parser.
+pub fn verify<'a, T, P>(parser: impl Parser<'a, T>, pred: P, expected: &'static str) -> impl Parser<'a, T>
+where P: Fn(&T) -> bool {
+    move |input: Cursor<'a>| {
+        let (value, next) = parser.parse(input)?;
+        if pred(&value) {
+            Ok((value, next))
+        }
+        else {
+            Err(Failure::new(input.span_to(next), expected))
+        }
+    }
+}
+
+/// Returns the value of a parser along with the span it matched.
+pub fn spanned<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, (T, Span)> {
+    move |input: Cursor<'a>| {
+        let (value, next) = parser.parse(input)?;
+        Ok(((value, input.span_to(next)), next))
+    }
+}
+
+/// Returns the text matched by a parser instead of its value.
+pub fn recognize<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, &'a str> {
+    move |input: Cursor<'a>| {
+        let (_, next) = parser.parse(input)?;
+        Ok((&input.rest()[..next.pos - input.pos], next))
+    }
+}
+
+/// Matches two parsers in turn.
+pub fn pair<'a, A, B>(a: impl Parser<'a, A>, b: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+    move |input: Cursor<'a>| {
+        let (x, input) = a.parse(input)?;
+        let (y, input) = b.parse(input)?;
+        Ok(((x, y), input))
+    }
+}
+
+/// Matches three parsers in turn.
+pub fn triple<'a, A, B, C>(a: impl Parser<'a, A>, b: impl Parser<'a, B>, c: impl Parser<'a, C>) -> impl Parser<'a, (A, B, C)> {
+    map(pair(a, pair(b, c)), |(x, (y, z))| (x, y, z))
+}
+
+/// Matches two parsers in turn, keeping the value of the second.
+pub fn preceded<'a, A, B>(a: impl Parser<'a, A>, b: impl Parser<'a, B>) -> impl Parser<'a, B> {
+    map(pair(a, b), |(_, y)| y)
+}
+
+/// Matches two parsers in turn, keeping the value of the first.
+pub fn terminated<'a, A, B>(a: impl Parser<'a, A>, b: impl Parser<'a, B>) -> impl Parser<'a, A> {
+    map(pair(a, b), |(x, _)| x)
+}
+
+/// Matches either parser, trying the first one first. The second isn't
+/// tried if the first failed inside a `cut`.
+pub fn alt<'a, T>(a: impl Parser<'a, T>, b: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |input: Cursor<'a>| {
+        match a.parse(input) {
+            Err(e1) if !e1.committed => b.parse(input).map_err(|e2| e1.or(e2)),
+            result => result
+        }
+    }
+}
+
+/// Commits to the parser: if it fails, enclosing `alt`, `optional` and
+/// repetition combinators fail too instead of backtracking, so the error
+/// is reported where it occurred.
+pub fn cut<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |input: Cursor<'a>| {
+        parser.parse(input).map_err(|e| Failure{ committed: true, ..e })
+    }
+}
+
+/// Matches the parser if possible.
+pub fn optional<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: Cursor<'a>| {
+        match parser.parse(input) {
+            Ok((value, next)) => Ok((Some(value), next)),
+            Err(e) if e.committed => Err(e),
+            Err(_) => Ok((None, input))
+        }
+    }
+}
+
+/// Matches the parser exactly count times.
+pub fn repeat<'a, T>(count: usize, parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Cursor<'a>| {
+        let mut values = Vec::with_capacity(count);
+        let mut input = input;
+        for _ in 0..count {
+            let (value, next) = parser.parse(input)?;
+            values.push(value);
+            input = next;
+        }
+        Ok((values, input))
+    }
+}
+
+/// Matches the parser one or more times.
+pub fn many1<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Cursor<'a>| {
+        let (first, mut input) = parser.parse(input)?;
+        let mut values = vec![first];
+        loop {
+            match parser.parse(input) {
+                Ok((value, next)) => {
+                    values.push(value);
+                    input = next;
+                },
+                Err(e) if e.committed => { return Err(e); },
+                Err(_) => { return Ok((values, input)); }
+            }
+        }
+    }
+}
+
+/// Matches one or more items separated by sep.
+pub fn separated_list1<'a, T, S>(item: impl Parser<'a, T>, sep: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+    move |input: Cursor<'a>| {
+        let (first, mut input) = item.parse(input)?;
+        let mut values = vec![first];
+        loop {
+            match sep.parse(input) {
+                Ok((_, next)) => {
+                    let (value, next) = item.parse(next)?;
+                    values.push(value);
+                    input = next;
+                },
+                Err(e) if e.committed => { return Err(e); },
+                Err(_) => { return Ok((values, input)); }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range<'a>() -> impl Parser<'a, (u32, u32)> {
+        pair(unsigned("a number"), preceded(literal("-"), unsigned("a number")))
+    }
+
+    #[test]
+    fn parses_sequences() {
+        assert_eq!(parse_all("1-3", range()), Ok((1, 3)));
+        assert_eq!(parse_all("x: -12", pair(terminated(take_until(":"), literal(":")), token(signed::<i32>("a number")))), Ok(("x", -12)));
+        assert_eq!(parse_all("1,22,333", separated_list1(unsigned::<u32>("a number"), literal(","))), Ok(vec![1, 22, 333]));
+        assert_eq!(parse_all("4 5  6", many1(token(unsigned::<u8>("a number")))), Ok(vec![4, 5, 6]));
+        assert_eq!(parse_all("ab", repeat(2, any_char("a character"))), Ok(vec!['a', 'b']));
+        assert_eq!(parse_all("7", pair(optional(literal("+")), unsigned::<u8>("a number"))), Ok((None, 7)));
+    }
+
+    #[test]
+    fn reports_spans() {
+        let e = parse_all("1-x", range()).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (3, "a number", "x"));
+
+        // A number that is too large spans all its digits.
+        let e = parse_all("1-300", pair(unsigned::<u8>("a number"), preceded(literal("-"), unsigned::<u8>("a small number")))).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (3, "a small number", "300"));
+
+        // Trailing text, and text missing at the end of the line.
+        let e = parse_all("1-3 extra", range()).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (4, "end of line", " extra"));
+        let e = parse_all("1", range()).unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (2, ""));
+        let e = parse_all("abc", take_until(":")).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (4, "':'"));
+
+        // Columns count characters, not bytes.
+        let e = parse_all("é-x", pair(any_char("a character"), preceded(literal("-"), unsigned::<u8>("a number")))).unwrap_err();
+        assert_eq!(e.column, 3);
+
+        let e = parse_all("12", verify(unsigned::<u32>("a number"), |&n| n % 5 == 0, "a multiple of 5")).unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (1, "12"));
+    }
+
+    #[test]
+    fn alternatives_report_the_furthest_failure() {
+        let parser = || alt(
+            map(preceded(literal("mask = "), rest()), |s| s.len()),
+            map(preceded(literal("mem["), unsigned::<usize>("an address")), |n| n)
+        );
+        assert_eq!(parse_all("mask = XX", parser()), Ok(2));
+        assert_eq!(parse_all("mem[8", parser()), Ok(8));
+
+        let e = parse_all("nop", parser()).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (1, "'mask = ' or 'mem['"));
+        let e = parse_all("mem[x", parser()).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (5, "an address"));
+    }
+
+    #[test]
+    fn cut_prevents_backtracking() {
+        let sequence = || many1(token(unsigned::<u32>("a number")));
+        let without_cut = pair(sequence(), optional(preceded(token(literal("|")), sequence())));
+        let e = parse_all("1 2 | x", without_cut).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (4, "end of line"));
+
+        let with_cut = || pair(sequence(), optional(preceded(token(literal("|")), cut(sequence()))));
+        assert_eq!(parse_all("1 2 | 3", with_cut()), Ok((vec![1, 2], Some(vec![3]))));
+        assert_eq!(parse_all("1 2", with_cut()), Ok((vec![1, 2], None)));
+        let e = parse_all("1 2 | x", with_cut()).unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (7, "a number"));
+    }
+
+    #[test]
+    fn recognizes_and_spans() {
+        assert_eq!(parse_all("ab12", pair(recognize(take_while1(char::is_alphabetic, "a letter")), spanned(unsigned::<u8>("a number")))),
+            Ok(("ab", (12, Span{ start: 2, end: 4 }))));
+    }
+}
@@ -0,0 +1,89 @@
+use super::{Answer, Diagnostics, Json, ParseError, RunResult};
+
+/// Version of the JSON output schema, which is incremented if the schema
+/// changes in a way that is not backward compatible.
+///
+/// The output is an object with a "schema" member (this version) and a
+/// "days" member, an array with one object per day run:
+///
+/// ```text
+/// {
+///   "day": 8,
+///   "input": "day8-input.txt",
+///   "error": null,              // error parsing the input, if any
+///   "parse_ns": 51200,          // null if there is an error
+///   "parts": [
+///     { "part": 1, "answer": 2003, "error": null, "time_ns": 10300 },
+///     ...
+///   ],
+///   "details": { ... },         // day-specific values, or null
+///   "warnings": [
+///     { "file": "...", "line": 3, "column": 1, "expected": "...", "found": "..." },
+///     ...
+///   ]
+/// }
+/// ```
+///
+/// Answers are numbers or strings, and are null if the part failed.
+pub const JSON_SCHEMA_VERSION : u32 = 1;
+
+impl From<&Answer> for Json {
+    fn from(answer: &Answer) -> Json {
+        match answer {
+            Answer::Number(n) => Json::Number(*n),
+            Answer::Text(s) => Json::String(s.clone())
+        }
+    }
+}
+
+impl From<&ParseError> for Json {
+    fn from(e: &ParseError) -> Json {
+        Json::object()
+            .with("file", e.file.clone().into())
+            .with("line", e.line.into())
+            .with("column", e.column.into())
+            .with("expected", e.expected.as_str().into())
+            .with("found", e.found.as_str().into())
+    }
+}
+
+/// Converts the result of running a day on the named input to JSON.
+pub fn day_to_json(day: u32, input: &str, result: &std::io::Result<RunResult>, diag: &Diagnostics) -> Json {
+    let warnings : Vec<Json> = diag.errors().iter().map(|e| e.into()).collect();
+    let obj = Json::object()
+        .with("day", day.into())
+        .with("input", input.into());
+    match result {
+        Ok(result) => {
+            let parts : Vec<Json> = result.parts.iter().map(|part| {
+                let (answer, error) = match &part.answer {
+                    Ok(answer) => (answer.into(), Json::Null),
+                    Err(e) => (Json::Null, e.to_string().into())
+                };
+                Json::object()
+                    .with("part", part.part.into())
+                    .with("answer", answer)
+                    .with("error", error)
+                    .with("time_ns", (part.elapsed.as_nanos() as u64).into())
+            }).collect();
+            obj.with("error", Json::Null)
+                .with("parse_ns", (result.parse_time.as_nanos() as u64).into())
+                .with("parts", Json::Array(parts))
+                .with("details", result.details.clone().unwrap_or(Json::Null))
+        },
+        Err(e) => {
+            obj.with("error", e.to_string().into())
+                .with("parse_ns", Json::Null)
+                .with("parts", Json::Array(Vec::new()))
+                .with("details", Json::Null)
+        }
+    }.with("warnings", Json::Array(warnings))
+}
+
+/// Wraps the JSON objects for the days that were run in the top-level
+/// output object.
+pub fn report_to_json(days: Vec<Json>) -> Json {
+    Json::object()
+        .with("schema", JSON_SCHEMA_VERSION.into())
+        .with("days", Json::Array(days))
+}
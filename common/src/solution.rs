@@ -0,0 +1,291 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use super::log::{parse_log_option, LOG_OPTIONS_USAGE};
+use super::{InputSource, INPUT_OPTIONS_USAGE, ParseError, ParseMode, Diagnostics, Json, Rng, day_to_json, report_to_json, make_error};
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String)
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s)
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::Number(n.into())
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, u32, i64);
+
+// Unsigned 64-bit answers may be too large for an Answer, so converting
+// them returns an error instead of wrapping.
+macro_rules! answer_try_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<$t> for Answer {
+                type Error = std::io::Error;
+
+                fn try_from(n: $t) -> std::io::Result<Answer> {
+                    i64::try_from(n)
+                        .map(Answer::Number)
+                        .map_err(|_| make_error(&format!("The answer {} is too large.", n)))
+                }
+            }
+        )*
+    };
+}
+
+answer_try_from_int!(u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+/// Solution to one day's puzzle.
+///
+/// The input is parsed once and the result is passed to each part.
+pub trait Solution: Sync {
+    /// Parsed representation of the puzzle input.
+    type Input;
+
+    /// Day of the puzzle, from 1 to 25.
+    const DAY: u32;
+
+    /// Name of the input file, relative to the day's crate directory.
+    const INPUT_FILE: &'static str = "input.txt";
+
+    /// Parses the input lines. Malformed lines are reported to diag, which
+    /// determines whether parsing fails or skips them.
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError>;
+
+    fn part1(&self, input: &Self::Input) -> std::io::Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> std::io::Result<Answer>;
+
+    /// Returns intermediate values of interest for machine-readable output,
+    /// such as values found while solving the parts, as a JSON object.
+    /// Most days have none.
+    fn details(&self, _input: &Self::Input) -> Option<Json> {
+        None
+    }
+
+    /// Returns named text outputs other than the answers, such as the NFA
+    /// transitions of day 19, which snapshot tests compare with stored
+    /// copies. Most days have none.
+    fn artifacts(&self, _input: &Self::Input) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Generates a random valid input, where size is the approximate number
+    /// of items (lines, records, etc.) to generate, or None if the day has
+    /// no generator.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> Option<Generated> {
+        None
+    }
+}
+
+/// A generated input, with the answers that are known from the way it was
+/// generated. The answers to other parts must be computed by the solver.
+pub struct Generated {
+    pub lines: Vec<String>,
+    pub answers: [Option<Answer>; 2]
+}
+
+impl Generated {
+    pub fn new(lines: Vec<String>) -> Generated {
+        Generated{ lines, answers: [None, None] }
+    }
+
+    /// Sets the known answer for part 1 and/or part 2.
+    pub fn with_answers(mut self, part1: Option<Answer>, part2: Option<Answer>) -> Generated {
+        self.answers = [part1, part2];
+        self
+    }
+}
+
+/// Result of running one part of a puzzle.
+pub struct PartResult {
+    pub part: u32,
+    pub answer: std::io::Result<Answer>,
+
+    /// Time taken to solve the part, excluding parsing.
+    pub elapsed: Duration
+}
+
+/// Result of parsing an input and running one or both parts.
+pub struct RunResult {
+    /// Time taken to parse the input.
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+
+    /// Intermediate values, if requested and the day has any.
+    pub details: Option<Json>
+}
+
+/// Object-safe interface to a Solution, so solutions for different days
+/// can be stored together and selected at run time.
+pub trait Solver: Sync {
+    fn day(&self) -> u32;
+    fn input_file(&self) -> &'static str;
+
+    /// Parses the input and runs the specified part, or both parts if
+    /// part is None. An error is returned only if the input can't be parsed;
+    /// errors from the parts themselves are returned in each PartResult.
+    fn run(&self, lines: &[String], part: Option<u32>, diag: &mut Diagnostics) -> std::io::Result<RunResult>;
+
+    /// Like run, but also gets the intermediate values from Solution::details.
+    fn run_detailed(&self, lines: &[String], part: Option<u32>, diag: &mut Diagnostics) -> std::io::Result<RunResult>;
+
+    /// Parses the input and returns its artifacts; see Solution::artifacts.
+    fn artifacts(&self, lines: &[String], diag: &mut Diagnostics) -> std::io::Result<Vec<(&'static str, String)>>;
+
+    /// Generates a random input; see Solution::generate.
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated>;
+}
+
+impl<S: Solution> Solver for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn input_file(&self) -> &'static str {
+        S::INPUT_FILE
+    }
+
+    fn run(&self, lines: &[String], part: Option<u32>, diag: &mut Diagnostics) -> std::io::Result<RunResult> {
+        run_solution(self, lines, part, diag, false)
+    }
+
+    fn run_detailed(&self, lines: &[String], part: Option<u32>, diag: &mut Diagnostics) -> std::io::Result<RunResult> {
+        run_solution(self, lines, part, diag, true)
+    }
+
+    fn artifacts(&self, lines: &[String], diag: &mut Diagnostics) -> std::io::Result<Vec<(&'static str, String)>> {
+        let input = self.parse(lines, diag).map_err(|e| diag.locate(e))?;
+        Ok(Solution::artifacts(self, &input))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Solution::generate(self, rng, size)
+    }
+}
+
+// Implements Solver::run and Solver::run_detailed.
+fn run_solution<S: Solution>(solution: &S, lines: &[String], part: Option<u32>, diag: &mut Diagnostics, details: bool) -> std::io::Result<RunResult> {
+    let start = Instant::now();
+    let input = solution.parse(lines, diag).map_err(|e| diag.locate(e))?;
+    let parse_time = start.elapsed();
+    crate::debug!("day {}: parsed {} lines in {:?}", S::DAY, lines.len(), parse_time);
+
+    let mut parts = Vec::new();
+    for p in 1..=2 {
+        if part.is_none() || part == Some(p) {
+            let start = Instant::now();
+            let answer = match p {
+                1 => solution.part1(&input),
+                _ => solution.part2(&input)
+            };
+            let elapsed = start.elapsed();
+            crate::debug!("day {}: solved part {} in {:?}", S::DAY, p, elapsed);
+            parts.push(PartResult{ part: p, answer, elapsed });
+        }
+    }
+    if parts.is_empty() {
+        return Err(make_error("Part must be 1 or 2."));
+    }
+    let details = if details { solution.details(&input) } else { None };
+    Ok(RunResult{ parse_time, parts, details })
+}
+
+/// Runs both parts of a solution and prints the answers. This is the body
+/// of each day's main function, where dir is the day's crate directory.
+/// The input is selected by command-line options, and is the day's
+/// checked-in input file by default. Malformed input lines are reported
+/// as warnings, or are errors with the --strict option. With --format json,
+/// the results are written as JSON (see JSON_SCHEMA_VERSION).
+pub fn run_main(solver: &dyn Solver, dir: &str) -> std::io::Result<()> {
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    let mut source = InputSource::Default;
+    let mut mode = ParseMode::Lenient;
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--strict" {
+            mode = ParseMode::Strict;
+            continue;
+        }
+        if parse_log_option(arg) {
+            continue;
+        }
+        if arg == "--format" {
+            json = parse_output_format(iter.next())?;
+            continue;
+        }
+        match InputSource::from_arg(arg, &mut iter)? {
+            Some(s) => { source = s; },
+            None => {
+                return Err(make_error(&format!(
+                    "Usage: day{} [--strict] [--format text|json] {} {}", solver.day(), INPUT_OPTIONS_USAGE, LOG_OPTIONS_USAGE
+                )));
+            }
+        }
+    }
+
+    let name = source.name(solver.input_file());
+    let mut diag = Diagnostics::new(mode, Some(&name));
+    if json {
+        // Print any error reading or parsing the input in the JSON output,
+        // and also return it so the exit status shows the failure.
+        let results = source.read_lines(Path::new(dir), solver.input_file())
+            .and_then(|lines| solver.run_detailed(&lines, None, &mut diag));
+        println!("{}", report_to_json(vec![day_to_json(solver.day(), &name, &results, &diag)]));
+        return results.map(|_| ());
+    }
+
+    let lines = source.read_lines(Path::new(dir), solver.input_file())?;
+    let results = solver.run(&lines, None, &mut diag);
+    print_diagnostics(&diag);
+    for result in results?.parts {
+        match result.answer {
+            Ok(answer) => println!("Part {}: {}", result.part, answer),
+            Err(e) => println!("Part {}: error: {}", result.part, e)
+        }
+    }
+    Ok(())
+}
+
+/// Parses the value of the --format option, returning true for JSON.
+pub fn parse_output_format(value: Option<&String>) -> std::io::Result<bool> {
+    match value.map(|s| s.as_str()) {
+        Some("text") | Some("table") => Ok(false),
+        Some("json") => Ok(true),
+        _ => Err(make_error("--format must be followed by text or json."))
+    }
+}
+
+/// Logs the errors collected in lenient mode as warnings.
+pub fn print_diagnostics(diag: &Diagnostics) {
+    for e in diag.errors() {
+        crate::warn!("{}", e);
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+use aoc_common::{Answer, Generated, Rng, find_all_k_sums};
+
+const SUM : i64 = 2020;
+
+// Largest filler number. Fillers are greater than the sum, so they can't
+// be part of a pair or triple, and there are enough of them for any size.
+const MAX_FILLER : i64 = SUM + 3995;
+
+// Generates size distinct numbers (at least 5 and at most 4000) containing
+// exactly one pair and one triple that sum to 2020.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let size = size.clamp(5, (MAX_FILLER - SUM) as usize + 5);
+
+    // Plant the pair and the triple, retrying if the numbers aren't
+    // distinct or form another pair or triple.
+    let mut values = loop {
+        let a = rng.range(1, SUM / 2 - 1);
+        let x = rng.range(1, SUM / 3 - 1);
+        let y = rng.range(x + 1, (SUM - x) / 2 - 1);
+        let values = vec![a, SUM - a, x, y, SUM - x - y];
+        let set : HashSet<i64> = values.iter().copied().collect();
+        if set.len() == values.len() && find_all_k_sums(&values, 2, SUM).len() == 1 && find_all_k_sums(&values, 3, SUM).len() == 1 {
+            break values;
+        }
+    };
+    let pair_product = values[0] * values[1];
+    let triple_product = values[2] * values[3] * values[4];
+
+    // Add fillers drawn without replacement.
+    let mut fillers : Vec<i64> = (SUM + 1..=MAX_FILLER).collect();
+    rng.shuffle(&mut fillers);
+    values.extend_from_slice(&fillers[..size - values.len()]);
+
+    rng.shuffle(&mut values);
+    let lines = values.iter().map(|n| n.to_string()).collect();
+    Generated::new(lines).with_answers(
+        Some(Answer::from(pair_product)),
+        Some(Answer::from(triple_product))
+    )
+}
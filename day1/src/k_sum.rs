@@ -0,0 +1,94 @@
+//! Choosing among the combinations of k entries that sum to a target.
+
+use aoc_common::{find_k_sum, find_all_k_sums};
+
+/// Which of the combinations found by `aoc_common::find_all_k_sums` to
+/// keep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Select {
+    /// The first combination, in lexicographic order.
+    First,
+
+    /// Every combination.
+    All,
+
+    /// The first combination with the smallest product.
+    MinProduct,
+
+    /// The first combination with the largest product.
+    MaxProduct
+}
+
+impl Select {
+    /// Returns the selection with the specified name: "first", "all",
+    /// "min-product" or "max-product".
+    pub fn from_name(name : &str) -> Option<Select> {
+        match name {
+            "first" => Some(Select::First),
+            "all" => Some(Select::All),
+            "min-product" => Some(Select::MinProduct),
+            "max-product" => Some(Select::MaxProduct),
+            _ => None
+        }
+    }
+}
+
+/// Returns the combinations of k values that add up to target chosen by
+/// select. The first one is found by `find_k_sum`, which stops there, and
+/// the others are chosen from every combination. The sums of k values must
+/// not overflow.
+pub fn find_selected(values : &[i64], k : usize, target : i64, selection : Select) -> Vec<Vec<i64>> {
+    if selection == Select::First {
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        return find_k_sum(&sorted, k, target).into_iter().collect();
+    }
+    select(find_all_k_sums(values, k, target), selection)
+}
+
+/// Returns the combinations chosen by select from the output of
+/// `find_all_k_sums`. Products are computed with 128-bit integers,
+/// saturating on overflow.
+pub fn select(combos : Vec<Vec<i64>>, select : Select) -> Vec<Vec<i64>> {
+    let product = |combo : &Vec<i64>| combo.iter().fold(1i128, |p, &n| p.saturating_mul(n as i128));
+    let best = match select {
+        Select::All => { return combos; },
+        Select::First => combos.into_iter().next(),
+        Select::MinProduct => combos.into_iter().min_by_key(product),
+        // max_by_key returns the last of equal elements, so search in
+        // reverse to get the first.
+        Select::MaxProduct => combos.into_iter().rev().max_by_key(product)
+    };
+    best.into_iter().collect()
+}
+
+/// Returns the product of the values, or None if it overflows.
+pub fn product(combo : &[i64]) -> Option<i64> {
+    combo.iter().try_fold(1i64, |p, &n| p.checked_mul(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_combinations() {
+        let combos = find_all_k_sums(&[1, 2, 3, 4, 5, 6], 2, 7);
+        assert_eq!(combos, vec![vec![1, 6], vec![2, 5], vec![3, 4]]);
+        assert_eq!(select(combos.clone(), Select::First), vec![vec![1, 6]]);
+        assert_eq!(select(combos.clone(), Select::MinProduct), vec![vec![1, 6]]);
+        assert_eq!(select(combos.clone(), Select::MaxProduct), vec![vec![3, 4]]);
+        assert_eq!(select(combos, Select::All).len(), 3);
+        assert_eq!(select(vec![vec![-2, 2], vec![-4, 4]], Select::MinProduct), vec![vec![-4, 4]]);
+        assert!(select(Vec::new(), Select::First).is_empty());
+    }
+
+    #[test]
+    fn finds_selected_combinations() {
+        let values = [6, 5, 4, 3, 2, 1];
+        assert_eq!(find_selected(&values, 2, 7, Select::First), vec![vec![1, 6]]);
+        assert_eq!(find_selected(&values, 2, 7, Select::MaxProduct), vec![vec![3, 4]]);
+        assert_eq!(find_selected(&values, 3, 7, Select::All), vec![vec![1, 2, 4]]);
+        assert!(find_selected(&values, 3, 20, Select::First).is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+//! Day 1: Report Repair. Finds the pair and the triple of expense report
+//! entries that sum to 2020.
+
+#![warn(missing_docs)]
+
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, find_k_sum, parse_numbers, make_error};
+
+mod generate;
+pub mod k_sum;
+pub mod stream;
+pub mod subset_sum;
+
+/// The solution for day 1.
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+    const DAY: u32 = 1;
+    const INPUT_FILE: &'static str = "day1-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<i32>, ParseError> {
+        let mut v = parse_numbers::<i32>(lines, diag)?;
+
+        // Sort the numbers, as find_k_sum requires.
+        v.sort_unstable();
+        Ok(v)
+    }
+
+    fn part1(&self, v: &Vec<i32>) -> std::io::Result<Answer> {
+        // Find the first pair that sums to 2020.
+        match find_pair(&v[..], 2020) {
+            Some((a, b)) => Ok(Answer::from(a * b)),
+            None => Err(make_error("No pair found that sums to 2020."))
+        }
+    }
+
+    fn part2(&self, v: &Vec<i32>) -> std::io::Result<Answer> {
+        match find_triple(&v[..], 2020) {
+            Some((a, b, c)) => Ok(Answer::from(a * b * c)),
+            None => Err(make_error("No triple found that sums to 2020."))
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Finds a pair of values in v that add up to sum, where v is sorted.
+pub fn find_pair(v: &[i32], sum: i32) -> Option<(i32, i32)> {
+    find_k_sum(v, 2, sum).map(|pair| (pair[0], pair[1]))
+}
+
+/// Finds three values in v that add up to sum, where v is sorted.
+pub fn find_triple(v: &[i32], sum : i32) -> Option<(i32, i32, i32)> {
+    find_k_sum(v, 3, sum).map(|triple| (triple[0], triple[1], triple[2]))
+}
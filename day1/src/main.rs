@@ -1,73 +1,122 @@
-use std::fs;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
+use std::path::Path;
+use aoc_common::{Diagnostics, InputSource, ParseMode, Solver, INPUT_OPTIONS_USAGE, parse_field, parse_numbers, print_diagnostics, make_error};
+use day1::k_sum::{self, Select};
+use day1::stream::SumStream;
+use day1::subset_sum::subset_sum;
 
-fn main() -> std::io::Result<()> {
-
-    // Read the file into a vector of i32.
-    let mut v = read_numbers("day1-input.txt")?;
-
-    // Sort the numbers, so we can binary_search later.
-    v.sort_unstable();
+const USAGE : &str = "Usage: day1 [--k <n> | --subset | --stream [--triples]] [--target <n>] [--select first|all|min-product|max-product] [--strict]";
 
-    // Find the first pair that sums to 2020.
-    if let Some((a, b)) = find_pair(&v[..], 2020) {
-        println!("{} * {} = {}", a, b, a * b);
-    }
-    else {
-        println!("No pair found that sums to 2020.");
-    }
-
-    if let Some((a, b, c)) = find_triple(&v[..], 2020) {
-        println!("{} * {} * {} = {}", a, b, c, a * b * c);
+fn main() -> std::io::Result<()> {
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    let k_sum_options = ["--k", "--subset", "--stream", "--triples", "--target", "--select"];
+    if args.iter().any(|arg| k_sum_options.contains(&arg.as_str())) {
+        k_sum_command(&args)
     }
     else {
-        println!("No triple found that sums to 2020.");
+        aoc_common::run_main(&day1::Day1, env!("CARGO_MANIFEST_DIR"))
     }
-
-    Ok(())
 }
 
-fn find_pair(v: &[i32], sum: i32) -> Option<(i32, i32)> {
-    for i in 1..v.len() {
-
-        // Let a be the value before the slice starting at i.
-        let a = v[i - 1];
+// Prints the combinations of k entries of the selected input that sum to
+// the target, which are 2 and 2020 by default, as chosen by --select. With
+// --subset, counts the subsets of any size that sum to the target instead,
+// and prints one of the smallest. With --stream, reads entries from
+// standard input as they arrive; see stream_command.
+fn k_sum_command(args : &[String]) -> std::io::Result<()> {
+    let usage = || make_error(&format!("{} {}", USAGE, INPUT_OPTIONS_USAGE));
+    let mut k = 2;
+    let mut subset = false;
+    let mut stream = false;
+    let mut triples = false;
+    let mut target = 2020;
+    let mut selection = Select::All;
+    let mut source = InputSource::Default;
+    let mut mode = ParseMode::Lenient;
 
-        // Let b the other member of the pair such that a + b == sum.
-        let b = sum - a;
-
-        if b < a {
-            // Be can't possibly exist for values >= a.
-            break;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--k" => { k = iter.next().and_then(|s| s.parse().ok()).ok_or_else(usage)?; },
+            "--subset" => { subset = true; },
+            "--stream" => { stream = true; },
+            "--triples" => { triples = true; },
+            "--target" => { target = iter.next().and_then(|s| s.parse().ok()).ok_or_else(usage)?; },
+            "--select" => { selection = iter.next().and_then(|s| Select::from_name(s)).ok_or_else(usage)?; },
+            "--strict" => { mode = ParseMode::Strict; },
+            _ => { source = InputSource::from_arg(arg, &mut iter)?.ok_or_else(usage)?; }
         }
+    }
 
-        // If be exists then we're done.
-        if let Ok(_i) = v[i..].binary_search(&b) {
-            return Some((a, b));
+    if triples && !stream {
+        return Err(make_error("--triples can only be used with --stream."));
+    }
+    if stream {
+        if source != InputSource::Default && source != InputSource::Stdin {
+            return Err(make_error("--stream reads standard input."));
         }
+        return stream_command(target, triples, mode);
     }
-    None
-}
 
-fn find_triple(v: &[i32], sum : i32) -> Option<(i32, i32, i32)> {
-    for i in 1..v.len() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let name = source.name(day1::Day1.input_file());
+    let lines = source.read_lines(dir, day1::Day1.input_file())?;
+    let mut diag = Diagnostics::new(mode, Some(&name));
+    let values = parse_numbers::<i64>(&lines, &mut diag).map_err(|e| diag.locate(e))?;
+    print_diagnostics(&diag);
 
-        // Let a be the value before the slice starting at i.
-        let a = v[i - 1];
+    if subset {
+        let sums = subset_sum(&values, target)?;
+        if let Some(smallest) = &sums.smallest {
+            let terms : Vec<String> = smallest.iter().map(|n| n.to_string()).collect();
+            println!("{} (smallest, {} {})", terms.join(" + "), smallest.len(), if smallest.len() == 1 { "entry" } else { "entries" });
+        }
+        println!("{} subset{} of entries sum to {}.", sums.count, if sums.count == 1 { "" } else { "s" }, target);
+        return Ok(());
+    }
 
-        // Look for a pair (b, c) that add up to (sum - a).
-        if let Some((b, c)) = find_pair(&v[i..], sum - a) {
-            return Some((a, b, c));
+    let combos = k_sum::find_selected(&values, k, target, selection);
+    for combo in &combos {
+        let terms : Vec<String> = combo.iter().map(|n| n.to_string()).collect();
+        match k_sum::product(combo) {
+            Some(product) => println!("{} (product {})", terms.join(" + "), product),
+            None => println!("{} (product overflows)", terms.join(" + "))
         }
     }
-    None
+    println!("{} combination{} of {} entries sum to {}.", combos.len(), if combos.len() == 1 { "" } else { "s" }, k, target);
+    Ok(())
 }
 
-fn read_numbers(path: &str) -> std::io::Result<Vec::<i32>> {
-    let mut v = Vec::<i32>::new();
-    for line in BufReader::new(fs::File::open(path)?).lines() {
-        let s = line?;
-        if let Ok(n) = s.parse::<i32>() { v.push(n); }
+// Reads entries from standard input, and prints each pair (and triple, if
+// triples is true) that sums to the target as soon as its last entry is
+// read, rather than waiting for the end of the input.
+fn stream_command(target : i64, triples : bool, mode : ParseMode) -> std::io::Result<()> {
+    let name = InputSource::Stdin.name(day1::Day1.input_file());
+    let mut diag = Diagnostics::new(mode, Some(&name));
+    let mut stream = SumStream::new(target, triples);
+    let mut count = 0;
+    let stdin = std::io::stdin();
+    for (i, line) in stdin.lock().lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let n = match parse_field::<i64>(&line, &line, "a number") {
+            Ok(n) => n,
+            Err(e) => {
+                diag.report(e.at_line(i + 1)).map_err(|e| diag.locate(e))?;
+                if let Some(e) = diag.errors().last() {
+                    aoc_common::warn!("{}", e);
+                }
+                continue;
+            }
+        };
+        for combo in stream.push(n) {
+            let terms : Vec<String> = combo.iter().map(|n| n.to_string()).collect();
+            println!("{} = {} (line {})", terms.join(" + "), target, i + 1);
+            count += 1;
+        }
     }
-    Ok(v)
+    println!("{} combination{} sum to {}.", count, if count == 1 { "" } else { "s" }, target);
+    Ok(())
 }
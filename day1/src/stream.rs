@@ -0,0 +1,124 @@
+//! Finding the pairs and triples that sum to a target as entries arrive.
+
+use std::collections::HashMap;
+
+/// Entries seen so far, indexed so that each new entry finds the pairs and
+/// triples it completes without sorting or searching the earlier ones.
+pub struct SumStream {
+    target : i64,
+
+    // How many times each value has been seen, and the distinct values in
+    // the order they were first seen.
+    counts : HashMap<i64, usize>,
+    distinct : Vec<i64>,
+
+    // The distinct pairs of values seen, by sum, if triples are wanted.
+    pairs_by_sum : Option<HashMap<i64, Vec<(i64, i64)>>>
+}
+
+impl SumStream {
+    /// Returns an empty stream that looks for pairs, and triples too if
+    /// triples is true, that sum to target.
+    pub fn new(target : i64, triples : bool) -> SumStream {
+        SumStream{
+            target,
+            counts : HashMap::new(),
+            distinct : Vec::new(),
+            pairs_by_sum : if triples { Some(HashMap::new()) } else { None }
+        }
+    }
+
+    /// Adds an entry, and returns the combinations that it completes: the
+    /// pairs, then the triples, each in ascending order. Each combination
+    /// of values is returned once, when its last value arrives, so an entry
+    /// that repeats an earlier one only completes combinations that need
+    /// the value more times.
+    ///
+    /// Finding pairs takes O(1) time. Triples take O(d) time when the entry
+    /// is a new value, where d is the number of distinct values so far, plus
+    /// the number of pairs that complete a triple, and O(d^2) memory. The
+    /// sums must not overflow.
+    pub fn push(&mut self, n : i64) -> Vec<Vec<i64>> {
+        let seen = self.counts.get(&n).copied().unwrap_or(0);
+        let mut combos = Vec::new();
+
+        // A pair is new if this is the first n, or the second n of n + n.
+        let m = self.target - n;
+        let needed = if m == n { 1 } else { 0 };
+        if seen == needed && self.counts.contains_key(&m) {
+            combos.push(if m < n { vec![m, n] } else { vec![n, m] });
+        }
+
+        if let Some(pairs_by_sum) = &mut self.pairs_by_sum {
+            // A triple is new if it needs every n seen so far, plus this one.
+            if let Some(pairs) = pairs_by_sum.get(&(self.target - n)) {
+                let mut triples = Vec::new();
+                for &(a, b) in pairs {
+                    let needed = [a, b].iter().filter(|&&v| v == n).count();
+                    if seen == needed {
+                        let mut triple = vec![a, b, n];
+                        triple.sort_unstable();
+                        triples.push(triple);
+                    }
+                }
+                triples.sort_unstable();
+                combos.extend(triples);
+            }
+
+            // Index the pairs this entry makes with the earlier ones.
+            if seen == 0 {
+                for &v in &self.distinct {
+                    let pair = if v < n { (v, n) } else { (n, v) };
+                    pairs_by_sum.entry(v + n).or_default().push(pair);
+                }
+            }
+            else if seen == 1 {
+                pairs_by_sum.entry(n + n).or_default().push((n, n));
+            }
+        }
+
+        if seen == 0 {
+            self.distinct.push(n);
+        }
+        *self.counts.entry(n).or_insert(0) += 1;
+        combos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::find_all_k_sums;
+
+    #[test]
+    fn reports_each_combination_once() {
+        let mut stream = SumStream::new(2020, true);
+        let example = [1721, 979, 366, 299, 675, 1456];
+        let found : Vec<Vec<Vec<i64>>> = example.iter().map(|&n| stream.push(n)).collect();
+        assert_eq!(found, vec![vec![], vec![], vec![], vec![vec![299, 1721]], vec![vec![366, 675, 979]], vec![]]);
+
+        let mut stream = SumStream::new(10, false);
+        assert_eq!(stream.push(5), Vec::<Vec<i64>>::new());
+        assert_eq!(stream.push(5), vec![vec![5, 5]]);
+        assert_eq!(stream.push(5), Vec::<Vec<i64>>::new());
+    }
+
+    #[test]
+    fn finds_what_find_all_k_sums_finds() {
+        let values = [5, -3, 2, 2, 8, 0, 7, 2, -1, 10, 3, 3];
+        for target in -4..=25 {
+            let mut stream = SumStream::new(target, true);
+            let mut pairs = Vec::new();
+            let mut triples = Vec::new();
+            for &n in &values {
+                for combo in stream.push(n) {
+                    if combo.len() == 2 { pairs.push(combo); } else { triples.push(combo); }
+                }
+            }
+            pairs.sort_unstable();
+            triples.sort_unstable();
+            assert_eq!(pairs, find_all_k_sums(&values, 2, target), "target = {}", target);
+            assert_eq!(triples, find_all_k_sums(&values, 3, target), "target = {}", target);
+        }
+    }
+}
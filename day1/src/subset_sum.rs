@@ -0,0 +1,385 @@
+//! Finding subsets of any size that sum to a target.
+
+use std::collections::HashMap;
+use aoc_common::make_error;
+
+// Most partial sums kept by the search: the sums of the subsets of each
+// half of the values, or the partial sums of all of them. This bounds the
+// memory used to a few hundred megabytes.
+const MAX_TABLE_LEN : usize = 1 << 21;
+
+/// The subsets of some values that add up to a target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubsetSums {
+    /// Number of distinct non-empty subsets, saturating at `u128::MAX`.
+    pub count : u128,
+
+    /// One of the subsets with the fewest values, in ascending order, or
+    /// None if there are no subsets.
+    pub smallest : Option<Vec<i64>>
+}
+
+// The subsets of the values seen so far that reach one partial sum.
+#[derive(Clone, Copy)]
+struct Reach {
+    count : u128,
+
+    // Size of the smallest subset, and its last step in the steps vector.
+    size : usize,
+    step : usize
+}
+
+// The last values added to a subset: times copies of value, after the
+// subset ending with the parent step, if any.
+struct Step {
+    value : i64,
+    times : usize,
+    parent : Option<usize>
+}
+
+/// Finds the non-empty subsets of values that add up to target, where a
+/// subset can have each value as many times as it occurs, and subsets with
+/// the same values are the same subset. The values can be in any order,
+/// and can be negative.
+///
+/// If there are few enough values, the subsets of each half of them are
+/// listed with their sums, and matched up (meet-in-the-middle), so n values
+/// take about O(2^(n / 2)) time whatever their size. Otherwise, the search
+/// goes by partial sums (see `by_partial_sums`), which is fast if the values
+/// are small, as in the puzzle. It is an error if neither search would fit
+/// in `MAX_TABLE_LEN` sums, rather than running out of memory.
+pub fn subset_sum(values : &[i64], target : i64) -> std::io::Result<SubsetSums> {
+    let mut counts : HashMap<i64, usize> = HashMap::new();
+    for &n in values {
+        *counts.entry(n).or_insert(0) += 1;
+    }
+    let mut counts : Counts = counts.into_iter().collect();
+    counts.sort_unstable();
+
+    match split_halves(&counts) {
+        Some((left, right)) => Ok(meet_in_the_middle(&left, &right, target)),
+        None => by_partial_sums(&counts, target, MAX_TABLE_LEN).ok_or_else(|| make_error(&format!(
+            "Too many entries to search: their subsets have more than {} partial sums.", MAX_TABLE_LEN
+        )))
+    }
+}
+
+// Distinct values, and how many times each occurs.
+type Counts = Vec<(i64, usize)>;
+
+// Splits the distinct values and their counts into two halves whose numbers
+// of subsets are about the same, or returns None if either half would have
+// more than MAX_TABLE_LEN subsets.
+fn split_halves(counts : &[(i64, usize)]) -> Option<(Counts, Counts)> {
+    let mut by_choices = counts.to_vec();
+    by_choices.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    let (mut left_len, mut right_len) = (1usize, 1usize);
+    for (value, count) in by_choices {
+        // Each value can be in a subset 0 to count times.
+        let choices = count.checked_add(1)?;
+        if left_len <= right_len {
+            left_len = left_len.checked_mul(choices).filter(|&len| len <= MAX_TABLE_LEN)?;
+            left.push((value, count));
+        }
+        else {
+            right_len = right_len.checked_mul(choices).filter(|&len| len <= MAX_TABLE_LEN)?;
+            right.push((value, count));
+        }
+    }
+    Some((left, right))
+}
+
+// A subset of half of the values: its sum, size, and how many times it has
+// each value, as a number whose digits (in mixed radix, count + 1 for each
+// value) are the times.
+#[derive(Clone, Copy)]
+struct HalfSubset {
+    sum : i128,
+    size : usize,
+    choice : usize
+}
+
+// Returns every subset of the values, including the empty one, in order of
+// sum and then size.
+fn half_subsets(counts : &[(i64, usize)]) -> Vec<HalfSubset> {
+    let mut subsets = vec![HalfSubset{ sum : 0, size : 0, choice : 0 }];
+    let mut radix = 1;
+    for &(value, count) in counts {
+        let mut next = Vec::with_capacity(subsets.len() * (count + 1));
+        for subset in &subsets {
+            for times in 0..=count {
+                next.push(HalfSubset{
+                    sum : subset.sum + value as i128 * times as i128,
+                    size : subset.size + times,
+                    choice : subset.choice + times * radix
+                });
+            }
+        }
+        subsets = next;
+        radix *= count + 1;
+    }
+    subsets.sort_unstable_by_key(|subset| (subset.sum, subset.size, subset.choice));
+    subsets
+}
+
+// Appends the values of a half's subset to subset.
+fn push_half_subset(counts : &[(i64, usize)], choice : usize, subset : &mut Vec<i64>) {
+    let mut choice = choice;
+    for &(value, count) in counts {
+        subset.extend(std::iter::repeat_n(value, choice % (count + 1)));
+        choice /= count + 1;
+    }
+}
+
+// Finds the subsets by matching each subset of the left half with the
+// subsets of the right half that complete the sum.
+fn meet_in_the_middle(left : &[(i64, usize)], right : &[(i64, usize)], target : i64) -> SubsetSums {
+    let target = target as i128;
+    let left_subsets = half_subsets(left);
+    let right_subsets = half_subsets(right);
+
+    // Both halves are sorted by sum, so go through the left sums upwards and
+    // the right sums downwards. Within a sum, the smallest subset is first.
+    let mut count : u128 = 0;
+    let mut best : Option<(usize, HalfSubset, HalfSubset)> = None;
+    let mut r_end = right_subsets.len();
+    let mut l = 0;
+    while l < left_subsets.len() {
+        let sum = left_subsets[l].sum;
+        let l_end = l + left_subsets[l..].iter().take_while(|s| s.sum == sum).count();
+        let complement = target - sum;
+        while r_end > 0 && right_subsets[r_end - 1].sum > complement {
+            r_end -= 1;
+        }
+        let r = r_end - right_subsets[..r_end].iter().rev().take_while(|s| s.sum == complement).count();
+        if r < r_end {
+            count = count.saturating_add((l_end - l) as u128 * (r_end - r) as u128);
+
+            // The smallest pair, unless both are empty; then the smallest
+            // with one of them replaced by the next smallest of its sum.
+            let mut pairs = vec![(left_subsets[l], right_subsets[r])];
+            if left_subsets[l].size + right_subsets[r].size == 0 {
+                pairs.clear();
+                if l + 1 < l_end {
+                    pairs.push((left_subsets[l + 1], right_subsets[r]));
+                }
+                if r + 1 < r_end {
+                    pairs.push((left_subsets[l], right_subsets[r + 1]));
+                }
+            }
+            for (a, b) in pairs {
+                if best.is_none_or(|(size, _, _)| a.size + b.size < size) {
+                    best = Some((a.size + b.size, a, b));
+                }
+            }
+        }
+        l = l_end;
+    }
+
+    // The empty subset is counted if the target is 0, but isn't a solution.
+    if target == 0 {
+        count -= 1;
+    }
+    let smallest = best.map(|(size, a, b)| {
+        let mut subset = Vec::with_capacity(size);
+        push_half_subset(left, a.choice, &mut subset);
+        push_half_subset(right, b.choice, &mut subset);
+        subset.sort_unstable();
+        subset
+    });
+    SubsetSums{ count, smallest }
+}
+
+// Finds the subsets by working through the distinct values in ascending
+// order, keeping the number of subsets and the smallest subset for each
+// partial sum. Partial sums are dropped when the values that are left can't
+// bring them to the target, so with n distinct values and s partial sums
+// kept, it takes O(n * s) time. For non-negative values, s is at most
+// target + 1. Returns None if there would be more than max_len partial
+// sums, or steps to record.
+fn by_partial_sums(counts : &[(i64, usize)], target : i64, max_len : usize) -> Option<SubsetSums> {
+    // Sums are 128-bit, so they can't overflow. low and high are the least
+    // and greatest amounts the values that are left can add.
+    let target = target as i128;
+    let mut low : i128 = counts.iter().filter(|(n, _)| *n < 0).map(|&(n, c)| n as i128 * c as i128).sum();
+    let mut high : i128 = counts.iter().filter(|(n, _)| *n > 0).map(|&(n, c)| n as i128 * c as i128).sum();
+
+    let mut reach : HashMap<i128, Reach> = HashMap::new();
+    let mut steps : Vec<Step> = Vec::new();
+    for &(value, count) in counts {
+        let total = value as i128 * count as i128;
+        if value < 0 { low -= total; } else { high -= total; }
+        let useful = |sum : i128| (low..=high).contains(&(target - sum));
+
+        // Each partial sum so far is kept, if it's still useful, and gets
+        // 1 to count copies of the value added, as does the empty subset,
+        // which isn't in reach as it isn't a solution. The sums are visited
+        // in order, so the smallest subset chosen among equals is the same
+        // on every run.
+        let prev = reach.iter().map(|(&sum, &r)| (sum, Some(r)));
+        let mut starts : Vec<(i128, Option<Reach>)> = prev.chain(std::iter::once((0, None))).collect();
+        starts.sort_unstable_by_key(|&(sum, r)| (sum, r.is_none()));
+
+        let mut next : HashMap<i128, Reach> = HashMap::new();
+        for (sum, r) in starts {
+            if let Some(r) = r.filter(|_| useful(sum)) {
+                merge(&mut next, sum, r);
+            }
+            for times in 1..=count {
+                let new_sum = sum + value as i128 * times as i128;
+                if !useful(new_sum) {
+                    continue;
+                }
+                let size = r.map_or(0, |r| r.size) + times;
+                let entry = next.get(&new_sum);
+                let step = if entry.is_none_or(|e| size < e.size) {
+                    steps.push(Step{ value, times, parent : r.map(|r| r.step) });
+                    steps.len() - 1
+                }
+                else {
+                    usize::MAX
+                };
+                merge(&mut next, new_sum, Reach{ count : r.map_or(1, |r| r.count), size, step });
+            }
+            if next.len() > max_len || steps.len() > max_len {
+                return None;
+            }
+        }
+        reach = next;
+    }
+
+    match reach.get(&target) {
+        Some(r) => {
+            let mut smallest = Vec::with_capacity(r.size);
+            let mut step = Some(r.step);
+            while let Some(i) = step {
+                smallest.extend(std::iter::repeat_n(steps[i].value, steps[i].times));
+                step = steps[i].parent;
+            }
+            smallest.sort_unstable();
+            Some(SubsetSums{ count : r.count, smallest : Some(smallest) })
+        },
+        None => Some(SubsetSums{ count : 0, smallest : None })
+    }
+}
+
+// Adds the subsets of r to those reaching sum, keeping the smaller of the
+// smallest subsets, or the existing one if they are the same size.
+fn merge(reach : &mut HashMap<i128, Reach>, sum : i128, r : Reach) {
+    match reach.get_mut(&sum) {
+        Some(e) => {
+            e.count = e.count.saturating_add(r.count);
+            if r.size < e.size {
+                e.size = r.size;
+                e.step = r.step;
+            }
+        },
+        None => { reach.insert(sum, r); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Finds the subsets by trying every subset of positions.
+    fn brute_force(values : &[i64], target : i64) -> (u128, Option<usize>) {
+        let mut subsets = Vec::new();
+        for mask in 1u32..(1 << values.len()) {
+            let mut subset : Vec<i64> = (0..values.len()).filter(|&i| mask & (1 << i) != 0).map(|i| values[i]).collect();
+            if subset.iter().sum::<i64>() == target {
+                subset.sort_unstable();
+                subsets.push(subset);
+            }
+        }
+        subsets.sort_unstable();
+        subsets.dedup();
+        (subsets.len() as u128, subsets.iter().map(|s| s.len()).min())
+    }
+
+    // Returns the distinct values and their counts, in ascending order.
+    fn distinct_counts(values : &[i64]) -> Counts {
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        let mut counts : Counts = Vec::new();
+        for n in sorted {
+            match counts.last_mut() {
+                Some((m, count)) if *m == n => { *count += 1; },
+                _ => counts.push((n, 1))
+            }
+        }
+        counts
+    }
+
+    // Checks that a subset has the target sum, is sorted, and is made of the
+    // values, each used at most as many times as it occurs.
+    fn assert_subset_of(subset : &[i64], values : &[i64], target : i64) {
+        assert_eq!(subset.iter().map(|&n| n as i128).sum::<i128>(), target as i128);
+        assert!(subset.windows(2).all(|w| w[0] <= w[1]));
+        for &n in subset {
+            assert!(subset.iter().filter(|&&m| m == n).count() <= values.iter().filter(|&&m| m == n).count());
+        }
+    }
+
+    #[test]
+    fn counts_subsets() {
+        let example = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(subset_sum(&example, 2020).unwrap(), SubsetSums{ count : 2, smallest : Some(vec![299, 1721]) });
+        assert_eq!(subset_sum(&example, 1).unwrap(), SubsetSums{ count : 0, smallest : None });
+
+        // Both searches give the same results as trying every subset.
+        let values = [5, -3, 2, 2, 8, 0, 7, 2, -1, 10, 3, 3];
+        let counts = distinct_counts(&values);
+        let (left, right) = split_halves(&counts).unwrap();
+        for target in -5..=42 {
+            let expected = brute_force(&values, target);
+            for sums in [meet_in_the_middle(&left, &right, target), by_partial_sums(&counts, target, MAX_TABLE_LEN).unwrap()] {
+                assert_eq!((sums.count, sums.smallest.as_ref().map(|s| s.len())), expected, "target = {}", target);
+                if let Some(smallest) = sums.smallest {
+                    assert_subset_of(&smallest, &values, target);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handles_large_amounts() {
+        let values = [i64::MAX, i64::MAX, i64::MIN, 1];
+        assert_eq!(subset_sum(&values, 0).unwrap().count, 1);
+        assert_eq!(subset_sum(&values, i64::MAX).unwrap().smallest, Some(vec![i64::MAX]));
+        assert_eq!(subset_sum(&values, -1).unwrap().smallest, Some(vec![i64::MIN, i64::MAX]));
+        let counts = distinct_counts(&values);
+        assert_eq!(by_partial_sums(&counts, 0, MAX_TABLE_LEN).unwrap().count, 1);
+        assert_eq!(by_partial_sums(&counts, -1, MAX_TABLE_LEN).unwrap().smallest, Some(vec![i64::MIN, i64::MAX]));
+
+        // An expense report of 30 amounts up to a billion has too many
+        // partial sums to keep, but its halves are small.
+        let mut rng = aoc_common::Rng::new(7);
+        let values : Vec<i64> = (0..30).map(|_| rng.range(1, 1_000_000_000)).collect();
+        let target : i64 = values[..12].iter().sum();
+        let sums = subset_sum(&values, target).unwrap();
+        assert!(sums.count >= 1);
+        assert_subset_of(sums.smallest.as_ref().unwrap(), &values, target);
+        assert!(sums.smallest.unwrap().len() <= 12);
+
+        // Amounts whose subsets all have different sums.
+        let values : Vec<i64> = (0..30).map(|i| 1_000_003 << i).collect();
+        let sums = subset_sum(&values, values[3] + values[17] + values[29]).unwrap();
+        assert_eq!(sums, SubsetSums{ count : 1, smallest : Some(vec![values[3], values[17], values[29]]) });
+        assert_eq!(subset_sum(&values, 1).unwrap().count, 0);
+    }
+
+    #[test]
+    fn searches_many_small_values() {
+        // Too many values to split, but few partial sums: the subsets of
+        // 1 to 100 that sum to 20 are its partitions into distinct parts.
+        let values : Vec<i64> = (1..=100).collect();
+        assert!(split_halves(&distinct_counts(&values)).is_none());
+        assert_eq!(subset_sum(&values, 20).unwrap(), SubsetSums{ count : 64, smallest : Some(vec![20]) });
+
+        // Too many partial sums to keep.
+        let values : Vec<i64> = (0..100).map(|i| 1_000_003 * (i + 1) * (i + 7)).collect();
+        assert!(by_partial_sums(&distinct_counts(&values), 123_456_789_000, 10_000).is_none());
+    }
+}
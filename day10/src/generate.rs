@@ -0,0 +1,56 @@
+use aoc_common::{Answer, Generated, Rng};
+
+// Generates up to size adapters whose joltages differ by 1 or 3 when
+// sorted. The adapters form runs of 1-jolt differences separated by 3-jolt
+// differences, and the number of arrangements is the product of the ways
+// to arrange each run. Generation stops early if the next run would make
+// the number of arrangements too large for an answer.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut jolts = Vec::new();
+    let mut jolt = 0;
+    let mut diffs1 = 0;
+    let mut diffs3 = 0;
+    let mut arrangements : i64 = 1;
+    loop {
+        // A run starts after a 3-jolt difference, except the first.
+        let run = rng.range(0, 4) as usize;
+        let step = if jolts.is_empty() { 0 } else { 1 };
+        if jolts.len() + step + run > size.max(1) {
+            break;
+        }
+        arrangements = match arrangements.checked_mul(run_arrangements(run)) {
+            Some(n) => n,
+            None => break
+        };
+        if step != 0 {
+            jolt += 3;
+            diffs3 += 1;
+            jolts.push(jolt);
+        }
+        for _ in 0..run {
+            jolt += 1;
+            diffs1 += 1;
+            jolts.push(jolt);
+        }
+    }
+    rng.shuffle(&mut jolts);
+
+    // The device's built-in adapter is 3 jolts higher than the highest.
+    diffs3 += 1;
+
+    let lines = jolts.iter().map(|n| n.to_string()).collect();
+    Generated::new(lines).with_answers(
+        Some(Answer::from(diffs1 * diffs3)),
+        Some(Answer::from(arrangements))
+    )
+}
+
+// Returns the number of ways to arrange a run of n 1-jolt differences. The
+// adapters at either end are kept, and no three in a row can be removed.
+fn run_arrangements(n : usize) -> i64 {
+    let mut ways = [1, 1, 2];
+    for _ in 2..n {
+        ways = [ways[1], ways[2], ways[0] + ways[1] + ways[2]];
+    }
+    ways[n.min(2)]
+}
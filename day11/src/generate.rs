@@ -0,0 +1,17 @@
+use aoc_common::{Generated, Grid, Rng};
+use super::{Cell, run, next_frame1, next_frame2, render};
+
+// Generates a seat map with size rows and columns. Some maps never reach a
+// steady state, so keep only maps that do under both sets of rules.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let size = size.max(1);
+    loop {
+        let cells = (0..size * size)
+            .map(|_| if rng.chance(0.75) { Cell::Empty } else { Cell::Space })
+            .collect();
+        let cells = Grid::from_cells(size, cells);
+        if run(&cells, next_frame1).is_some() && run(&cells, next_frame2).is_some() {
+            return Generated::new(render(&cells).lines().map(|line| line.to_string()).collect());
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Day 11: Seating System. Simulates people taking and leaving seats until
+//! nothing changes, under two sets of rules.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use std::fs;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Grid, Rng, DIRECTIONS, make_error};
+
+mod generate;
+
+// Maximum number of frames to compute before giving up on reaching a
+// steady state.
+const MAX_FRAMES : usize = 10000;
+
+/// A cell of the seat layout.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Cell {
+    /// Floor, which is never occupied.
+    Space,
+
+    /// An empty seat.
+    Empty,
+
+    /// An occupied seat.
+    Full
+}
+
+/// The solution for day 11.
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Grid<Cell>;
+    const DAY: u32 = 11;
+    const INPUT_FILE: &'static str = "day11-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError> {
+        parse_cells(lines, diag)
+    }
+
+    fn part1(&self, input: &Self::Input) -> std::io::Result<Answer> {
+        run(input, next_frame1).ok_or_else(no_steady_state).and_then(Answer::try_from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> std::io::Result<Answer> {
+        run(input, next_frame2).ok_or_else(no_steady_state).and_then(Answer::try_from)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+fn no_steady_state() -> std::io::Error {
+    make_error("The seats never reach a steady state.")
+}
+
+/// Applies func to compute each frame from the previous one, and returns
+/// the number of full cells once the cells stop changing, or None if they
+/// cycle instead. The arguments of func are the current cells and the
+/// cells of the next frame to set.
+pub fn run<T>(input: &Grid<Cell>, func: T) -> Option<usize>
+where T: Fn(&Grid<Cell>, &mut Grid<Cell>) {
+    let mut previous = input.clone();
+    let mut current = input.clone();
+    let mut next = input.clone();
+
+    // Compute the initial frame + 1.
+    func(&current, &mut next);
+
+    // Calculate additional frames until we reach a steady state. Stop if
+    // the cells alternate between two frames, or take too long.
+    let mut frame_count = 1;
+    while next != current {
+        if next == previous || frame_count == MAX_FRAMES {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+        std::mem::swap(&mut current, &mut next);
+        func(&current, &mut next);
+        frame_count += 1;
+    }
+
+    Some(count_full_cells(current.cells().iter()))
+}
+
+fn count_full_cells<'a, I>(cells : I) -> usize
+where I: Iterator<Item = &'a Cell> {
+    cells.filter(|&cell| *cell == Cell::Full).count()
+}
+
+// Returns the next value of a cell, given its number of full neighbours,
+// and the number of full neighbours at which a full seat is emptied.
+fn next_cell(cell : Cell, count : usize, max_count : usize) -> Cell {
+    match cell {
+        Cell::Space => Cell::Space,
+        Cell::Empty => if count == 0 { Cell::Full } else { Cell::Empty },
+        Cell::Full => if count < max_count { Cell::Full } else { Cell::Empty }
+    }
+}
+
+/// Computes the next frame using the rules for part 1, which count the
+/// full cells next to each seat.
+pub fn next_frame1(v : &Grid<Cell>, next : &mut Grid<Cell>) {
+    for (x, y, &cell) in v.positions() {
+        let count = count_full_cells(v.neighbors(x, y, &DIRECTIONS));
+        next[(x, y)] = next_cell(cell, count, 4);
+    }
+}
+
+/// Computes the next frame using the rules for part 2, which count the
+/// first seat visible in each direction that is full.
+pub fn next_frame2(v : &Grid<Cell>, next : &mut Grid<Cell>) {
+    for (x, y, &cell) in v.positions() {
+        let count = DIRECTIONS.iter()
+            .filter(|&&(dx, dy)| v.ray(x, y, dx, dy).find(|&&seat| seat != Cell::Space) == Some(&Cell::Full))
+            .count();
+        next[(x, y)] = next_cell(cell, count, 5);
+    }
+}
+
+/// Renders the cells in the same format as the input.
+pub fn render(cells : &Grid<Cell>) -> String {
+    cells.render(|cell| match cell {
+        Cell::Space => '.',
+        Cell::Empty => 'L',
+        Cell::Full => '#'
+    })
+}
+
+#[allow(dead_code)]
+fn save_frame(cells : &Grid<Cell>, frame_index : i32)  -> std::io::Result<()> {
+    let path = format!("frame-{}.txt", frame_index);
+    fs::write(path, render(cells))
+}
+
+/// Parses the rows of cells.
+pub fn parse_cells(lines: &[String], diag: &mut Diagnostics) -> Result<Grid<Cell>, ParseError> {
+    Grid::parse(lines, diag, "'.', 'L' or '#'", |ch| match ch {
+        '.' => Some(Cell::Space),
+        'L' => Some(Cell::Empty),
+        '#' => Some(Cell::Full),
+        _ => None
+    })
+}
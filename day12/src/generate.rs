@@ -0,0 +1,12 @@
+use aoc_common::{Generated, Rng};
+
+// Generates size navigation instructions.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let lines = (0..size).map(|_| {
+        match *rng.choose(&['N', 'S', 'E', 'W', 'L', 'R', 'F']) {
+            code @ ('L' | 'R') => format!("{}{}", code, 90 * rng.range(1, 3)),
+            code => format!("{}{}", code, rng.range(1, 100))
+        }
+    }).collect();
+    Generated::new(lines)
+}
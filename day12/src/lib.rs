@@ -0,0 +1,244 @@
+//! Day 12: Rain Risk. Navigates a ship by following instructions that
+//! move either the ship or a waypoint.
+
+#![warn(missing_docs)]
+
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng};
+use aoc_common::parser::{Failure, parse_all, char_if, unsigned, pair, spanned};
+
+mod generate;
+
+/// The solution for day 12.
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Direction>;
+    const DAY: u32 = 12;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<Direction>, ParseError> {
+        parse_directions(lines, diag)
+    }
+
+    // Manhattan distance after navigating by moving the ship.
+    fn part1(&self, directions: &Vec<Direction>) -> std::io::Result<Answer> {
+        let mut ship = Ship::new();
+        for &dir in directions {
+            ship.navigate(dir);
+        }
+        Ok(Answer::from(distance(ship.position())))
+    }
+
+    // Manhattan distance after navigating by moving the waypoint.
+    fn part2(&self, directions: &Vec<Direction>) -> std::io::Result<Answer> {
+        let mut ship2 = Ship2::new();
+        for &dir in directions {
+            ship2.navigate(dir);
+        }
+        Ok(Answer::from(distance(ship2.position())))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// A navigation instruction. North is negative y.
+#[derive(Copy, Clone)]
+pub enum Direction {
+    /// Move north, south, east or west (N, S, E or W).
+    Translate {
+        /// Distance east.
+        dx : i32,
+
+        /// Distance south.
+        dy : i32
+    },
+
+    /// Turn clockwise by a multiple of 90 degrees (L or R).
+    Rotate {
+        /// Clockwise angle from 0 to 270.
+        degrees : i32
+    },
+
+    /// Move forward (F).
+    Forward {
+        /// Number of times to move.
+        distance : i32
+    }
+}
+
+/// A position, where north is negative y.
+#[derive(Copy, Clone)]
+pub struct Point {
+    /// Distance east.
+    pub x : i32,
+
+    /// Distance south.
+    pub y : i32
+}
+
+/// A ship that moves itself according to the directions (part 1).
+pub struct Ship {
+    position : Point,
+    heading : i32   // 0 = East, 90 = South, etc.
+}
+
+impl Ship {
+    /// Creates a ship at the origin, facing east.
+    pub fn new() -> Ship {
+        Ship{ position : Point{ x: 0, y: 0 }, heading : 0 }
+    }
+
+    /// Returns the position of the ship.
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    /// Follows a direction, which moves or turns the ship.
+    pub fn navigate(&mut self, dir : Direction) {
+        match dir {
+            Direction::Translate{ dx, dy } => {
+                self.position.x += dx;
+                self.position.y += dy;
+            },
+            Direction::Rotate{ degrees} => {
+                self.heading = normalize_angle(self.heading + degrees);
+            },
+            Direction::Forward{ distance} => {
+                match self.heading {
+                    0 => {
+                        self.position.x += distance;
+                    },
+                    90 => {
+                        self.position.y += distance;
+                    },
+                    180 => {
+                        self.position.x -= distance;
+                    },
+                    270 => {
+                        self.position.y -= distance;
+                    },
+                    // Parsing only accepts multiples of 90 degrees.
+                    _ => unreachable!("invalid heading: {}", self.heading)
+                }
+            }
+        };
+    }
+}
+
+impl Default for Ship {
+    fn default() -> Ship {
+        Ship::new()
+    }
+}
+
+/// A ship that moves a waypoint according to the directions, and moves
+/// itself toward the waypoint (part 2).
+pub struct Ship2 {
+    position : Point,
+    waypoint : Point
+}
+
+impl Ship2 {
+    /// Creates a ship at the origin, with the waypoint 10 east and 1 north.
+    pub fn new() -> Ship2 {
+        Ship2{ 
+            position : Point{ x : 0, y : 0 },
+            waypoint : Point{ x : 10, y : -1 }
+        }
+    }
+
+    /// Returns the position of the ship.
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    /// Follows a direction, which moves or rotates the waypoint, or moves
+    /// the ship toward it.
+    pub fn navigate(&mut self, dir : Direction) {
+        match dir {
+            Direction::Translate{ dx, dy } => {
+                self.waypoint.x += dx;
+                self.waypoint.y += dy;
+            },
+            Direction::Rotate{ degrees} => {
+                //
+                // Rotate the waypoint around the origin.
+                // Compute a rotation matrix from the angle.
+                //
+                let (sin, cos) = match degrees {
+                    0 => (0, 1),
+                    90 => (1, 0),
+                    180 => (0, -1),
+                    270 => (-1, 0),
+                    // Parsing only accepts multiples of 90 degrees.
+                    _ => unreachable!("invalid angle: {}", degrees)
+                };
+                let m11 = cos;
+                let m12 = sin;
+                let m21 = -sin;
+                let m22 = cos;
+
+                //
+                // Multiply (x, y) by the rotation matrix.
+                //
+                let Point{ x, y} = self.waypoint;
+                self.waypoint.x = m11 * x + m21 * y;
+                self.waypoint.y = m12 * x + m22 * y;
+            },
+            Direction::Forward{ distance} => {
+                self.position.x += distance * self.waypoint.x;
+                self.position.y += distance * self.waypoint.y;
+            }
+        };
+    }
+}
+
+impl Default for Ship2 {
+    fn default() -> Ship2 {
+        Ship2::new()
+    }
+}
+
+/// Returns the Manhattan distance of a point from the origin.
+pub fn distance(pt : Point) -> i32 {
+    pt.x.abs() + pt.y.abs()
+}
+
+fn normalize_angle(angle : i32) -> i32 {
+    let a = angle % 360;
+    if a >= 0 { a } else { a + 360 }
+}
+
+/// Parses a direction, which is a code letter followed by a number.
+pub fn parse_direction(line: &str) -> Result<Direction, ParseError> {
+    let code = char_if(|ch| "NSEWLRF".contains(ch), "'N', 'S', 'E', 'W', 'L', 'R' or 'F'");
+    let (code, (arg, span)) = parse_all(line, pair(code, spanned(unsigned::<i32>("a number"))))?;
+
+    // The ships can only turn in multiples of 90 degrees.
+    if (code == 'L' || code == 'R') && arg % 90 != 0 {
+        return Err(Failure::new(span, "a multiple of 90 degrees").to_error(line));
+    }
+
+    Ok(match code {
+        'N' => Direction::Translate{ dx : 0, dy : -arg },
+        'S' => Direction::Translate{ dx : 0, dy : arg },
+        'E' => Direction::Translate{ dx : arg, dy : 0 },
+        'W' => Direction::Translate{ dx : -arg, dy : 0 },
+        'L' => Direction::Rotate{ degrees : normalize_angle(-arg) },
+        'R' => Direction::Rotate{ degrees : normalize_angle(arg) },
+        _ => Direction::Forward{ distance : arg }
+    })
+}
+
+/// Parses the directions, one per line, reporting malformed lines to diag.
+pub fn parse_directions(lines: &[String], diag: &mut Diagnostics) -> Result<Vec::<Direction>, ParseError> {
+    let mut v = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match parse_direction(line) {
+            Ok(dir) => v.push(dir),
+            Err(e) => diag.report(e.at_line(i + 1))?
+        }
+    }
+    Ok(v)
+}
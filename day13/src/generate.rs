@@ -0,0 +1,54 @@
+use std::convert::TryFrom;
+use aoc_common::{Answer, Generated, Rng};
+
+// Bus IDs are distinct primes, so they are coprime and the answer to part 2
+// is unique below their product.
+const PRIMES : [usize; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47,
+    53, 59, 61, 67, 71, 73, 79, 83, 89, 97
+];
+
+// Generates a start time and up to size buses (at least 2 and at most 9,
+// so the product of the IDs doesn't overflow), placed so that the answer
+// to part 2 is a chosen time.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut periods = PRIMES.to_vec();
+    rng.shuffle(&mut periods);
+    periods.truncate(size.clamp(2, 9));
+    let product : usize = periods.iter().product();
+
+    // Choose the answer, which must be positive.
+    let time = rng.range(1, product as i64 - 1) as usize;
+
+    // Place each bus at the first free index where it departs.
+    let mut indices = Vec::new();
+    for &p in &periods {
+        let mut i = (p - time % p) % p;
+        while indices.get(i).copied().flatten().is_some() {
+            i += p;
+        }
+        if i >= indices.len() {
+            indices.resize(i + 1, None);
+        }
+        indices[i] = Some(p);
+    }
+    let buses : Vec<String> = indices.iter()
+        .map(|bus| bus.map_or("x".to_string(), |p| p.to_string()))
+        .collect();
+
+    // Choose a start time with a unique shortest wait. A bus that departs
+    // at the start time counts as a wait of a full period.
+    loop {
+        let start_time = rng.range(100, 10000) as usize;
+        let mut waits : Vec<(usize, usize)> = periods.iter().map(|&p| (p - start_time % p, p)).collect();
+        waits.sort_unstable();
+        if waits[0].0 != waits[1].0 {
+            let (wait, p) = waits[0];
+            let lines = vec![start_time.to_string(), buses.join(",")];
+            return Generated::new(lines).with_answers(
+                Answer::try_from(wait * p).ok(),
+                Answer::try_from(time).ok()
+            );
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Day 13: Shuttle Search. Finds the earliest bus to take, and the
+//! earliest time at which the buses depart at their offsets in the list.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, chinese_remainder, parse_field, make_error};
+
+mod generate;
+
+/// A bus, with its period (ID) and its index in the list of buses.
+#[derive(Eq, PartialEq, Ord, PartialOrd)]
+pub struct Bus(/*p*/usize, /*i*/usize);
+
+impl Bus {
+    /// Returns the period of the bus, which is also its ID.
+    pub fn period(&self) -> usize { self.0 }
+
+    /// Returns the index of the bus in the list, which is its offset for part 2.
+    pub fn index(&self) -> usize { self.1 }
+}
+
+/// The solution for day 13.
+pub struct Day13;
+
+impl Solution for Day13 {
+    // Start time and buses sorted by period.
+    type Input = (usize, Vec<Bus>);
+    const DAY: u32 = 13;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError> {
+        parse_input(lines, diag)
+    }
+
+    fn part1(&self, (start_time, periods): &Self::Input) -> std::io::Result<Answer> {
+        Answer::try_from(find_best_period(*start_time, periods))
+    }
+
+    fn part2(&self, (_start_time, periods): &Self::Input) -> std::io::Result<Answer> {
+        match find_special_time(periods) {
+            Some(t) => Answer::try_from(t),
+            None => Err(make_error("No matching time found."))
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Returns the product of the best period and the wait time for that bus.
+pub fn find_best_period(start_time : usize, periods : &[Bus]) -> usize {
+    let mut best_period = 0;
+    let mut best_wait = usize::MAX;
+
+    for &Bus(p, _i) in periods {
+        let wait = compute_wait(start_time, p);
+        if wait < best_wait {
+            best_period = p;
+            best_wait = wait;
+        }
+    }
+
+    best_period * best_wait
+}
+
+/// Finds the earliest time t at which each bus departs at t plus its
+/// index, i.e., t + index is a multiple of the period. The periods need not
+/// be coprime.
+pub fn find_special_time(periods : &[Bus]) -> Option<usize> {
+    if periods.is_empty() {
+        return None;
+    }
+    let congruences : Vec<(i64, u64)> = periods.iter()
+        .map(|bus| (-(bus.index() as i64), bus.period() as u64))
+        .collect();
+    let (t, _period) = chinese_remainder(&congruences)?;
+    usize::try_from(t).ok()
+}
+
+fn compute_wait(start_time : usize, p : usize) -> usize {
+    p - (start_time % p)
+}
+
+/// Parses the start time and the list of buses, returning the buses sorted
+/// by period.
+pub fn parse_input(lines: &[String], diag: &mut Diagnostics) -> Result<(usize,Vec::<Bus>), ParseError> {
+    let mut start_time = 0;
+    let mut periods = Vec::new();
+
+    // The first line is the earliest departure time.
+    let s = lines.first().map_or("", |s| s.as_str());
+    match parse_field::<usize>(s, s, "a departure time") {
+        Ok(n) => { start_time = n; },
+        Err(e) => diag.report(e.at_line(1))?
+    }
+
+    // The second line is a comma-separated list of bus IDs, where 'x' means
+    // no bus. A missing line is an error even in lenient mode, as there is
+    // nothing to solve.
+    let s = match lines.get(1) {
+        Some(s) => s.as_str(),
+        None => { return Err(ParseError::new(1, "a list of bus IDs", "").at_line(2)); }
+    };
+    for (i, field) in s.split(',').enumerate() {
+        if field.trim() == "x" {
+            continue;
+        }
+        match parse_field::<usize>(s, field, "a bus ID or 'x'") {
+            Ok(p) if p > 0 => periods.push(Bus(p, i)),
+            _ => diag.report(ParseError::at_field(s, field.trim(), "a bus ID or 'x'").at_line(2))?
+        }
+    }
+
+    periods.sort_unstable();
+
+    Ok((start_time, periods))
+}
@@ -0,0 +1,30 @@
+use aoc_common::{Generated, Rng};
+
+// Maximum number of floating bits in a generated mask, which keeps part 2
+// fast.
+const MAX_FLOATING : usize = 9;
+
+// Generates size instructions, starting with a mask.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut lines = Vec::new();
+    for i in 0..size.max(1) {
+        if i == 0 || rng.chance(0.25) {
+            lines.push(format!("mask = {}", random_mask(rng)));
+        }
+        else {
+            let address = rng.range(0, 65535);
+            let value = rng.range(0, (1 << 36) - 1);
+            lines.push(format!("mem[{}] = {}", address, value));
+        }
+    }
+    Generated::new(lines)
+}
+
+fn random_mask(rng : &mut Rng) -> String {
+    let mut mask : Vec<char> = (0..36).map(|_| *rng.choose(&['0', '1'])).collect();
+    for _ in 0..rng.range(0, MAX_FLOATING as i64) {
+        let i = rng.index(mask.len());
+        mask[i] = 'X';
+    }
+    mask.into_iter().collect()
+}
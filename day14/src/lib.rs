@@ -0,0 +1,230 @@
+//! Day 14: Docking Data. Runs a program that writes to memory through a
+//! bitmask, which applies to the values or the addresses.
+
+#![warn(missing_docs)]
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, make_error};
+use aoc_common::parser::{parse_all, literal, char_if, unsigned, map, pair, preceded, alt, repeat};
+
+mod generate;
+
+// Maximum number of floating bits in a mask for part 2, which writes to
+// pow(2, N) addresses for each memory instruction.
+const MAX_FLOATING_BITS : u32 = 16;
+
+/// The solution for day 14.
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Instruction>;
+    const DAY: u32 = 14;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<Instruction>, ParseError> {
+        parse_instructions(lines, diag)
+    }
+
+    fn part1(&self, code: &Vec<Instruction>) -> std::io::Result<Answer> {
+        Answer::try_from(exec(code, false))
+    }
+
+    fn part2(&self, code: &Vec<Instruction>) -> std::io::Result<Answer> {
+        for inst in code {
+            if let Instruction::Mask{ zero_bits, one_bits } = inst {
+                if floating_bits(*zero_bits, *one_bits).count_ones() > MAX_FLOATING_BITS {
+                    return Err(make_error("Too many floating bits in mask."));
+                }
+            }
+        }
+        Answer::try_from(exec(code, true))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Runs the program and returns the sum of memory. If is_v2 is true, masks
+/// apply to addresses (part 2); otherwise, to values.
+pub fn exec(code : &[Instruction], is_v2 : bool) -> u64 {
+    let mut computer = Computer::new();
+
+    for inst in code {
+        computer.exec(inst, is_v2);
+    }
+
+    computer.memory_sum()
+}
+
+/// An instruction of the program.
+pub enum Instruction {
+    /// Sets the mask.
+    Mask {
+        /// Bits that are 0 in the mask.
+        zero_bits : u64,
+
+        /// Bits that are 1 in the mask.
+        one_bits : u64
+    },
+
+    /// Writes a value to memory.
+    Mem {
+        /// The memory address.
+        address : u64,
+
+        /// The value to write.
+        value : u64
+    }
+}
+
+/// The mask and memory of the computer running a program.
+pub struct Computer {
+    zero_bits : u64,
+    one_bits : u64,
+    memory : HashMap<u64, u64>
+}
+
+impl Computer {
+    /// Creates a computer with an all-X mask and empty memory.
+    pub fn new() -> Computer {
+        Computer{
+            zero_bits : u64::MAX,
+            one_bits : 0,
+            memory : HashMap::new()
+        }
+    }
+
+    /// Returns the sum of the values in memory.
+    pub fn memory_sum(&self) -> u64 {
+        self.memory.values().sum()
+    }
+
+    /// Runs an instruction, applying the mask to the address if is_v2 is
+    /// true, or to the value otherwise.
+    pub fn exec(&mut self, inst : &Instruction, is_v2 : bool) {
+        match inst {
+            Instruction::Mask{ zero_bits, one_bits } => {
+                self.zero_bits = *zero_bits;
+                self.one_bits = *one_bits;
+            },
+            Instruction::Mem{ address, value} => {
+                if is_v2 {
+                    self.set_mem2(*address, *value);
+                }
+                else {
+                    self.set_mem1(*address, *value);
+                }
+            }
+        }
+    }
+
+    fn set_mem1(&mut self, address : u64, value : u64) {
+        let val = (value & !self.zero_bits) | self.one_bits;
+        self.memory.insert(address, val);
+    }
+
+    fn set_mem2(&mut self, address : u64, value : u64) {
+        let addr = address | self.one_bits;
+        let floating = floating_bits(self.zero_bits, self.one_bits);
+        if floating == 0 {
+            self.memory.insert(addr, value);
+        }
+        else {
+            let mut bit_indices = [0u8; 64];
+            let mut bit_count = 0;
+            for i in 0..64 {
+                if test_bit(floating, i) {
+                    bit_indices[bit_count as usize] = i;
+                    bit_count += 1;
+                }
+            }
+            for combo in 0..(1u64 << bit_count) {
+                let mut flipped = 0;
+                for i in 0..bit_count {
+                    if test_bit(combo, i) {
+                        flipped |= 1u64 << bit_indices[i as usize];
+                    }
+                }
+                self.memory.insert(addr ^ flipped, value);
+            }
+        }
+    }
+}
+
+impl Default for Computer {
+    fn default() -> Computer {
+        Computer::new()
+    }
+}
+
+/// Returns the bits that are neither 0 nor 1 in a 36-bit mask.
+pub fn floating_bits(zero_bits : u64, one_bits : u64) -> u64 {
+    const BITMASK : u64 = (1 << 36) - 1;
+    !(one_bits | zero_bits) & BITMASK
+}
+
+fn test_bit(bits : u64, index : u8) -> bool {
+    ((bits >> index) & 1) != 0
+}
+
+/// Parses a `mask = <bits>` or `mem[<address>] = <value>` instruction.
+pub fn parse_instruction(line : &str) -> Result<Instruction, ParseError> {
+    const MASK_BITS : usize = 36;
+
+    let bit = char_if(|ch| ch == 'X' || ch == '0' || ch == '1', "'X', '0' or '1'");
+    let mask = map(preceded(literal("mask = "), repeat(MASK_BITS, bit)), |bits| {
+        let mut zero_bits = 0;
+        let mut one_bits = 0;
+        for ch in bits {
+            zero_bits = (zero_bits << 1) | (ch == '0') as u64;
+            one_bits = (one_bits << 1) | (ch == '1') as u64;
+        }
+        Instruction::Mask{ zero_bits, one_bits }
+    });
+    let mem = map(
+        pair(
+            preceded(literal("mem["), unsigned("an address")),
+            preceded(literal("] = "), unsigned("a value"))
+        ),
+        |(address, value)| Instruction::Mem{ address, value }
+    );
+    parse_all(line, alt(mask, mem))
+}
+
+/// Parses the program, one instruction per line, reporting malformed lines
+/// to diag.
+pub fn parse_instructions(lines: &[String], diag: &mut Diagnostics) -> Result<Vec::<Instruction>, ParseError> {
+    let mut v = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match parse_instruction(line) {
+            Ok(inst) => v.push(inst),
+            Err(e) => diag.report(e.at_line(i + 1))?
+        }
+    }
+    Ok(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_instructions() {
+        match parse_instruction("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X").unwrap() {
+            Instruction::Mask{ zero_bits, one_bits } => assert_eq!((zero_bits, one_bits), (0b10, 0b1000000)),
+            _ => panic!("expected a mask")
+        }
+        match parse_instruction("mem[8] = 11").unwrap() {
+            Instruction::Mem{ address, value } => assert_eq!((address, value), (8, 11)),
+            _ => panic!("expected a memory write")
+        }
+
+        let e = parse_instruction("mask = XX2").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (10, "'X', '0' or '1'", "2"));
+        let e = parse_instruction("mem[8] 11").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (6, "'] = '"));
+        let e = parse_instruction("nop").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (1, "'mask = ' or 'mem['"));
+    }
+}
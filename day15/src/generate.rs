@@ -0,0 +1,10 @@
+use aoc_common::{Generated, Rng};
+
+// Generates up to size distinct starting numbers (at most 10).
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut numbers : Vec<u32> = (0..20).collect();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(size.clamp(1, 10));
+    let fields : Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+    Generated::new(vec![fields.join(",")])
+}
@@ -0,0 +1,120 @@
+//! Day 15: Rambunctious Recitation. Plays the memory game, in which each
+//! number is how many turns ago the previous number was last spoken.
+
+#![warn(missing_docs)]
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, parse_field, trace};
+
+mod generate;
+
+/// The solution for day 15.
+pub struct Day15;
+
+impl Solution for Day15 {
+    // Starting numbers.
+    type Input = Vec<usize>;
+    const DAY: u32 = 15;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<usize>, ParseError> {
+        parse_starting_numbers(lines, diag)
+    }
+
+    fn part1(&self, input: &Vec<usize>) -> std::io::Result<Answer> {
+        Answer::try_from(play(input, 2020))
+    }
+
+    fn part2(&self, input: &Vec<usize>) -> std::io::Result<Answer> {
+        Answer::try_from(play(input, 30000000))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Parses the starting numbers, which are comma-separated on the first line.
+/// There must be at least one.
+pub fn parse_starting_numbers(lines: &[String], diag: &mut Diagnostics) -> Result<Vec<usize>, ParseError> {
+    let mut input = Vec::new();
+    let line = lines.first().map_or("", |s| s.as_str());
+    if !line.trim().is_empty() {
+        for field in line.split(',') {
+            match parse_field::<usize>(line, field, "a starting number") {
+                Ok(n) => input.push(n),
+                Err(e) => diag.report(e.at_line(1))?
+            }
+        }
+    }
+    if input.is_empty() {
+        return Err(ParseError::end_of_line(line, "a starting number").at_line(1));
+    }
+    Ok(input)
+}
+
+/// Returns the number spoken on the specified turn, where turns are
+/// numbered from 1.
+pub fn play(input : &[usize], turn_index : usize) -> usize {
+    let mut game = GameState::new(input);
+    game.advance_to(turn_index);
+    game.last_number()
+}
+
+/// The state of the game after some number of turns.
+pub struct GameState {
+    next_index : usize,
+    last_number : usize,
+    turn_map : HashMap<usize, usize>
+}
+
+impl GameState {
+    /// Starts a game by speaking the starting numbers, of which there must
+    /// be at least one.
+    pub fn new(input : &[usize]) -> GameState {
+        let mut turn_map = HashMap::new();
+
+        let next_index = input.len();
+        let last_index = next_index - 1;
+        let last_number = input[last_index];
+
+        for (i, &n) in input[..last_index].iter().enumerate() {
+            trace!("{}. {}", i + 1, n);
+            turn_map.insert(n, i + 1);
+        }
+        trace!("{}. {}", next_index, last_number);
+
+        GameState {
+            next_index,
+            last_number,
+            turn_map
+        }
+    }
+
+    /// Returns the number spoken on the last turn.
+    pub fn last_number(&self) -> usize {
+        self.last_number
+    }
+
+    /// Plays the next turn.
+    pub fn next(&mut self) {
+        let n = match self.turn_map.get(&self.last_number) {
+            Some(index) => self.next_index - *index,
+            None => 0
+        };
+        self.turn_map.insert(self.last_number, self.next_index);
+
+        self.last_number = n;
+        self.next_index += 1;
+
+        trace!("{}. {}", self.next_index, self.last_number);
+    }
+
+    /// Plays turns until the specified turn, numbered from 1, has been
+    /// played.
+    pub fn advance_to(&mut self, turn_index : usize) {
+        while self.next_index < turn_index {
+            self.next();
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::convert::TryFrom;
+use aoc_common::{Answer, Generated, Rng};
+
+const FIELD_NAMES : [&str; 20] = [
+    "departure location", "departure station", "departure platform",
+    "departure track", "departure date", "departure time",
+    "arrival location", "arrival station", "arrival platform", "arrival track",
+    "class", "duration", "price", "route", "row", "seat", "train", "type",
+    "wagon", "zone"
+];
+
+// Upper bound of the first field's second range, and how much lower the
+// bound is for each field after it.
+const MAX_VALUE : u32 = 900;
+const STEP : u32 = 20;
+
+// The gap between the two ranges of each field is below the lowest bound.
+const MIN_GAP : u32 = 50;
+const MAX_GAP : u32 = 400;
+
+// Generates the fields, my ticket, and size nearby tickets (at least 1),
+// of which about a quarter are invalid.
+//
+// Field j accepts values up to MAX_VALUE - j * STEP, except for one value
+// (the gap between its ranges). The column for field j has a value greater
+// than the bound for field j + 1, so it can only be one of the fields up to
+// j. Field 0 is therefore identified first, then field 1, and so on.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let field_count = FIELD_NAMES.len();
+    let bound = |j : usize| MAX_VALUE - j as u32 * STEP;
+
+    let mut names = FIELD_NAMES.to_vec();
+    rng.shuffle(&mut names);
+    let mut columns : Vec<usize> = (0..field_count).collect();
+    rng.shuffle(&mut columns);
+    let mut gaps = Vec::new();
+    while gaps.len() < field_count {
+        let gap = rng.range(MIN_GAP as i64, MAX_GAP as i64) as u32;
+        if !gaps.contains(&gap) {
+            gaps.push(gap);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for j in 0..field_count {
+        lines.push(format!("{}: 1-{} or {}-{}", names[j], gaps[j] - 1, gaps[j] + 1, bound(j)));
+    }
+
+    // Returns a valid value for field j, which is greater than the bound of
+    // the next field if above_next is true.
+    let valid_value = |rng : &mut Rng, j : usize, above_next : bool| loop {
+        let min = if above_next && j + 1 < field_count { bound(j + 1) + 1 } else { 1 };
+        let value = rng.range(min as i64, bound(j) as i64) as u32;
+        if !gaps.contains(&value) {
+            return value;
+        }
+    };
+    let valid_ticket = |rng : &mut Rng, above_next : bool| {
+        let mut ticket = vec![0; field_count];
+        for j in 0..field_count {
+            ticket[columns[j]] = valid_value(rng, j, above_next);
+        }
+        ticket
+    };
+
+    let my_ticket = valid_ticket(rng, false);
+    let mut product : usize = 1;
+    for j in 0..field_count {
+        if names[j].starts_with("departure") {
+            product *= my_ticket[columns[j]] as usize;
+        }
+    }
+
+    // The first nearby ticket has the values that identify the fields.
+    let mut nearby = vec![valid_ticket(rng, true)];
+    let mut error_sum : u32 = 0;
+    for _ in 1..size.max(1) {
+        let mut ticket = valid_ticket(rng, false);
+        if rng.chance(0.25) {
+            let value = rng.range(MAX_VALUE as i64 + 1, 999) as u32;
+            ticket[rng.index(field_count)] = value;
+            error_sum += value;
+        }
+        nearby.push(ticket);
+    }
+    rng.shuffle(&mut nearby);
+
+    let to_line = |ticket : &Vec<u32>| ticket.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",");
+    lines.push(String::new());
+    lines.push("your ticket:".to_string());
+    lines.push(to_line(&my_ticket));
+    lines.push(String::new());
+    lines.push("nearby tickets:".to_string());
+    lines.extend(nearby.iter().map(to_line));
+
+    Generated::new(lines).with_answers(
+        Some(Answer::from(error_sum)),
+        Answer::try_from(product).ok()
+    )
+}
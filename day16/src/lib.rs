@@ -0,0 +1,359 @@
+//! Day 16: Ticket Translation. Finds the invalid values on nearby tickets,
+//! and works out which ticket column holds which field.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, Json, Record, split_records, make_error};
+use aoc_common::parser::{Parser, Failure, parse_all, literal, take_until, spaces, token, unsigned, map, pair, triple, preceded, terminated, separated_list1, spanned};
+
+mod generate;
+
+// Maximum number of fields, since sets of fields are stored as u32 bitmasks.
+const MAX_FIELDS : usize = 32;
+
+/// The solution for day 16.
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Input;
+    const DAY: u32 = 16;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Input, ParseError> {
+        parse_input(lines, diag)
+    }
+
+    // Sum of the invalid fields.
+    fn part1(&self, input: &Input) -> std::io::Result<Answer> {
+        Ok(Answer::from(input.invalid_value_sum()))
+    }
+
+    // Product of the "departure" fields on my ticket.
+    fn part2(&self, input: &Input) -> std::io::Result<Answer> {
+        let input = input.identify_fields()?;
+        let mut product = 1;
+        for field in &input.field_defs {
+            if field.name.starts_with("departure") {
+                if let Some(i) = field.input_index {
+                    product *= input.my_ticket[i as usize] as usize;
+                }
+            }
+        }
+        Answer::try_from(product)
+    }
+
+    // Input column of each field, by field name.
+    fn details(&self, input: &Input) -> Option<Json> {
+        let input = input.identify_fields().ok()?;
+        let columns = input.field_defs.iter()
+            .map(|field| (field.name.clone(), field.input_index.into()))
+            .collect();
+        Some(Json::object().with("field_columns", Json::Object(columns)))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Range {
+    min : u32,
+    max : u32
+}
+
+impl Range {
+    fn new(min : u32, max : u32) -> Range {
+        Range{ min, max }
+    }
+    // Returns a parser for a range of the form "<min>-<max>".
+    fn parser<'a>() -> impl Parser<'a, Range> {
+        let range = pair(
+            token(unsigned("a number")),
+            preceded(token(literal("-")), token(unsigned("a number")))
+        );
+        map(range, |(min, max)| Range::new(min, max))
+    }
+    fn includes(&self, val : u32) -> bool {
+        val >= self.min && val <= self.max
+    }
+}
+
+/// The name of a ticket field and its two valid ranges.
+#[derive(Clone)]
+pub struct FieldDef  {
+    name : String,
+    first_range : Range,
+    second_range : Range,
+    input_index : Option<u32>
+}
+
+impl FieldDef {
+    fn new(name : &str, first_range : Range, second_range : Range) -> FieldDef {
+        FieldDef{ name : name.to_string(), first_range, second_range, input_index : None }
+    }
+    fn parse(line : &str) -> Result<FieldDef, ParseError> {
+        // A field definition is "<name>: <range> or <range>".
+        let field_def = triple(
+            terminated(take_until(":"), literal(":")),
+            Range::parser(),
+            preceded(literal(" or "), terminated(Range::parser(), spaces()))
+        );
+        let (name, first_range, second_range) = parse_all(line, field_def)?;
+
+        Ok(FieldDef::new(name.trim(), first_range, second_range))
+    }
+    /// Returns the name of the field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the ticket column of the field, once identified.
+    pub fn input_index(&self) -> Option<u32> {
+        self.input_index
+    }
+
+    /// Returns true if the value is in either of the field's ranges.
+    pub fn includes(&self, val : u32) -> bool {
+        self.first_range.includes(val) || self.second_range.includes(val)
+    }
+}
+
+/// The field definitions, my ticket, and the nearby tickets.
+#[derive(Clone)]
+pub struct Input {
+    field_defs : Vec<FieldDef>,
+    my_ticket : Vec<u32>,
+    nearby_tickets : Vec<Vec<u32>>
+}
+
+impl Input {
+    fn new() -> Input {
+        Input{
+            field_defs : Vec::new(),
+            my_ticket : Vec::new(),
+            nearby_tickets : Vec::new()
+        }
+    }
+
+    /// Returns the field definitions, in input order.
+    pub fn field_defs(&self) -> &[FieldDef] {
+        &self.field_defs
+    }
+
+    /// Returns the values on my ticket.
+    pub fn my_ticket(&self) -> &[u32] {
+        &self.my_ticket
+    }
+
+    /// Returns the values on each nearby ticket.
+    pub fn nearby_tickets(&self) -> &[Vec<u32>] {
+        &self.nearby_tickets
+    }
+
+    /// Returns the sum of the values on nearby tickets that aren't valid
+    /// for any field.
+    pub fn invalid_value_sum(&self) -> u32 {
+        let mut sum = 0;
+        for ticket in &self.nearby_tickets {
+            for &field in ticket {
+                if self.valid_field_bits(field) == 0 {
+                    sum += field;
+                }
+            }
+        }
+        sum
+    }
+
+    fn valid_field_bits(&self, val : u32) -> u32 {
+        let mut valid_bits = 0;
+        for i in 0..self.field_defs.len() {
+            let def = &self.field_defs[i];
+            if def.input_index.is_none() && def.includes(val) {
+                valid_bits |= 1u32 << i;
+            }
+        }
+        valid_bits
+    }
+
+    /// Returns true if every value on the ticket is valid for some field.
+    pub fn is_valid_ticket(&self, ticket : &[u32]) -> bool {
+        for &field in ticket {
+            if self.valid_field_bits(field) == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns a copy of the input with the input index of each field set,
+    /// based on the valid nearby tickets.
+    pub fn identify_fields(&self) -> std::io::Result<Input> {
+        let mut input = self.clone();
+        input.remove_invalid_tickets();
+        input.set_input_indices()?;
+        Ok(input)
+    }
+
+    /// Removes the nearby tickets that have invalid values.
+    pub fn remove_invalid_tickets(&mut self) {
+        let mut valid = Vec::new();
+
+        for ticket in &self.nearby_tickets {
+            if self.is_valid_ticket(ticket) {
+                valid.push(ticket.clone());
+            }
+        }
+
+        self.nearby_tickets = valid;
+    }
+
+    /// Sets the input index of each field, by repeatedly finding a column
+    /// whose values on the nearby tickets are valid for only one field.
+    /// Invalid tickets should be removed first.
+    pub fn set_input_indices(&mut self) -> std::io::Result<()> {
+        let field_count = self.my_ticket.len();
+        let all_input_bits = (1u32 << field_count) - 1;
+        let mut used_input_bits = 0;
+
+        while used_input_bits != all_input_bits {
+            let mut assigned_index = false;
+
+            for input_index in 0..field_count {
+                if test_bit(used_input_bits, input_index) {
+                    continue;
+                }
+
+                // Initially assume this input could go with any field.
+                let mut field_bits = all_input_bits;
+
+                for ticket in &self.nearby_tickets {
+                    field_bits &= self.valid_field_bits(ticket[input_index]);
+                }
+
+                if field_bits == 0 {
+                    return Err(make_error(&format!(
+                        "Can't identify field for input column {}.", input_index
+                    )));
+                }
+
+                // If only one bit is set then it can only belong to that field.
+                if (field_bits & (field_bits - 1)) == 0 {
+                    for i in 0..field_count {
+                        if test_bit(field_bits, i) {
+                            self.field_defs[i].input_index = Some(input_index as u32);
+                        }
+                    }
+                    used_input_bits |= 1 << input_index;
+                    assigned_index = true;
+                    break;
+                }
+            }
+
+            // If we didn't make any progress, exit the loop.
+            if !assigned_index {
+                return Err(make_error("Failed to set input indices."));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn test_bit(bits : u32, index : usize) -> bool {
+    ((bits >> index) & 1) != 0
+}
+
+// Parses a comma-separated ticket with one value per field.
+fn parse_ticket(s : &str, field_count : usize) -> Result<Vec<u32>, ParseError> {
+    let field = terminated(token(unsigned("a field value")), spaces());
+    let (fields, span) = parse_all(s, spanned(separated_list1(field, literal(","))))?;
+    if fields.len() != field_count {
+        let expected = format!("{} field values", field_count);
+        return Err(Failure::new(span, &expected).to_error(s));
+    }
+    Ok(fields)
+}
+
+// Checks the heading line of a section.
+fn parse_heading(record : &Record, heading : &str) -> Result<(), ParseError> {
+    let s = &record.lines[0];
+    if s.trim() == heading {
+        Ok(())
+    }
+    else {
+        Err(ParseError::at_field(s, s, &format!("'{}'", heading)).at_line(record.line_number(0)))
+    }
+}
+
+/// Parses the field definitions, my ticket, and the nearby tickets,
+/// reporting malformed lines to diag.
+pub fn parse_input(lines: &[String], diag: &mut Diagnostics) -> Result<Input, ParseError> {
+    let mut input = Input::new();
+    let records = split_records(lines);
+    let mut records = records.iter();
+
+    // The first record contains the field definitions. Fields are stored
+    // as bits of a u32, so there can be at most 32.
+    if let Some(record) = records.next() {
+        for (i, s) in record.lines.iter().enumerate() {
+            let result = if input.field_defs.len() == MAX_FIELDS {
+                Err(ParseError::at_field(s, s, "at most 32 field definitions"))
+            }
+            else {
+                FieldDef::parse(s)
+            };
+            match result {
+                Ok(def) => input.field_defs.push(def),
+                Err(e) => diag.report(e.at_line(record.line_number(i)))?
+            }
+        }
+    }
+    let field_count = input.field_defs.len();
+
+    // The next record is the "your ticket" section, which has a heading
+    // followed by a line containing the fields. Without it there is no
+    // puzzle, so errors here are fatal.
+    let record = records.next().ok_or_else(
+        || ParseError::new(1, "'your ticket:'", "").at_line(lines.len() + 1)
+    )?;
+    parse_heading(record, "your ticket:")?;
+    let s = record.lines.get(1).ok_or_else(
+        || ParseError::new(1, "a ticket", "").at_line(record.line_number(1))
+    )?;
+    input.my_ticket = parse_ticket(s, field_count).map_err(|e| e.at_line(record.line_number(1)))?;
+
+    // The last record is the "nearby tickets" section, which has a heading
+    // followed by one ticket per line.
+    if let Some(record) = records.next() {
+        parse_heading(record, "nearby tickets:")?;
+        for (i, s) in record.lines.iter().enumerate().skip(1) {
+            match parse_ticket(s, field_count) {
+                Ok(ticket) => input.nearby_tickets.push(ticket),
+                Err(e) => diag.report(e.at_line(record.line_number(i)))?
+            }
+        }
+    }
+
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_field_defs_and_tickets() {
+        let field = FieldDef::parse("departure location: 1-3 or 5-7").unwrap();
+        assert_eq!(field.name(), "departure location");
+        assert!(field.includes(3) && !field.includes(4) && field.includes(5));
+
+        let e = FieldDef::parse("class: 1-3 and 5-7").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (11, "' or '"));
+        let e = FieldDef::parse("class: 1-x or 5-7").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (10, "a number", "x"));
+
+        assert_eq!(parse_ticket("7,1, 14", 3).unwrap(), vec![7, 1, 14]);
+        let e = parse_ticket("7,1", 3).err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (1, "3 field values", "7,1"));
+    }
+}
@@ -0,0 +1,11 @@
+use aoc_common::{Generated, Rng};
+
+// Generates an initial grid with size rows and columns (at most 12, which
+// keeps part 2 fast).
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let size = size.clamp(1, 12);
+    let lines = (0..size)
+        .map(|_| (0..size).map(|_| if rng.chance(0.4) { '#' } else { '.' }).collect())
+        .collect();
+    Generated::new(lines)
+}
@@ -0,0 +1,250 @@
+//! Day 17: Conway Cubes. Runs a cellular automaton, starting from a 2D
+//! slice, in three and in four dimensions.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Grid, Rng};
+
+mod generate;
+
+/// Number of cycles the automaton runs for.
+pub const MAX_FRAME_COUNT : usize = 6;
+
+/// The solution for day 17.
+pub struct Day17;
+
+impl Solution for Day17 {
+    // Initial cells, which are true if active.
+    type Input = Grid<bool>;
+    const DAY: u32 = 17;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError> {
+        parse_input(lines, diag)
+    }
+
+    fn part1(&self, cells: &Self::Input) -> std::io::Result<Answer> {
+        Answer::try_from(part1(cells))
+    }
+
+    fn part2(&self, cells: &Self::Input) -> std::io::Result<Answer> {
+        Answer::try_from(part2(cells))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Returns the number of active cells after running the automaton in three
+/// dimensions, where cells is the initial slice.
+pub fn part1(cells : &Grid<bool>) -> usize {
+    let (width, height) = (cells.width(), cells.height());
+
+    // Allocate a 3d grid with enough margin around it that we won't
+    // reach its boundaries in MAX_FRAME_COUNT frames.
+    let margin = MAX_FRAME_COUNT + 1;
+    let mut grid = Grid3::new(
+        width + margin * 2,
+        height + margin * 2,
+        1 + margin * 2
+    );
+
+    // Set the initial active cells in the grid.
+    for (x, y, &cell) in cells.positions() {
+        if cell {
+            grid.set(x + margin, y + margin, margin, true);
+        }
+    }
+
+    // Execute the cellular automaton.
+    for _i in 0..MAX_FRAME_COUNT {
+        grid.next_frame();
+    }
+
+    // Return the final number of active cells.
+    grid.count_active_cells()
+}
+
+/// Returns the number of active cells after running the automaton in four
+/// dimensions, where cells is the initial slice.
+pub fn part2(cells : &Grid<bool>) -> usize {
+    let (width, height) = (cells.width(), cells.height());
+
+    // Allocate a 4d grid with enough margin around it that we won't
+    // reach its boundaries in MAX_FRAME_COUNT frames.
+    let margin = MAX_FRAME_COUNT + 1;
+    let mut grid = Grid4::new(
+        width + margin * 2,
+        height + margin * 2,
+        1 + margin * 2,
+        1 + margin * 2
+    );
+
+    // Set the initial active cells in the grid.
+    for (x, y, &cell) in cells.positions() {
+        if cell {
+            grid.set(x + margin, y + margin, margin, margin, true);
+        }
+    }
+
+    // Execute the cellular automaton.
+    for _i in 0..MAX_FRAME_COUNT {
+        grid.next_frame();
+    }
+
+    // Return the final number of active cells.
+    grid.count_active_cells()
+}
+
+struct Grid3 {
+    cells : Vec<bool>,
+    next_cells : Vec<bool>,
+    width : usize,
+    height : usize,
+    depth : usize
+}
+
+impl Grid3 {
+    fn new(width : usize, height : usize, depth : usize) -> Grid3 {
+        let mut cells = Vec::new();
+        cells.resize(width * height * depth, false);
+        let next_cells = cells.clone();
+        Grid3{ cells, next_cells, width, height, depth }
+    }
+
+    fn index(&self, x : usize, y : usize, z : usize) -> usize {
+        (((z * self.height) + y) * self.width) + x
+    }
+
+    fn set(&mut self, x : usize, y : usize, z : usize, value : bool) {
+        let i = self.index(x, y, z);
+        self.cells[i] = value;
+    }
+
+    fn test(&self, x : usize, y: usize, z : usize) -> bool {
+        let i = self.index(x, y, z);
+        self.cells[i]
+    }
+
+    fn next_frame(&mut self) {
+        for z in 1..self.depth - 1 {
+            for y in 1..self.height - 1 {
+                for x in 1..self.width - 1 {
+                    let i = self.index(x, y, z);
+                    let is_active = self.cells[i];
+                    let count = self.count_neighbors_and_self(x, y, z) - (is_active as usize);
+                    self.next_cells[i] = match is_active {
+                        true => count == 2 || count == 3,
+                        false => count == 3
+                    };
+                }
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.next_cells);
+    }
+
+    fn count_neighbors_and_self(&self, x : usize, y : usize, z : usize) -> usize {
+        let mut count = 0;
+        for z in z-1..z+2 {
+            for y in y-1..y+2 {
+                for x in x-1..x+2 {
+                    count += self.test(x, y, z) as usize;
+                }
+            }
+        }
+        count
+    }
+
+    fn count_active_cells(&self) -> usize {
+        let mut count = 0;
+        for &cell in &self.cells {
+            count += cell as usize;
+        }
+        count
+    }
+}
+
+struct Grid4 {
+    cells : Vec<bool>,
+    next_cells : Vec<bool>,
+    width : usize,
+    height : usize,
+    depth : usize,
+    wlen : usize
+}
+
+impl Grid4 {
+    fn new(width : usize, height : usize, depth : usize, wlen : usize) -> Grid4 {
+        let mut cells = Vec::new();
+        cells.resize(width * height * depth * wlen, false);
+        let next_cells = cells.clone();
+        Grid4{ cells, next_cells, width, height, depth, wlen }
+    }
+
+    fn index(&self, x : usize, y : usize, z : usize, w : usize) -> usize {
+        ((w * self.wlen + z) * self.height + y) * self.width + x
+    }
+
+    fn set(&mut self, x : usize, y : usize, z : usize, w : usize, value : bool) {
+        let i = self.index(x, y, z, w);
+        self.cells[i] = value;
+    }
+
+    fn test(&self, x : usize, y: usize, z : usize, w : usize) -> bool {
+        let i = self.index(x, y, z, w);
+        self.cells[i]
+    }
+
+    fn next_frame(&mut self) {
+        for w in 1..self.wlen - 1 {
+            for z in 1..self.depth - 1 {
+                for y in 1..self.height - 1 {
+                    for x in 1..self.width - 1 {
+                        let i = self.index(x, y, z, w);
+                        let is_active = self.cells[i];
+                        let count = self.count_neighbors_and_self(x, y, z, w) - (is_active as usize);
+                        self.next_cells[i] = match is_active {
+                            true => count == 2 || count == 3,
+                            false => count == 3
+                        };
+                    }
+                }
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.next_cells);
+    }
+
+    fn count_neighbors_and_self(&self, x : usize, y : usize, z : usize, w : usize) -> usize {
+        let mut count = 0;
+        for w in w-1..w+2 {
+            for z in z-1..z+2 {
+                for y in y-1..y+2 {
+                    for x in x-1..x+2 {
+                        count += self.test(x, y, z, w) as usize;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn count_active_cells(&self) -> usize {
+        let mut count = 0;
+        for &cell in &self.cells {
+            count += cell as usize;
+        }
+        count
+    }
+}
+
+/// Parses the initial slice of '#' (active) and '.' cells, up to the first
+/// blank line.
+pub fn parse_input(lines: &[String], diag: &mut Diagnostics) -> Result<Grid<bool>, ParseError> {
+    let end = lines.iter().position(|s| s.is_empty()).unwrap_or(lines.len());
+    Grid::parse(&lines[..end], diag, "'#' or '.'", |ch| match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None
+    })
+}
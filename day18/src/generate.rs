@@ -0,0 +1,120 @@
+use std::convert::TryFrom;
+use std::fmt;
+use aoc_common::{Answer, Generated, Rng};
+
+// Maximum number of numbers in an expression. Numbers are single digits,
+// so an expression's value is less than pow(9, MAX_NUMBERS), and the sum of
+// up to MAX_LINES expressions fits in a u64.
+const MAX_NUMBERS : usize = 16;
+const MAX_LINES : usize = 1000;
+
+// Maximum nesting depth of parentheses.
+const MAX_DEPTH : usize = 2;
+
+// An operand, which is a number or a parenthesized expression.
+enum Operand {
+    Number(u64),
+    Group(Expr)
+}
+
+// An expression, which is a sequence of operands separated by operators,
+// where true means '+' and false means '*'.
+struct Expr {
+    operands : Vec<Operand>,
+    is_add : Vec<bool>
+}
+
+impl Expr {
+    // Generates an expression with at most budget numbers, and subtracts
+    // the numbers used from the budget.
+    fn generate(rng : &mut Rng, depth : usize, budget : &mut usize) -> Expr {
+        let count = rng.range(2, 5) as usize;
+        let mut expr = Expr{ operands : Vec::new(), is_add : Vec::new() };
+        for i in 0..count {
+            // Reserve one number for each remaining operand.
+            let reserved = count - i - 1;
+            if i > 0 {
+                if *budget <= reserved {
+                    break;
+                }
+                expr.is_add.push(rng.chance(0.5));
+            }
+            if depth < MAX_DEPTH && *budget >= reserved + 2 && rng.chance(0.3) {
+                let mut group_budget = *budget - reserved;
+                let before = group_budget;
+                let group = Expr::generate(rng, depth + 1, &mut group_budget);
+                *budget -= before - group_budget;
+                expr.operands.push(Operand::Group(group));
+            }
+            else {
+                *budget -= 1;
+                expr.operands.push(Operand::Number(rng.range(1, 9) as u64));
+            }
+        }
+        expr
+    }
+
+    // Evaluates left to right, or with addition before multiplication if
+    // use_precedence is true.
+    fn eval(&self, use_precedence : bool) -> u64 {
+        let values : Vec<u64> = self.operands.iter().map(|operand| match operand {
+            Operand::Number(n) => *n,
+            Operand::Group(expr) => expr.eval(use_precedence)
+        }).collect();
+
+        if use_precedence {
+            // Add runs of added values, then multiply the sums.
+            let mut product = 1;
+            let mut sum = values[0];
+            for (i, &is_add) in self.is_add.iter().enumerate() {
+                if is_add {
+                    sum += values[i + 1];
+                }
+                else {
+                    product *= sum;
+                    sum = values[i + 1];
+                }
+            }
+            product * sum
+        }
+        else {
+            let mut result = values[0];
+            for (i, &is_add) in self.is_add.iter().enumerate() {
+                result = if is_add { result + values[i + 1] } else { result * values[i + 1] };
+            }
+            result
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, operand) in self.operands.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", if self.is_add[i - 1] { " + " } else { " * " })?;
+            }
+            match operand {
+                Operand::Number(n) => write!(f, "{}", n)?,
+                Operand::Group(expr) => write!(f, "({})", expr)?
+            }
+        }
+        Ok(())
+    }
+}
+
+// Generates size expressions (at most MAX_LINES).
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut lines = Vec::new();
+    let mut totals = [0, 0];
+    for _ in 0..size.clamp(1, MAX_LINES) {
+        let mut budget = MAX_NUMBERS;
+        let expr = Expr::generate(rng, 0, &mut budget);
+        totals[0] += expr.eval(false);
+        totals[1] += expr.eval(true);
+        lines.push(expr.to_string());
+    }
+    Generated::new(lines).with_answers(
+        Answer::try_from(totals[0]).ok(),
+        Answer::try_from(totals[1]).ok()
+    )
+}
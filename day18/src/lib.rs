@@ -0,0 +1,312 @@
+//! Day 18: Operation Order. Evaluates expressions in which addition and
+//! multiplication have the same precedence, or addition comes first.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, make_error};
+
+mod generate;
+
+/// The solution for day 18.
+pub struct Day18;
+
+impl Solution for Day18 {
+    // Expressions, one per line.
+    type Input = Vec<String>;
+    const DAY: u32 = 18;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<String>, ParseError> {
+        let mut v = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            match check_expression(line) {
+                Ok(()) => v.push(line.clone()),
+                Err(e) => diag.report(e.at_line(i + 1))?
+            }
+        }
+        Ok(v)
+    }
+
+    fn part1(&self, input: &Vec<String>) -> std::io::Result<Answer> {
+        Answer::try_from(get_total(input, false)?)
+    }
+
+    fn part2(&self, input: &Vec<String>) -> std::io::Result<Answer> {
+        Answer::try_from(get_total(input, true)?)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Checks that an expression contains only valid characters and balanced
+/// parentheses. Other errors are detected when the expression is evaluated.
+pub fn check_expression(line : &str) -> Result<(), ParseError> {
+    let mut depth = 0;
+    for (col, ch) in line.chars().enumerate() {
+        match ch {
+            '(' => { depth += 1; },
+            ')' => {
+                if depth == 0 {
+                    return Err(ParseError::new(col + 1, "digit, operator or '('", ")"));
+                }
+                depth -= 1;
+            },
+            '+' | '*' | ' ' => {},
+            _ if ch.is_ascii_digit() => {},
+            _ => {
+                return Err(ParseError::new(col + 1, "digit, operator or parenthesis", &ch.to_string()));
+            }
+        }
+    }
+    if depth != 0 {
+        return Err(ParseError::end_of_line(line, "')'"));
+    }
+    Ok(())
+}
+
+/// Returns the sum of the values of the expressions, or an error if any
+/// expression can't be evaluated.
+pub fn get_total(input : &[String], use_precedence : bool) -> std::io::Result<u64> {
+    let mut total = 0;
+    for line in input {
+        if let Some(n) = eval(line, use_precedence) {
+            total += n;
+        }
+        else {
+            return Err(make_error(&format!("Error evaluating expression: {}", &line)));
+        }
+    }
+    Ok(total)
+}
+
+/// A binary operator.
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpType {
+    /// Addition.
+    Add,
+
+    /// Multiplication.
+    Mul
+}
+
+use OpType::*;
+
+impl OpType {
+    /// Applies the operator to two operands.
+    pub fn eval(&self, lhs : u64, rhs : u64) -> u64 {
+        match self {
+            Add => lhs + rhs,
+            Mul => lhs * rhs
+        }
+    }
+}
+
+/// A token of an expression, or an entry on the evaluation stack.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Token {
+    /// No token, returned when looking below the bottom of the stack.
+    None,
+
+    /// A binary operator.
+    Operator (OpType),
+
+    /// A number, or the value of an evaluated subexpression.
+    Number (u64),
+
+    /// An opening parenthesis.
+    Open,
+
+    /// A closing parenthesis.
+    Close
+}
+
+use Token::Operator;
+use Token::Number;
+use Token::Open;
+use Token::Close;
+
+/// Evaluates an expression as its tokens are added, by reducing the
+/// operators on a stack.
+pub struct Stack {
+    stack : Vec<Token>,
+    use_precedence : bool
+}
+
+impl Stack {
+    /// Creates an empty stack. If use_precedence is true, addition is
+    /// evaluated before multiplication; otherwise, left to right.
+    pub fn new(use_precedence : bool) -> Stack {
+        Stack{ stack : Vec::new(), use_precedence }
+    }
+
+    fn frame(&self, index : usize) -> Token {
+        let c = self.stack.len();
+        if index < c {
+            self.stack[c - 1 - index]
+        }
+        else {
+            Token::None
+        }
+    }
+
+    fn top(&self) -> Token {
+        self.frame(0)
+    }
+
+    fn push_number(&mut self, value : u64) {
+        self.stack.push(Number(value));
+    }
+
+    fn push_op(&mut self, op : OpType) {
+        if self.use_precedence && op == Mul {
+            self.reduce_all();
+        }
+        self.stack.push(Operator(op));
+    }
+
+    fn push_group(&mut self) {
+        self.stack.push(Open);
+    }
+
+    fn pop(&mut self, count : usize) {
+        self.stack.resize(self.stack.len() - count, Token::None);
+    }
+
+    /// Returns the value of the expression, or None if it is incomplete.
+    pub fn result(&mut self) -> Option<u64> {
+        self.reduce_all();
+        if self.stack.len() == 1 {
+            if let Number(n) = self.stack[0] {
+                return Some(n);
+            }
+        }
+        None
+    }
+
+    /// Adds the next token of the expression, returning None if it is
+    /// out of place.
+    pub fn add_token(&mut self, tok : Token) -> Option<()> {
+        match tok {
+            Token::None =>
+                None,
+            Operator(op) => {
+                self.push_op(op);
+                Some(())
+            },
+            Number(value) => {
+                self.push_number(value);
+                self.reduce()?;
+                Some(())
+            }
+            Open => {
+                self.push_group();
+                Some(())
+            }
+            Close => {
+                self.close_group()?;
+                Some(())
+            }
+        }
+    }
+
+    fn close_group(&mut self) -> Option<()> {
+        self.reduce_all();
+        if let Number(value) = self.top() {
+            if self.frame(1) == Open {
+                self.pop(2);
+                self.push_number(value);
+                return self.reduce();
+            }
+        }
+        None
+    }
+
+    fn reduce_all(&mut self) -> Option<()> {
+        self.reduce_impl(true)
+    }
+
+    fn reduce(&mut self) -> Option<()> {
+        self.reduce_impl(!self.use_precedence)
+    }
+
+    fn reduce_impl(&mut self, force : bool) -> Option<()> {
+        while let Number(rhs) = self.top() {
+            if let Operator(op) = self.frame(1) {
+                if !force && op == Mul {
+                    break;
+                }
+
+                if let Number(lhs) = self.frame(2) {
+                    self.pop(3);
+                    self.push_number(op.eval(lhs, rhs));
+                }
+                else {
+                    // Error: no left operand.
+                    return None;
+                }
+            }
+            else {
+                break;
+            }
+        }
+        Some(())
+    }
+}
+
+/// Evaluates an expression of single-digit numbers, operators and
+/// parentheses. Returns None if the expression is malformed.
+pub fn eval(expr : &str, use_precedence : bool) -> Option<u64> {
+    let mut stack = Stack::new(use_precedence);
+
+    for ch in expr.chars() {
+        match ch {
+            '+' => { stack.add_token(Operator(OpType::Add))?; },
+            '*' => { stack.add_token(Operator(OpType::Mul))?; },
+            '(' => { stack.add_token(Open)?; },
+            ')' => { stack.add_token(Close)?; },
+            ' ' => {},
+            _ => {
+                if ch.is_ascii_digit() {
+                    stack.add_token(Number(ch as u64 - '0' as u64))?;
+                }
+                else {
+                    return None;
+                }
+            }
+        }
+    }
+
+    stack.result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_examples() {
+        assert_eq!(eval("1 + 2 * 3 + 4 * 5 + 6", false), Some(71));
+        assert_eq!(eval("1 + 2 * 3 + 4 * 5 + 6", true), Some(231));
+        assert_eq!(eval("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", false), Some(13632));
+        assert_eq!(eval("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", true), Some(23340));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert_eq!(eval("1 +", false), None);
+        assert_eq!(eval("* 2", true), None);
+        assert_eq!(eval("(1 + 2", false), None);
+        assert_eq!(eval("1 + 2)", false), None);
+    }
+
+    #[test]
+    fn stack_evaluates_tokens() {
+        let mut stack = Stack::new(true);
+        for &token in &[Open, Number(2), Operator(Mul), Number(3), Close, Operator(Add), Number(1)] {
+            assert_eq!(stack.add_token(token), Some(()));
+        }
+        assert_eq!(stack.result(), Some(6 + 1));
+    }
+}
@@ -0,0 +1,133 @@
+use aoc_common::{Answer, Generated, Rng};
+
+// Number of levels of rules above the terminals, and the number of rules
+// at each level. Rule 0 is the only rule at the top level.
+const LEVELS : usize = 4;
+const RULES_PER_LEVEL : usize = 4;
+
+// A generated rule, which is a terminal or one or two alternative
+// sequences of rule indices.
+enum GenRule {
+    Terminal(char),
+    Alternatives(Vec<Vec<usize>>)
+}
+
+struct Grammar {
+    rules : Vec<GenRule>
+}
+
+impl Grammar {
+    // Generates rules in levels, where each rule refers only to rules in
+    // the level below, so there are no cycles. Rule 0 is the last rule.
+    fn generate(rng : &mut Rng) -> Grammar {
+        let mut rules = vec![GenRule::Terminal('a'), GenRule::Terminal('b')];
+        let mut below = vec![0, 1];
+        for level in 0..=LEVELS {
+            let count = if level == LEVELS { 1 } else { RULES_PER_LEVEL };
+            let mut current = Vec::new();
+            for _ in 0..count {
+                let alt_count = if level == LEVELS { 1 } else { rng.range(1, 2) as usize };
+                let alternatives = (0..alt_count).map(|_| {
+                    let len = if level == LEVELS { 3 } else { rng.range(1, 2) as usize };
+                    (0..len).map(|_| *rng.choose(&below)).collect()
+                }).collect();
+                current.push(rules.len());
+                rules.push(GenRule::Alternatives(alternatives));
+            }
+            below = current;
+        }
+        Grammar{ rules }
+    }
+
+    fn root(&self) -> usize {
+        self.rules.len() - 1
+    }
+
+    // Appends a random message matching the specified rule.
+    fn derive(&self, rng : &mut Rng, rule : usize, message : &mut String) {
+        match &self.rules[rule] {
+            GenRule::Terminal(ch) => message.push(*ch),
+            GenRule::Alternatives(alternatives) => {
+                for &r in rng.choose(alternatives) {
+                    self.derive(rng, r, message);
+                }
+            }
+        }
+    }
+
+    // Returns the positions where a match of the rule starting at pos can end.
+    fn match_ends(&self, rule : usize, message : &[u8], pos : usize) -> Vec<usize> {
+        match &self.rules[rule] {
+            GenRule::Terminal(ch) => {
+                if message.get(pos) == Some(&(*ch as u8)) { vec![pos + 1] } else { Vec::new() }
+            },
+            GenRule::Alternatives(alternatives) => {
+                let mut ends = Vec::new();
+                for sequence in alternatives {
+                    let mut positions = vec![pos];
+                    for &r in sequence {
+                        let mut next : Vec<usize> = positions.iter()
+                            .flat_map(|&p| self.match_ends(r, message, p))
+                            .collect();
+                        next.sort_unstable();
+                        next.dedup();
+                        positions = next;
+                    }
+                    ends.extend(positions);
+                }
+                ends
+            }
+        }
+    }
+
+    fn is_match(&self, message : &str) -> bool {
+        self.match_ends(self.root(), message.as_bytes(), 0).contains(&message.len())
+    }
+}
+
+// Generates an acyclic grammar and size messages, about half of which match
+// rule 0. Part 2 replaces rules with cycles, so it has no known answer.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let grammar = Grammar::generate(rng);
+
+    // Assign rule numbers, with 0 for the root.
+    let mut numbers : Vec<usize> = (1..grammar.rules.len()).collect();
+    rng.shuffle(&mut numbers);
+    numbers.insert(grammar.root(), 0);
+
+    let mut lines : Vec<String> = grammar.rules.iter().enumerate().map(|(i, rule)| {
+        let body = match rule {
+            GenRule::Terminal(ch) => format!("\"{}\"", ch),
+            GenRule::Alternatives(alternatives) => alternatives.iter().map(|sequence| {
+                sequence.iter().map(|&r| numbers[r].to_string()).collect::<Vec<_>>().join(" ")
+            }).collect::<Vec<_>>().join(" | ")
+        };
+        format!("{}: {}", numbers[i], body)
+    }).collect();
+    rng.shuffle(&mut lines);
+    lines.push(String::new());
+
+    // Derive matching messages, and change some of them so they (usually)
+    // don't match.
+    let mut match_count = 0;
+    for _ in 0..size {
+        let mut message = String::new();
+        grammar.derive(rng, grammar.root(), &mut message);
+        if rng.chance(0.5) {
+            let mut bytes = message.into_bytes();
+            let i = rng.index(bytes.len());
+            match rng.index(3) {
+                0 => { bytes[i] = if bytes[i] == b'a' { b'b' } else { b'a' }; },
+                1 => { bytes.remove(i); },
+                _ => { bytes.insert(i, *rng.choose(b"ab")); }
+            }
+            message = String::from_utf8(bytes).unwrap();
+        }
+        if grammar.is_match(&message) {
+            match_count += 1;
+        }
+        lines.push(message);
+    }
+
+    Generated::new(lines).with_answers(Some(Answer::from(match_count)), None)
+}
@@ -1,50 +1,72 @@
-use std::fs;
-use std::io::{prelude::*, BufReader};
+//! Parsing the grammar rules and messages of the input.
 
+use aoc_common::{ParseError, Diagnostics};
+use aoc_common::parser::{Parser, parse_all, literal, spaces, token, unsigned, map, pair, preceded, terminated, alt, optional, many1, cut};
+
+/// A token matched by a transition.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Token {
+    /// Matches without consuming a character.
     Nil,
+
+    /// The character 'a'.
     A,
+
+    /// The character 'b'.
     B
 }
 
+/// A grammar rule. Rules refer to other rules by number.
 #[derive(Clone)]
 pub enum Rule {
+    /// An undefined rule, which matches the empty string.
     Void,
+
+    /// Matches a single character.
     Terminal(Token),
+
+    /// Matches each of the rules in turn.
     Sequence(Vec<usize>),
+
+    /// Matches either sequence of rules.
     Choice(Vec<usize>, Vec<usize>)
 }
 use Rule::*;
 
 impl Rule {
-    fn parse(input : &str) -> Option<Rule> {
-        let mut chars = input.chars();
-        let first_char = chars.next()?;
-        if first_char == '\"' {
-            match chars.next()? {
-                'a' => Some(Terminal(Token::A)),
-                'b' => Some(Terminal(Token::B)),
-                _ => None
+    // Returns a parser for the right-hand side of a rule, which is a
+    // quoted character, a sequence of rule numbers, or two sequences
+    // separated by '|'.
+    fn parser<'a>() -> impl Parser<'a, Rule> {
+        let token_char = alt(
+            map(literal("a"), |_| Token::A),
+            map(literal("b"), |_| Token::B)
+        );
+        let terminal = map(
+            preceded(token(literal("\"")), cut(terminated(token_char, literal("\"")))),
+            Terminal
+        );
+        let sequences = map(
+            pair(Rule::sequence(), optional(preceded(token(literal("|")), cut(Rule::sequence())))),
+            |(first, second)| match second {
+                Some(second) => Choice(first, second),
+                None => Sequence(first)
             }
-        }
-        else if let Some(i) = input.find('|') {
-            Some(Choice(
-                Rule::parse_sequence(&input[0..i])?,
-                Rule::parse_sequence(&input[i + 1..])?
-            ))
-        }
-        else {
-            Some(Sequence(Rule::parse_sequence(input)?))
-        }
+        );
+        alt(terminal, sequences)
     }
 
-    fn parse_sequence(input : &str) -> Option<Vec<usize>> {
-        let mut vec = Vec::new();
-        for s in input.trim().split(' ') {
-            vec.push(s.parse::<usize>().ok()?);
+    fn sequence<'a>() -> impl Parser<'a, Vec<usize>> {
+        many1(token(unsigned("a rule number")))
+    }
+
+    // Returns the highest rule number referenced by this rule, or 0 if none.
+    fn max_reference(&self) -> usize {
+        match self {
+            Sequence(v) => v.iter().copied().max().unwrap_or(0),
+            Choice(a, b) => a.iter().chain(b).copied().max().unwrap_or(0),
+            _ => 0
         }
-        if vec.is_empty() { None } else { Some(vec) }
     }
 }
 
@@ -56,45 +78,77 @@ impl Grammar {
     fn new() -> Grammar {
         Grammar{ rules : Vec::new() }
     }
-    fn add_rule(&mut self, input : &str) -> Option<()> {
-        // Get the rule id.
-        let i = input.find(':')?;
-        let rule_num = input[0..i].parse::<usize>().ok()?;
-
-        // Parse the rule.
-        let rule = Rule::parse(input[i + 1..].trim())?;
+    fn add_rule(&mut self, input : &str) -> Result<(), ParseError> {
+        // A rule is "<rule number>: <right-hand side>".
+        let rule = pair(
+            terminated(token(unsigned::<usize>("a rule number")), token(literal(":"))),
+            terminated(Rule::parser(), spaces())
+        );
+        let (rule_num, rule) = parse_all(input, rule)?;
 
         if self.rules.len() <= rule_num {
             self.rules.resize(rule_num + 1, Rule::Void);
         }
 
         self.rules[rule_num] = rule;
-        Some(())
+        Ok(())
     }
 }
 
-fn make_error(message: &str) -> std::io::Error {
-    std::io::Error::new(std::io::ErrorKind::Other, message)
-} 
-
-pub fn read_file(path: &str) -> std::io::Result<(Vec<Rule>, Vec::<String>)> {
+/// Parses the rules, indexed by rule number, and the messages that follow
+/// them after a blank line. Malformed rules are reported to diag.
+pub fn parse_input(lines: &[String], diag: &mut Diagnostics) -> Result<(Vec<Rule>, Vec::<String>), ParseError> {
     let mut grammar = Grammar::new();
     let mut input = Vec::new();
     let mut in_grammar = true;
 
-    for line in BufReader::new(fs::File::open(path)?).lines() {
-        let s = line?;
+    for (i, s) in lines.iter().enumerate() {
         if s.is_empty() {
             in_grammar = false;
         }
         else if in_grammar {
-            grammar.add_rule(&s).ok_or(
-                make_error(&format!("Invalid rule: {}", &s))
-            )?;
+            if let Err(e) = grammar.add_rule(s) {
+                diag.report(e.at_line(i + 1))?;
+            }
         }
         else {
-            input.push(s);
+            input.push(s.clone());
         }
     }
+
+    // Rules that are referenced but not defined (including rule 0) match
+    // the empty string, so the NFA never indexes past the end of the rules.
+    let max_ref = grammar.rules.iter().map(|rule| rule.max_reference()).max().unwrap_or(0);
+    if grammar.rules.len() <= max_ref {
+        grammar.rules.resize(max_ref + 1, Rule::Void);
+    }
     Ok((grammar.rules, input))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_rule(input : &str) -> Result<Rule, ParseError> {
+        let mut grammar = Grammar::new();
+        grammar.add_rule(input)?;
+        Ok(grammar.rules.pop().unwrap())
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert!(matches!(parse_rule("4: \"a\""), Ok(Terminal(Token::A))));
+        assert!(matches!(parse_rule("0: 4 1 5"), Ok(Sequence(v)) if v == vec![4, 1, 5]));
+        assert!(matches!(parse_rule("1: 2 3 | 3 2"), Ok(Choice(a, b)) if a == vec![2, 3] && b == vec![3, 2]));
+    }
+
+    #[test]
+    fn reports_malformed_rules() {
+        let e = parse_rule("4: \"c\"").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (5, "'a' or 'b'", "c"));
+        let e = parse_rule("1: 2 3 | x").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (10, "a rule number"));
+        let e = parse_rule("1 2 3").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (3, "':'"));
+    }
+}
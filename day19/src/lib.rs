@@ -0,0 +1,58 @@
+//! Day 19: Monster Messages. Counts the messages that match rule 0 of a
+//! grammar, using an NFA built from the rules.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, ParseMode, Diagnostics, Generated, Rng, make_error};
+
+mod generate;
+pub mod grammar;
+pub mod nfa;
+
+/// The solution for day 19.
+pub struct Day19;
+
+impl Solution for Day19 {
+    // Grammar rules and the messages to match.
+    type Input = (Vec<grammar::Rule>, Vec<String>);
+    const DAY: u32 = 19;
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError> {
+        grammar::parse_input(lines, diag)
+    }
+
+    // Number of messages that match rule 0.
+    fn part1(&self, (rules, input): &Self::Input) -> std::io::Result<Answer> {
+        Answer::try_from(count_matches(rules, input))
+    }
+
+    // Part 2 replaces rules 8 and 11 with rules that contain cycles
+    // (see input2.txt), which the NFA can't represent.
+    fn part2(&self, _input: &Self::Input) -> std::io::Result<Answer> {
+        Err(make_error("Part 2 is not implemented (rules with cycles)."))
+    }
+
+    // The transitions of the NFA for the rules.
+    fn artifacts(&self, (rules, _input): &Self::Input) -> Vec<(&'static str, String)> {
+        vec![("transitions", nfa::Nfa::new(rules).transitions_text())]
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Returns the number of messages that match rule 0 of the specified rules.
+pub fn count_matches(rules : &[grammar::Rule], messages : &[String]) -> usize {
+    let nfa = nfa::Nfa::new(rules);
+    messages.iter().filter(|message| nfa.is_match(message)).count()
+}
+
+/// Builds the NFA for the rules in the specified input, and writes its
+/// state transitions to a file.
+pub fn write_transitions(lines: &[String], path : &str) -> std::io::Result<()> {
+    let mut diag = Diagnostics::new(ParseMode::Strict, None);
+    let (rules, _input) = grammar::parse_input(lines, &mut diag)?;
+    nfa::Nfa::new(&rules).write_transitions(path)
+}
@@ -1,30 +1,28 @@
-mod grammar;
-mod nfa;
+use std::path::Path;
+use aoc_common::{InputSource, Solver, INPUT_OPTIONS_USAGE, make_error};
 
 fn main() -> std::io::Result<()> {
-
-    // Part 1
-    test_input("input.txt")?;
-
-    // Part 2 - contains cycles
-    //test_input("input2.txt")?;
-
-    Ok(())
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(|s| s.as_str()) == Some("--transitions") {
+        write_transitions(&args[1..])
+    }
+    else {
+        aoc_common::run_main(&day19::Day19, env!("CARGO_MANIFEST_DIR"))
+    }
 }
 
-fn test_input(path : &str) -> std::io::Result<()> {
-    let (rules, input) = grammar::read_file(path)?;
-
-    let nfa = nfa::Nfa::new(&rules);
-    nfa.write_transitions("transitions.txt")?;
-
-    let mut match_count = 0;
-    for line in &input[..] {
-        let is_match = nfa.is_match(line);
-        //println!("{} -> {}", line, is_match);
-        if is_match { match_count += 1; }
+// Writes the state transitions of the NFA for the selected input to a file,
+// for debugging. The arguments are the output path and input options.
+fn write_transitions(args : &[String]) -> std::io::Result<()> {
+    let usage = || make_error(&format!("Usage: day19 --transitions <path> {}", INPUT_OPTIONS_USAGE));
+    let mut iter = args.iter();
+    let path = iter.next().ok_or_else(usage)?;
+    let mut source = InputSource::Default;
+    while let Some(arg) = iter.next() {
+        source = InputSource::from_arg(arg, &mut iter)?.ok_or_else(usage)?;
     }
-    println!("Matched {} of {}", match_count, input.len());
- 
-    Ok(())
-}
\ No newline at end of file
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lines = source.read_lines(dir, day19::Day19.input_file())?;
+    day19::write_transitions(&lines, path)
+}
@@ -1,18 +1,24 @@
+//! A nondeterministic finite automaton that matches messages against
+//! grammar rules without cycles.
+
 use super::grammar::*;
+use aoc_common::trace;
 use Rule::*;
 use std::fs;
-use std::io::{prelude::*, BufWriter};
 
 const START_STATE : usize = 0;
 const END_STATE : usize = 1;
 
+/// The NFA for rule 0 of a grammar.
 pub struct Nfa{
-    pub state_count : usize,
-    pub transitions : Vec<(usize, Token, usize)>,
-    pub transition_ranges : Vec<(usize, usize)>
+    state_count : usize,
+    transitions : Vec<(usize, Token, usize)>,
+    transition_ranges : Vec<(usize, usize)>
 }
 
 impl Nfa{
+    /// Builds the NFA for rule 0 of the specified rules, which must not
+    /// contain cycles.
     pub fn new(rules : &[Rule]) -> Nfa {
 
         // Initialize the NFA with no transitions and two states:
@@ -54,14 +60,14 @@ impl Nfa{
             Sequence(v) => {
                 // Add states for a sequence, and return the end state of
                 // the sequence.
-                self.add_sequence(rules, &v, prev_state)
+                self.add_sequence(rules, v, prev_state)
             },
             Choice(a, b) => {
                 // Add states for each of the alternate sequences.
                 // The previous state will have transitions to the start states
                 // of both sequences, making this an NFA rather than a DFA.
-                let end1 = self.add_sequence(rules, &a, prev_state);
-                let end2 = self.add_sequence(rules, &b, prev_state);
+                let end1 = self.add_sequence(rules, a, prev_state);
+                let end2 = self.add_sequence(rules, b, prev_state);
 
                 // Add final state for the choice.
                 let new_state = self.state_count;
@@ -89,18 +95,21 @@ impl Nfa{
         last_state
     }
 
-    pub fn write_transitions(&self, path : &str) -> std::io::Result<()> {
-        let mut writer = BufWriter::new(fs::File::create(path)?);
+    /// Returns the state transitions, one per line, as the from state,
+    /// token and to state.
+    pub fn transitions_text(&self) -> String {
+        let mut text = String::new();
         for &(from, token, to) in &self.transitions {
-            let line = format!(
-                "{}, {}, {}\n",
-                from,
-                match token { Token::A => "a", Token::B => "b", _ => "" },
-                to
-            );
-            writer.write(line.as_bytes())?;
+            let token = match token { Token::A => "a", Token::B => "b", _ => "" };
+            text.push_str(&format!("{}, {}, {}\n", from, token, to));
         }
-        Ok(())
+        text
+    }
+
+    /// Writes the state transitions to a file, in the format returned by
+    /// transitions_text.
+    pub fn write_transitions(&self, path : &str) -> std::io::Result<()> {
+        fs::write(path, self.transitions_text())
     }
 
     fn finalize(&mut self) {
@@ -135,12 +144,16 @@ impl Nfa{
         &self.transitions[begin..end]
     }
 
+    /// Returns true if the input matches rule 0.
     pub fn is_match(&self, input : &str) -> bool {
         let chars : Vec<char> = input.chars().collect();
-        self.match_rule(START_STATE, &chars)
+        let result = self.match_rule(START_STATE, &chars);
+        trace!("{}: {}", input, if result { "match" } else { "no match" });
+        result
     }
 
     fn match_rule(&self, state_index : usize, input : &[char]) -> bool {
+        trace!("state {} with {} characters left", state_index, input.len());
         if input.is_empty() {
             self.match_end(state_index)
         }
@@ -152,10 +165,8 @@ impl Nfa{
             };
             for &(from, token, to) in self.find_transitions(state_index) {
                 assert_eq!(from, state_index);
-                if token == t && self.match_rule(to, &input[1..]) {
-                    return true;
-                }
-                else if token == Token::Nil && self.match_rule(to, input) {
+                if (token == t && self.match_rule(to, &input[1..])) ||
+                    (token == Token::Nil && self.match_rule(to, input)) {
                     return true;
                 }
             }
@@ -0,0 +1,164 @@
+//! A report of which rules each password follows, and why it fails the
+//! others.
+
+use aoc_common::{ParseError, Diagnostics, Json};
+use super::parse_line;
+use super::policy::PolicySet;
+
+/// The results of checking the password on one line of the input. The
+/// password itself isn't kept, so the report can be shared.
+pub struct AuditEntry {
+    /// One-based line number.
+    pub line : usize,
+
+    /// The policy on the line, such as `1-3 a`.
+    pub policy : String,
+
+    /// Each rule, as written in a policy file, and Ok if the password
+    /// follows it, or the reason it doesn't.
+    pub results : Vec<(String, Result<(), String>)>
+}
+
+impl AuditEntry {
+    /// Returns true if the password follows every rule.
+    pub fn is_valid(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.is_ok())
+    }
+}
+
+/// Checks the password on each line against every rule. Malformed lines
+/// are reported to diag, which determines whether auditing fails or skips
+/// them.
+pub fn audit(lines : &[String], policies : &PolicySet, diag : &mut Diagnostics) -> Result<Vec<AuditEntry>, ParseError> {
+    let mut entries = Vec::new();
+    for (i, s) in lines.iter().enumerate() {
+        match parse_line(s) {
+            Ok((line_policy, password)) => {
+                let results = policies.policies().iter()
+                    .map(|policy| (policy.to_string(), policy.check(&line_policy, password)))
+                    .collect();
+                entries.push(AuditEntry{ line : i + 1, policy : line_policy.to_string(), results });
+            },
+            Err(e) => diag.report(e.at_line(i + 1))?
+        }
+    }
+    Ok(entries)
+}
+
+/// Formats the report as text, with a line for each password and an
+/// indented line for each rule, saying "pass" or why it fails.
+pub fn to_text(entries : &[AuditEntry]) -> String {
+    let mut text = String::new();
+    for entry in entries {
+        let status = if entry.is_valid() { "valid" } else { "invalid" };
+        text.push_str(&format!("Line {} ({}): {}\n", entry.line, entry.policy, status));
+        for (rule, result) in &entry.results {
+            match result {
+                Ok(()) => text.push_str(&format!("    {}: pass\n", rule)),
+                Err(reason) => text.push_str(&format!("    {}: fail: {}\n", rule, reason))
+            }
+        }
+    }
+    let valid_count = entries.iter().filter(|entry| entry.is_valid()).count();
+    text.push_str(&format!("{} of {} passwords are valid.\n", valid_count, entries.len()));
+    text
+}
+
+/// Formats the report as CSV, with a header row and then a row for each
+/// rule of each password: line, policy, rule, result ("pass" or "fail")
+/// and reason (empty if it passed).
+pub fn to_csv(entries : &[AuditEntry]) -> String {
+    let mut text = String::from("line,policy,rule,result,reason\n");
+    for entry in entries {
+        for (rule, result) in &entry.results {
+            let (status, reason) = match result {
+                Ok(()) => ("pass", ""),
+                Err(reason) => ("fail", reason.as_str())
+            };
+            let fields = [entry.line.to_string(), csv_field(&entry.policy), csv_field(rule), status.to_string(), csv_field(reason)];
+            text.push_str(&fields.join(","));
+            text.push('\n');
+        }
+    }
+    text
+}
+
+// Quotes a CSV field if it contains a comma, quote or line break.
+fn csv_field(s : &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    }
+    else {
+        s.to_string()
+    }
+}
+
+/// Formats the report as a JSON array with an object for each password,
+/// with "line", "policy", "valid" and "rules" members, where each rule is
+/// an object with "rule", "pass" and, if it failed, "reason" members.
+pub fn to_json(entries : &[AuditEntry]) -> Json {
+    Json::Array(entries.iter().map(|entry| {
+        let rules = entry.results.iter().map(|(rule, result)| {
+            let object = Json::object()
+                .with("rule", rule.as_str().into())
+                .with("pass", result.is_ok().into());
+            match result {
+                Ok(()) => object,
+                Err(reason) => object.with("reason", reason.as_str().into())
+            }
+        }).collect();
+        Json::object()
+            .with("line", entry.line.into())
+            .with("policy", entry.policy.as_str().into())
+            .with("valid", entry.is_valid().into())
+            .with("rules", Json::Array(rules))
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::ParseMode;
+
+    fn example_audit() -> Vec<AuditEntry> {
+        let rules : Vec<String> = ["count", "positions exactly-one", "forbid cd,e fg"].iter().map(|s| s.to_string()).collect();
+        let policies = PolicySet::parse(&rules, &mut Diagnostics::new(ParseMode::Strict, None)).unwrap();
+        let lines : Vec<String> = ["1-3 a: abcde", "1-3 b: cdefg", "x", "2-9 c: ccccccccc"].iter().map(|s| s.to_string()).collect();
+        let mut diag = Diagnostics::new(ParseMode::Lenient, None);
+        let entries = audit(&lines, &policies, &mut diag).unwrap();
+        assert_eq!(diag.errors().len(), 1);
+        entries
+    }
+
+    #[test]
+    fn reports_reasons() {
+        let entries = example_audit();
+        assert_eq!(to_text(&entries[..2]), concat!(
+            "Line 1 (1-3 a): valid\n",
+            "    count: pass\n",
+            "    positions exactly-one: pass\n",
+            "    forbid cd,e fg: pass\n",
+            "Line 2 (1-3 b): invalid\n",
+            "    count: fail: 'b' appears 0 times, allowed 1-3\n",
+            "    positions exactly-one: fail: neither position 1 nor 3 is 'b'\n",
+            "    forbid cd,e fg: fail: contains 'fg'\n",
+            "1 of 2 passwords are valid.\n"
+        ));
+        assert_eq!(entries[2].line, 4);
+    }
+
+    #[test]
+    fn exports_csv_and_json() {
+        let entries = example_audit();
+        let csv = to_csv(&entries);
+        let rows : Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 1 + 3 * 3);
+        assert_eq!(rows[0], "line,policy,rule,result,reason");
+        assert_eq!(rows[3], "1,1-3 a,\"forbid cd,e fg\",pass,");
+        assert_eq!(rows[6], "2,1-3 b,\"forbid cd,e fg\",fail,contains 'fg'");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+
+        let json = to_json(&entries[..1]).to_string();
+        assert!(json.starts_with("[{\"line\":1,\"policy\":\"1-3 a\",\"valid\":true,\"rules\":[{\"rule\":\"count\",\"pass\":true}"), "{}", json);
+    }
+}
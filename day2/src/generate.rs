@@ -0,0 +1,24 @@
+use aoc_common::{Generated, Rng};
+
+// Generates size lines, each with a password policy and a password.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut lines = Vec::new();
+    for _ in 0..size {
+        let len = rng.range(3, 20);
+        let min = rng.range(1, len - 1);
+        let max = rng.range(min + 1, len);
+        let ch = random_letter(rng);
+
+        // Bias the password toward the policy's letter, so the policies
+        // are satisfied some of the time.
+        let password : String = (0..len)
+            .map(|_| if rng.chance(0.3) { ch } else { random_letter(rng) })
+            .collect();
+        lines.push(format!("{}-{} {}: {}", min, max, ch, password));
+    }
+    Generated::new(lines)
+}
+
+fn random_letter(rng : &mut Rng) -> char {
+    (b'a' + rng.below(26) as u8) as char
+}
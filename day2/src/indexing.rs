@@ -0,0 +1,180 @@
+//! What the positions of a positional rule count: bytes, characters or
+//! grapheme clusters.
+
+/// The unit that one-based positions in a password count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indexing {
+    /// Bytes of the UTF-8 encoding. A position holds a character if the
+    /// character's encoding starts at that byte, so a position inside a
+    /// multi-byte character holds no character.
+    Bytes,
+
+    /// Characters (Unicode scalar values), as in the puzzle. A letter
+    /// followed by a combining accent is two characters.
+    Chars,
+
+    /// Grapheme clusters: what a reader sees as one character, such as a
+    /// letter with its combining accents, or an emoji built from several
+    /// characters. A position holds a character only if its cluster is
+    /// just that character. See `graphemes` for the rules used.
+    Graphemes
+}
+
+impl Indexing {
+    /// Returns the indexing with the specified name: "bytes", "chars" or
+    /// "graphemes".
+    pub fn from_name(name : &str) -> Option<Indexing> {
+        match name {
+            "bytes" => Some(Indexing::Bytes),
+            "chars" => Some(Indexing::Chars),
+            "graphemes" => Some(Indexing::Graphemes),
+            _ => None
+        }
+    }
+
+    /// Returns the name of the indexing.
+    pub fn name(self) -> &'static str {
+        match self {
+            Indexing::Bytes => "bytes",
+            Indexing::Chars => "chars",
+            Indexing::Graphemes => "graphemes"
+        }
+    }
+}
+
+/// Returns the text of the units at each of the zero-based positions, or
+/// None for a position past the end. In byte indexing, the text is the
+/// character starting at the byte, or empty if the byte is inside one.
+///
+/// The password is scanned once, up to the last position, rather than once
+/// for each position. An ASCII password is indexed directly, since its
+/// bytes, characters and grapheme clusters are the same (except for a CR
+/// followed by LF, which is one cluster).
+pub fn units_at<'a>(password : &'a str, indexing : Indexing, positions : &[usize]) -> Vec<Option<&'a str>> {
+    if password.is_ascii() && (indexing != Indexing::Graphemes || !password.contains("\r\n")) {
+        return positions.iter().map(|&pos| password.get(pos..=pos)).collect();
+    }
+
+    let mut units = vec![None; positions.len()];
+    let last = match positions.iter().max() {
+        Some(&last) => last,
+        None => { return units; }
+    };
+    let mut record = |pos : usize, unit : &'a str| {
+        for (i, &p) in positions.iter().enumerate() {
+            if p == pos {
+                units[i] = Some(unit);
+            }
+        }
+    };
+    match indexing {
+        Indexing::Bytes => {
+            for pos in 0..password.len().min(last.saturating_add(1)) {
+                let unit = match password.get(pos..) {
+                    Some(rest) => rest.chars().next().map_or("", |ch| &rest[..ch.len_utf8()]),
+                    None => ""
+                };
+                record(pos, unit);
+            }
+        },
+        Indexing::Chars => {
+            for (pos, (start, ch)) in password.char_indices().take(last.saturating_add(1)).enumerate() {
+                record(pos, &password[start..start + ch.len_utf8()]);
+            }
+        },
+        Indexing::Graphemes => {
+            for (pos, unit) in graphemes(password).take(last.saturating_add(1)).enumerate() {
+                record(pos, unit);
+            }
+        }
+    }
+    units
+}
+
+/// Splits text into grapheme clusters, using a simplified form of the
+/// Unicode rules (UAX #29) that needs no tables: CR LF is one cluster, and
+/// a cluster extends over combining marks in the main combining blocks,
+/// variation selectors, emoji skin tone modifiers, tag characters, and a
+/// zero width joiner along with the character after it. Two regional
+/// indicators (a flag) are one cluster. Hangul syllable sequences and
+/// spacing marks of most scripts other than Devanagari aren't joined.
+pub fn graphemes(text : &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices().peekable();
+        let (_, first) = chars.next()?;
+        let mut end = first.len_utf8();
+        let mut joined = false;
+        let mut regional_count = if is_regional_indicator(first) { 1 } else { 0 };
+        while let Some(&(i, ch)) = chars.peek() {
+            let extends = joined
+                || (first == '\r' && ch == '\n' && i == 1)
+                || is_extender(ch)
+                || (regional_count == 1 && is_regional_indicator(ch));
+            if !extends || first == '\n' || (first == '\r' && ch != '\n') {
+                break;
+            }
+            joined = ch == '\u{200D}';
+            if is_regional_indicator(ch) {
+                regional_count += 1;
+            }
+            end = i + ch.len_utf8();
+            chars.next();
+        }
+        let (cluster, next) = rest.split_at(end);
+        rest = next;
+        Some(cluster)
+    })
+}
+
+// Returns true for characters that continue the cluster before them.
+fn is_extender(ch : char) -> bool {
+    matches!(ch as u32,
+        0x0300..=0x036F | 0x0483..=0x0489 | 0x0591..=0x05BD | 0x05BF | 0x05C1..=0x05C2 | 0x05C4..=0x05C5 | 0x05C7 |
+        0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06DC | 0x06DF..=0x06E4 | 0x06E7..=0x06E8 | 0x06EA..=0x06ED |
+        0x0900..=0x0903 | 0x093A..=0x093C | 0x093E..=0x094F | 0x0951..=0x0957 | 0x0962..=0x0963 |
+        0x0E31 | 0x0E34..=0x0E3A | 0x0E47..=0x0E4E |
+        0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x200C..=0x200D | 0x20D0..=0x20FF | 0x302A..=0x302F | 0x3099..=0x309A |
+        0xFE00..=0xFE0F | 0xFE20..=0xFE2F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F | 0xE0100..=0xE01EF
+    )
+}
+
+fn is_regional_indicator(ch : char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_graphemes() {
+        let clusters : Vec<&str> = graphemes("e\u{301}a\r\nb\u{1F44D}\u{1F3FD}\u{1F1F8}\u{1F1EA}\u{1F1F3}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}").collect();
+        assert_eq!(clusters, vec![
+            "e\u{301}", "a", "\r\n", "b", "\u{1F44D}\u{1F3FD}", "\u{1F1F8}\u{1F1EA}", "\u{1F1F3}",
+            "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"
+        ]);
+        assert_eq!(graphemes("\u{301}x\n\u{301}").collect::<Vec<&str>>(), vec!["\u{301}", "x", "\n", "\u{301}"]);
+        assert_eq!(graphemes("").count(), 0);
+    }
+
+    #[test]
+    fn indexes_multi_byte_passwords() {
+        // "é" as e and a combining acute accent, then "ab".
+        let password = "e\u{301}ab";
+        assert_eq!(units_at(password, Indexing::Chars, &[0, 1, 2, 4]), vec![Some("e"), Some("\u{301}"), Some("a"), None]);
+        assert_eq!(units_at(password, Indexing::Graphemes, &[0, 1, 2, 3]), vec![Some("e\u{301}"), Some("a"), Some("b"), None]);
+        assert_eq!(units_at(password, Indexing::Bytes, &[0, 1, 2, 3, 4, 5]), vec![Some("e"), Some("\u{301}"), Some(""), Some("a"), Some("b"), None]);
+
+        // Precomposed "é" is one character of two bytes.
+        let password = "\u{e9}t\u{e9}";
+        assert_eq!(units_at(password, Indexing::Chars, &[2, 0]), vec![Some("\u{e9}"), Some("\u{e9}")]);
+        assert_eq!(units_at(password, Indexing::Bytes, &[2, 3, 4]), vec![Some("t"), Some("\u{e9}"), Some("")]);
+
+        // ASCII passwords take the fast path, and every indexing agrees.
+        for &indexing in &[Indexing::Bytes, Indexing::Chars, Indexing::Graphemes] {
+            assert_eq!(units_at("abcde", indexing, &[0, 2, 5]), vec![Some("a"), Some("c"), None]);
+        }
+        assert_eq!(units_at("a\r\nb", Indexing::Graphemes, &[1, 2]), vec![Some("\r\n"), Some("b")]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Day 2: Password Philosophy. Counts the passwords that are valid
+//! according to their policies, under two interpretations of the policy.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use std::fmt;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng};
+use aoc_common::parser::{Parser, parse_all, literal, any_char, take_while1, rest, spaces, token, unsigned, map, pair, triple, preceded, terminated};
+
+mod generate;
+pub mod audit;
+pub mod indexing;
+pub mod passwords;
+pub mod policy;
+
+use indexing::Indexing;
+use policy::{Policy, CountRange, Positions, PositionMode};
+
+/// The solution for day 2.
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<(PasswordPolicy, String)>;
+    const DAY: u32 = 2;
+    const INPUT_FILE: &'static str = "day2-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError> {
+        let mut v = Vec::new();
+        for (i, s) in lines.iter().enumerate() {
+            match parse_line(s) {
+                Ok((policy, password)) => v.push((policy, password.to_string())),
+                Err(e) => diag.report(e.at_line(i + 1))?
+            }
+        }
+        Ok(v)
+    }
+
+    fn part1(&self, input: &Self::Input) -> std::io::Result<Answer> {
+        let valid_count = input.iter()
+            .filter(|(policy, password)| is_valid_password(policy, password))
+            .count();
+        Answer::try_from(valid_count)
+    }
+
+    fn part2(&self, input: &Self::Input) -> std::io::Result<Answer> {
+        let valid_count = input.iter()
+            .filter(|(policy, password)| is_valid_password2(policy, password))
+            .count();
+        Answer::try_from(valid_count)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// A policy of the form `<min>-<max> <ch>`.
+pub struct PasswordPolicy {
+    ch : char,
+    min_count : u32,
+    max_count : u32
+}
+
+impl fmt::Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{} {}", self.min_count, self.max_count, self.ch)
+    }
+}
+
+/// Returns true if the password contains the policy's character between
+/// min and max times.
+pub fn is_valid_password(policy: &PasswordPolicy, password: &str) -> bool {
+    CountRange.check(policy, password).is_ok()
+}
+
+/// Returns true if exactly one of the characters at the policy's one-based
+/// positions min and max is the policy's character.
+pub fn is_valid_password2(policy: &PasswordPolicy, password: &str) -> bool {
+    Positions{ mode : PositionMode::ExactlyOne, indexing : Indexing::Chars }.check(policy, password).is_ok()
+}
+
+// Parses a policy of the form "<min>-<max> <ch>".
+fn password_policy<'a>() -> impl Parser<'a, PasswordPolicy> {
+    let policy = triple(
+        token(unsigned("a number")),
+        preceded(literal("-"), token(unsigned("a number"))),
+        preceded(take_while1(char::is_whitespace, "' '"), any_char("a character"))
+    );
+    map(policy, |(min_count, max_count, ch)| PasswordPolicy{ ch, min_count, max_count })
+}
+
+/// Parses a policy of the form `<min>-<max> <ch>`.
+pub fn parse_policy(s: &str) -> Result<PasswordPolicy, ParseError> {
+    parse_all(s, terminated(password_policy(), spaces()))
+}
+
+/// Parses a line of the form `<min>-<max> <ch>: <password>`.
+pub fn parse_line(line: &str) -> Result<(PasswordPolicy, &str), ParseError> {
+    let password = map(rest(), str::trim);
+    parse_all(line, pair(terminated(password_policy(), token(literal(":"))), password))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines() {
+        let (policy, password) = parse_line("1-3 a: abcde").unwrap();
+        assert_eq!((policy.min_count, policy.max_count, policy.ch, password), (1, 3, 'a', "abcde"));
+        assert!(is_valid_password(&policy, password) && is_valid_password2(&policy, password));
+
+        let e = parse_line("1-x a: abcde").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (3, "a number", "x"));
+        let e = parse_line("1-3 a abcde").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (7, "':'", "a"));
+    }
+}
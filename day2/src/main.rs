@@ -1,84 +1,163 @@
-use std::fs;
-use std::io::{prelude::*, BufReader};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use aoc_common::{Diagnostics, InputSource, ParseMode, Rng, Solution, Solver, INPUT_OPTIONS_USAGE, print_diagnostics, make_error};
+use day2::audit;
+use day2::passwords::{self, Generator};
+use day2::policy::PolicySet;
+
+const USAGE : &str = "Usage: day2 [--policies <path>] [--audit [--format text|csv|json]] [--strict]";
+
+const GENERATE_USAGE : &str = "Usage: day2 --generate --policy <policy>... --length <n> [--policies <path>] [--alphabet <chars>] [--random <n> | --count | --all] [--seed <n>]";
+
+// Default alphabet of generated passwords.
+const DEFAULT_ALPHABET : &str = "abcdefghijklmnopqrstuvwxyz";
 
 fn main() -> std::io::Result<()> {
-    let mut valid_count = 0;
-    let mut valid_count2 = 0;
-    for line in BufReader::new(fs::File::open("day2-input.txt")?).lines() {
-        let s = line?;
-        if let Some((policy, password)) = parse_line(&s) {
-            if is_valid_password(&policy, password) {
-                valid_count += 1;
-            }
-            if is_valid_password2(&policy, password) {
-                valid_count2 += 1;
-            }
-        }
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--generate") {
+        generate_command(&args)
+    }
+    else if args.iter().any(|arg| arg == "--policies" || arg == "--audit") {
+        policies_command(&args)
+    }
+    else {
+        aoc_common::run_main(&day2::Day2, env!("CARGO_MANIFEST_DIR"))
     }
-    println!("valid count = {}", valid_count);
-    println!("valid count per second policy = {}", valid_count2);
-    Ok(())
 }
 
-struct PasswordPolicy {
-    ch : char,
-    min_count : u32,
-    max_count : u32
-}
+// Counts the passwords of the selected input that follow every rule of a
+// policy file, instead of the rules of the puzzle. With --audit, prints
+// which rules each password follows, and why it fails the others, as text,
+// CSV or JSON; the rules are those of the puzzle unless a policy file is
+// specified.
+fn policies_command(args : &[String]) -> std::io::Result<()> {
+    let usage = || make_error(&format!("{} {}", USAGE, INPUT_OPTIONS_USAGE));
+    let mut policies = None;
+    let mut audit = false;
+    let mut format = None;
+    let mut source = InputSource::Default;
+    let mut mode = ParseMode::Lenient;
 
-fn is_valid_password(policy: &PasswordPolicy, password: &str) -> bool {
-    let mut actual_count = 0u32;
-    for ch in password.chars() {
-        if ch == policy.ch {
-            actual_count += 1;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--policies" => { policies = Some(iter.next().ok_or_else(usage)?); },
+            "--audit" => { audit = true; },
+            "--format" => {
+                match iter.next().map(|s| s.as_str()) {
+                    Some(name) if ["text", "csv", "json"].contains(&name) => { format = Some(name); },
+                    _ => { return Err(make_error("--format must be followed by text, csv or json.")); }
+                }
+            },
+            "--strict" => { mode = ParseMode::Strict; },
+            _ => { source = InputSource::from_arg(arg, &mut iter)?.ok_or_else(usage)?; }
         }
     }
-    actual_count >= policy.min_count && actual_count <= policy.max_count
-}
-
-fn is_valid_password2(policy: &PasswordPolicy, password: &str) -> bool {
-    // In this policy, reinterpret min_count and max_count as one-based character indices.
-    let i = (policy.min_count - 1) as usize;
-    let j = (policy.max_count - 1) as usize;
-
-    // Get the characters at indices i and j.
-    if let Some(ch1) = password.chars().nth(i) {
-        if let Some(ch2) = password.chars().nth(j) {
+    if format.is_some() && !audit {
+        return Err(usage());
+    }
+    let policies = match policies {
+        Some(path) => PolicySet::load(path)?,
+        None => PolicySet::puzzle()
+    };
 
-            // Return true if exactly one of the two characters equals policy.ch.
-            return (ch1 == policy.ch) != (ch2 == policy.ch);
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let name = source.name(day2::Day2.input_file());
+    let lines = source.read_lines(dir, day2::Day2.input_file())?;
+    let mut diag = Diagnostics::new(mode, Some(&name));
+    if audit {
+        let entries = audit::audit(&lines, &policies, &mut diag).map_err(|e| diag.locate(e))?;
+        print_diagnostics(&diag);
+        match format.unwrap_or("text") {
+            "csv" => print!("{}", audit::to_csv(&entries)),
+            "json" => println!("{}", audit::to_json(&entries)),
+            _ => print!("{}", audit::to_text(&entries))
         }
+        return Ok(());
     }
-    false
-}
 
-fn string_to_char(s: &str) -> Option<char> {
-    for ch in s.chars() {
-        return Some(ch);
-    }
-    None
-}
+    let input = day2::Day2.parse(&lines, &mut diag).map_err(|e| diag.locate(e))?;
+    print_diagnostics(&diag);
 
-fn split_string(s: &str, delim: char) -> Option<(&str, &str)> {
-    let i = s.find(delim)?;
-    Some((&s[..i], &s[i + 1..]))
+    let valid_count = input.iter().filter(|(policy, password)| policies.is_valid(policy, password)).count();
+    println!("{} of {} passwords are valid.", valid_count, input.len());
+    Ok(())
 }
 
-fn parse_password_policy(s: &str) -> Option<PasswordPolicy> {
+// What to do with the passwords that follow the rules.
+enum Output {
+    Random(usize),
+    Count,
+    All
+}
 
-    // Split string of the form "<min>-<max> <ch>" into three fields.
-    let (field_min, right) = split_string(s, '-')?;
-    let (field_max, field_ch) = split_string(right, ' ')?;
+// Generates, counts or lists the passwords of a given length that follow
+// the rules (of a policy file, or of the puzzle) for every one of the line
+// policies, such as "1-3 a". Reports the rules that can't be satisfied if
+// there are no such passwords. Random passwords come from Rng, which is
+// fine for test data, but isn't a cryptographic generator.
+fn generate_command(args : &[String]) -> std::io::Result<()> {
+    let usage = || make_error(GENERATE_USAGE);
+    let mut line_policies = Vec::new();
+    let mut policies = None;
+    let mut len = None;
+    let mut alphabet : Vec<char> = DEFAULT_ALPHABET.chars().collect();
+    let mut output = Output::Random(1);
+    let mut seed = None;
 
-    // Fill in the struct.
-    Some(PasswordPolicy{
-        ch: string_to_char(field_ch)?,
-        min_count: field_min.parse::<u32>().ok()?,
-        max_count: field_max.parse::<u32>().ok()?
-    })
-}
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--generate" => {},
+            "--policy" => { line_policies.push(day2::parse_policy(iter.next().ok_or_else(usage)?)?); },
+            "--policies" => { policies = Some(iter.next().ok_or_else(usage)?); },
+            "--length" => { len = Some(iter.next().and_then(|s| s.parse::<usize>().ok()).ok_or_else(usage)?); },
+            "--alphabet" => { alphabet = iter.next().ok_or_else(usage)?.chars().collect(); },
+            "--random" => { output = Output::Random(iter.next().and_then(|s| s.parse().ok()).ok_or_else(usage)?); },
+            "--count" => { output = Output::Count; },
+            "--all" => { output = Output::All; },
+            "--seed" => { seed = Some(iter.next().and_then(|s| s.parse::<u64>().ok()).ok_or_else(usage)?); },
+            _ => { return Err(usage()); }
+        }
+    }
+    let len = len.ok_or_else(usage)?;
+    if line_policies.is_empty() || alphabet.is_empty() {
+        return Err(usage());
+    }
+    let policies = match policies {
+        Some(path) => PolicySet::load(path)?,
+        None => PolicySet::puzzle()
+    };
+    let (labels, constraints) : (Vec<String>, Vec<_>) = passwords::constraints(&policies, &line_policies).map_err(
+        |rule| make_error(&format!("Passwords can't be generated for the rule '{}'.", rule))
+    )?.into_iter().unzip();
 
-fn parse_line(line: &str) -> Option<(PasswordPolicy, &str)> {
-    let (policy, password) = split_string(line, ':')?;
-    Some((parse_password_policy(policy.trim())?, password.trim()))
+    let mut generator = Generator::new(&alphabet, len, &constraints);
+    if generator.count() == 0 {
+        let rules : Vec<String> = passwords::unsatisfiable(&alphabet, len, &constraints).iter()
+            .map(|&i| format!("'{}'", labels[i]))
+            .collect();
+        let reason = if rules.is_empty() { "The rules can't all be satisfied at once".to_string() } else { format!("No password satisfies {}", rules.join(", or ")) };
+        return Err(make_error(&format!("{} with {} characters from the alphabet.", reason, len)));
+    }
+    match output {
+        Output::Count => {
+            // The count saturates if there are too many to count.
+            let count = generator.count();
+            println!("{}{}", count, if count == u128::MAX { " or more" } else { "" });
+        },
+        Output::All => {
+            for password in generator.enumerate(usize::MAX) {
+                println!("{}", password);
+            }
+        },
+        Output::Random(n) => {
+            let seed = seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64));
+            let mut rng = Rng::new(seed);
+            for _ in 0..n {
+                println!("{}", generator.random(&mut rng).unwrap_or_default());
+            }
+        }
+    }
+    Ok(())
 }
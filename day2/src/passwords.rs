@@ -0,0 +1,449 @@
+//! Generating, counting and listing the passwords that follow a set of
+//! rules.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use aoc_common::Rng;
+use super::PasswordPolicy;
+use super::policy::{PolicySet, CharClass};
+
+/// A set of characters that a `Constraint::Count` counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CharSet {
+    /// One character.
+    Char(char),
+
+    /// The characters of a class.
+    Class(CharClass),
+
+    /// Every character.
+    Any
+}
+
+impl CharSet {
+    /// Returns true if the character is in the set.
+    pub fn contains(&self, ch : char) -> bool {
+        match self {
+            CharSet::Char(c) => ch == *c,
+            CharSet::Class(class) => class.contains(ch),
+            CharSet::Any => true
+        }
+    }
+}
+
+/// A rule in a form the generator can work with. Each built-in rule is one
+/// of these for a given line policy; see `Policy::constraint`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Between min and max of the characters are in the set.
+    Count{
+        /// The characters counted.
+        set : CharSet,
+
+        /// If there are positions, only the characters at these zero-based
+        /// positions are counted, and a position listed twice counts
+        /// twice. If any of them is past the end of the password, no
+        /// password follows the rule.
+        positions : Option<Vec<usize>>,
+
+        /// The least count allowed.
+        min : usize,
+
+        /// The greatest count allowed, or None if there is no limit.
+        max : Option<usize>
+    },
+
+    /// None of the words appear in the password.
+    Forbid(Vec<String>)
+}
+
+/// Returns the constraints of every rule for each of the line policies,
+/// each with a label such as `count for 1-3 a`, or the first rule that
+/// has no constraint form.
+pub fn constraints(rules : &PolicySet, line_policies : &[PasswordPolicy]) -> Result<Vec<(String, Constraint)>, String> {
+    let mut constraints : Vec<(String, Constraint)> = Vec::new();
+    for line_policy in line_policies {
+        for rule in rules.policies() {
+            let constraint = rule.constraint(line_policy).ok_or_else(|| rule.to_string())?;
+
+            // Rules that don't depend on the line policy are the same for
+            // each one.
+            if !constraints.iter().any(|(_, c)| *c == constraint) {
+                constraints.push((format!("{} for {}", rule, line_policy), constraint));
+            }
+        }
+    }
+    Ok(constraints)
+}
+
+// A Count constraint, as applied to passwords of a given length.
+struct Counter {
+    set : CharSet,
+
+    // Number of counted positions from each position to the end.
+    remaining : Vec<usize>,
+
+    // Whether each position is counted, and how many times.
+    weights : Vec<u32>,
+    min : u32,
+    max : Option<u32>
+}
+
+/// Counts, lists and generates the passwords of a given length, made of
+/// the characters of an alphabet, that satisfy a set of constraints.
+///
+/// The passwords are built one character at a time, and the state of a
+/// partial password is what the constraints need to know about it: how
+/// many characters each Count constraint has counted so far (up to the
+/// point where more don't matter), and the state of an automaton that
+/// matches the forbidden words (Aho-Corasick). The number of passwords
+/// that complete each state is memoized, so counting takes time
+/// proportional to the number of reachable states times the alphabet size,
+/// rather than the number of passwords. Counts saturate at `u128::MAX`.
+pub struct Generator {
+    alphabet : Vec<char>,
+    len : usize,
+    counters : Vec<Counter>,
+    automaton : Automaton,
+
+    // True if some constraint can't be satisfied at this length.
+    impossible : bool,
+
+    // Number of ways to complete a password from a position and state.
+    memo : HashMap<(usize, Vec<u32>), u128>
+}
+
+impl Generator {
+    /// Creates a generator of passwords of len characters from the
+    /// alphabet, which are used in sorted order.
+    pub fn new(alphabet : &[char], len : usize, constraints : &[Constraint]) -> Generator {
+        let mut alphabet = alphabet.to_vec();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let mut counters = Vec::new();
+        let mut words = Vec::new();
+        let mut impossible = false;
+        for constraint in constraints {
+            match constraint {
+                Constraint::Count{ set, positions, min, max } => {
+                    let weights = match positions {
+                        Some(positions) => {
+                            let mut weights = vec![0; len];
+                            for &pos in positions {
+                                match weights.get_mut(pos) {
+                                    Some(w) => { *w += 1; },
+                                    None => { impossible = true; }
+                                }
+                            }
+                            weights
+                        },
+                        None => vec![1; len]
+                    };
+                    let mut remaining = vec![0; len + 1];
+                    for pos in (0..len).rev() {
+                        remaining[pos] = remaining[pos + 1] + weights[pos] as usize;
+                    }
+                    let min = u32::try_from(*min).unwrap_or(u32::MAX);
+                    let max = max.map(|max| u32::try_from(max).unwrap_or(u32::MAX));
+                    impossible |= max.is_some_and(|max| max < min);
+                    counters.push(Counter{ set : set.clone(), remaining, weights, min, max });
+                },
+                Constraint::Forbid(forbidden) => {
+                    words.extend(forbidden.iter().cloned());
+                }
+            }
+        }
+        Generator{ alphabet, len, counters, automaton : Automaton::new(&words), impossible, memo : HashMap::new() }
+    }
+
+    /// Returns the number of passwords that satisfy the constraints.
+    pub fn count(&mut self) -> u128 {
+        match self.start() {
+            Some(state) => self.ways(0, &state),
+            None => 0
+        }
+    }
+
+    /// Returns the first passwords that satisfy the constraints, in
+    /// lexicographic order, up to limit of them.
+    pub fn enumerate(&mut self, limit : usize) -> Vec<String> {
+        let mut passwords = Vec::new();
+        if let Some(state) = self.start() {
+            let mut password = String::new();
+            self.enumerate_from(0, &state, &mut password, limit, &mut passwords);
+        }
+        passwords
+    }
+
+    fn enumerate_from(&mut self, pos : usize, state : &[u32], password : &mut String, limit : usize, passwords : &mut Vec<String>) {
+        if pos == self.len {
+            passwords.push(password.clone());
+            return;
+        }
+        for i in 0..self.alphabet.len() {
+            if passwords.len() == limit {
+                return;
+            }
+            let ch = self.alphabet[i];
+            if let Some(next) = self.step(pos, state, ch) {
+                if self.ways(pos + 1, &next) > 0 {
+                    password.push(ch);
+                    self.enumerate_from(pos + 1, &next, password, limit, passwords);
+                    password.pop();
+                }
+            }
+        }
+    }
+
+    /// Returns a random password that satisfies the constraints, chosen
+    /// uniformly (unless the counts saturate), or None if there are none.
+    pub fn random(&mut self, rng : &mut Rng) -> Option<String> {
+        let mut state = self.start()?;
+        if self.ways(0, &state) == 0 {
+            return None;
+        }
+        let mut password = String::with_capacity(self.len);
+        for pos in 0..self.len {
+            // Choose each character with probability proportional to the
+            // number of passwords that start with it.
+            let mut choices = Vec::new();
+            for i in 0..self.alphabet.len() {
+                let ch = self.alphabet[i];
+                if let Some(next) = self.step(pos, &state, ch) {
+                    let ways = self.ways(pos + 1, &next);
+                    if ways > 0 {
+                        choices.push((ch, next, ways));
+                    }
+                }
+            }
+            let total = choices.iter().fold(0u128, |total, &(_, _, ways)| total.saturating_add(ways));
+            let mut r = (((rng.next_u64() as u128) << 64) | rng.next_u64() as u128) % total;
+            for (ch, next, ways) in choices {
+                if r < ways {
+                    password.push(ch);
+                    state = next;
+                    break;
+                }
+                r -= ways;
+            }
+        }
+        Some(password)
+    }
+
+    // Returns the state of the empty password, or None if no password can
+    // satisfy the constraints. The state is the automaton state, followed
+    // by the count of each counter.
+    fn start(&self) -> Option<Vec<u32>> {
+        if self.impossible {
+            return None;
+        }
+        Some(vec![0; 1 + self.counters.len()])
+    }
+
+    // Returns the state after adding ch at pos, or None if the password
+    // can no longer satisfy the constraints.
+    fn step(&self, pos : usize, state : &[u32], ch : char) -> Option<Vec<u32>> {
+        let node = self.automaton.step(state[0] as usize, ch);
+        if self.automaton.matched[node] {
+            return None;
+        }
+        let mut next = Vec::with_capacity(state.len());
+        next.push(node as u32);
+        for (counter, &count) in self.counters.iter().zip(&state[1..]) {
+            let mut count = count;
+            if counter.set.contains(ch) {
+                count += counter.weights[pos];
+            }
+            match counter.max {
+                Some(max) if count > max => { return None; },
+                Some(_) => {},
+                // Without a maximum, counts past the minimum don't matter.
+                None => { count = count.min(counter.min); }
+            }
+            if (count as usize) + counter.remaining[pos + 1] < counter.min as usize {
+                return None;
+            }
+            next.push(count);
+        }
+        Some(next)
+    }
+
+    // Returns the number of ways to complete a password in state at pos.
+    fn ways(&mut self, pos : usize, state : &[u32]) -> u128 {
+        if pos == self.len {
+            let done = self.counters.iter().zip(&state[1..]).all(|(counter, &count)| count >= counter.min);
+            return if done { 1 } else { 0 };
+        }
+        let key = (pos, state.to_vec());
+        if let Some(&ways) = self.memo.get(&key) {
+            return ways;
+        }
+        let mut ways = 0u128;
+        for i in 0..self.alphabet.len() {
+            if let Some(next) = self.step(pos, state, self.alphabet[i]) {
+                ways = ways.saturating_add(self.ways(pos + 1, &next));
+            }
+        }
+        self.memo.insert(key, ways);
+        ways
+    }
+}
+
+/// Returns the indices of the constraints that no password of len
+/// characters from the alphabet satisfies on its own. If no passwords
+/// satisfy all of the constraints, but this is empty, it is the
+/// combination of them that can't be satisfied.
+pub fn unsatisfiable(alphabet : &[char], len : usize, constraints : &[Constraint]) -> Vec<usize> {
+    (0..constraints.len())
+        .filter(|&i| Generator::new(alphabet, len, &constraints[i..=i]).count() == 0)
+        .collect()
+}
+
+// An automaton that matches any of a set of words (Aho-Corasick). Node 0
+// is the root.
+struct Automaton {
+    children : Vec<HashMap<char, usize>>,
+    fail : Vec<usize>,
+
+    // True if a word ends at the node, or at a node on its fail chain.
+    matched : Vec<bool>
+}
+
+impl Automaton {
+    fn new(words : &[String]) -> Automaton {
+        let mut children = vec![HashMap::new()];
+        let mut matched = vec![false];
+        for word in words {
+            let mut node = 0;
+            for ch in word.chars() {
+                node = match children[node].get(&ch) {
+                    Some(&child) => child,
+                    None => {
+                        children.push(HashMap::new());
+                        matched.push(false);
+                        let child = children.len() - 1;
+                        children[node].insert(ch, child);
+                        child
+                    }
+                };
+            }
+            matched[node] = true;
+        }
+
+        // Set the fail links breadth first, so each node's parent is done.
+        let mut fail = vec![0; children.len()];
+        let mut queue : std::collections::VecDeque<usize> = children[0].values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let edges : Vec<(char, usize)> = children[node].iter().map(|(&ch, &child)| (ch, child)).collect();
+            for (ch, child) in edges {
+                let mut f = fail[node];
+                while f != 0 && !children[f].contains_key(&ch) {
+                    f = fail[f];
+                }
+                fail[child] = children[f].get(&ch).copied().filter(|&target| target != child).unwrap_or(0);
+                matched[child] |= matched[fail[child]];
+                queue.push_back(child);
+            }
+        }
+        Automaton{ children, fail, matched }
+    }
+
+    fn step(&self, mut node : usize, ch : char) -> usize {
+        loop {
+            if let Some(&child) = self.children[node].get(&ch) {
+                return child;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.fail[node];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{Diagnostics, ParseMode};
+    use super::super::parse_policy;
+
+    fn rules(text : &str) -> PolicySet {
+        let lines : Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        PolicySet::parse(&lines, &mut Diagnostics::new(ParseMode::Strict, None)).unwrap()
+    }
+
+    // Returns the constraints of rules for the line policies, without
+    // their labels.
+    fn unlabeled(rules : &PolicySet, line_policies : &[&str]) -> Vec<Constraint> {
+        let line_policies : Vec<PasswordPolicy> = line_policies.iter().map(|s| parse_policy(s).unwrap()).collect();
+        constraints(rules, &line_policies).unwrap().into_iter().map(|(_, c)| c).collect()
+    }
+
+    // Lists every password of len characters from the alphabet.
+    fn all_passwords(alphabet : &[char], len : usize) -> Vec<String> {
+        let mut passwords = vec![String::new()];
+        for _ in 0..len {
+            passwords = passwords.iter().flat_map(|p| alphabet.iter().map(move |&ch| format!("{}{}", p, ch))).collect();
+        }
+        passwords
+    }
+
+    #[test]
+    fn agrees_with_the_rules() {
+        let alphabet = ['a', 'b', 'c', '1', '!'];
+        let cases = [
+            ("count\npositions exactly-one", "1-3 a"),
+            ("positions all\nforbid bb cab", "2-4 b"),
+            ("positions none\nrequire 2 letter\nrequire 1 symbol", "1-1 c"),
+            ("count\nforbid a1 1a aa\nmin-length 4", "0-2 a"),
+            ("positions exactly-one", "2-2 a")
+        ];
+        for &(text, line) in &cases {
+            let rules = rules(text);
+            let constraints = unlabeled(&rules, &[line]);
+            let line_policy = parse_policy(line).unwrap();
+            let expected : Vec<String> = all_passwords(&alphabet, 4).into_iter()
+                .filter(|password| rules.is_valid(&line_policy, password))
+                .collect();
+
+            let mut generator = Generator::new(&alphabet, 4, &constraints);
+            assert_eq!(generator.count(), expected.len() as u128, "{}", text);
+            let mut sorted = expected.clone();
+            sorted.sort();
+            assert_eq!(generator.enumerate(usize::MAX), sorted, "{}", text);
+            assert_eq!(generator.enumerate(2), sorted.iter().take(2).cloned().collect::<Vec<String>>());
+
+            let mut rng = Rng::new(7);
+            for _ in 0..5 {
+                match generator.random(&mut rng) {
+                    Some(password) => assert!(rules.is_valid(&line_policy, &password), "{}: {}", text, password),
+                    None => assert!(expected.is_empty())
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reports_unsatisfiable_rules() {
+        let alphabet : Vec<char> = ('a'..='z').collect();
+        let labeled = constraints(&rules("count\nrequire 1 digit\nmin-length 6"), &[parse_policy("1-9 z").unwrap()]).unwrap();
+        assert_eq!(labeled[1].0, "require 1 digit for 1-9 z");
+        let list : Vec<Constraint> = labeled.into_iter().map(|(_, c)| c).collect();
+        assert_eq!(Generator::new(&alphabet, 8, &list).count(), 0);
+        assert_eq!(unsatisfiable(&alphabet, 8, &list), vec![1]);
+
+        // Position 9 is past the end of an 8-character password.
+        let list = unlabeled(&rules("positions none"), &["1-9 z"]);
+        assert_eq!(unsatisfiable(&alphabet, 8, &list), vec![0]);
+
+        // Each policy alone can be satisfied, but not both.
+        let list = unlabeled(&rules("count"), &["5-8 a", "5-8 b"]);
+        assert_eq!(Generator::new(&alphabet, 8, &list).count(), 0);
+        assert!(unsatisfiable(&alphabet, 8, &list).is_empty());
+
+        // 26^30 is more than a u128 can hold.
+        assert_eq!(Generator::new(&alphabet, 30, &[]).count(), u128::MAX);
+        assert_eq!(Generator::new(&alphabet, 20, &[]).count(), 26u128.pow(20));
+    }
+}
@@ -0,0 +1,449 @@
+//! Password rules that can be combined and loaded from a policy file.
+//!
+//! Each line of a policy file is one rule, and a password is valid if it
+//! follows every rule. Blank lines and lines starting with `#` are ignored.
+//! The rules are:
+//!
+//! - `count`: the character of the password's line policy appears between
+//!   min and max times (the rule of part 1).
+//! - `positions exactly-one|all|none [bytes|chars|graphemes]`: the
+//!   character is at exactly one, both, or neither of the one-based
+//!   positions min and max (part 2 is `exactly-one`). The positions count
+//!   characters by default; see `Indexing` for the others.
+//! - `forbid <word>...`: the password doesn't contain any of the words.
+//! - `require <n> lower|upper|letter|digit|symbol`: the password has at
+//!   least n characters of the class, where a symbol is any character that
+//!   isn't a letter, digit or whitespace.
+//! - `min-length <n>`: the password has at least n characters.
+
+use std::fmt;
+use aoc_common::{ParseError, ParseMode, Diagnostics, read_file, make_error};
+use aoc_common::parser::{Parser, Cursor, Failure, parse_all, literal, take_while1, token, unsigned, map, pair, preceded, alt, cut, optional, many1};
+use super::PasswordPolicy;
+use super::indexing::{Indexing, units_at};
+use super::passwords::{Constraint, CharSet};
+
+/// A rule that a password must follow, which can depend on the policy on
+/// the password's line. Displaying a rule gives its line in a policy file.
+pub trait Policy: fmt::Display + Sync {
+    /// Returns Ok if the password, from a line with the specified policy,
+    /// follows the rule, or the reason it doesn't.
+    fn check(&self, line_policy: &PasswordPolicy, password: &str) -> Result<(), String>;
+
+    /// Returns the rule, for a line with the specified policy, as a
+    /// constraint that the password generator can work with, or None if
+    /// it has no such form, so passwords can't be generated for it.
+    fn constraint(&self, _line_policy: &PasswordPolicy) -> Option<Constraint> {
+        None
+    }
+}
+
+/// The character of the line policy must appear between min and max times.
+pub struct CountRange;
+
+impl Policy for CountRange {
+    fn check(&self, line_policy: &PasswordPolicy, password: &str) -> Result<(), String> {
+        let count = password.chars().filter(|&ch| ch == line_policy.ch).count() as u32;
+        if count >= line_policy.min_count && count <= line_policy.max_count {
+            Ok(())
+        }
+        else {
+            Err(format!(
+                "'{}' appears {} time{}, allowed {}-{}",
+                line_policy.ch, count, if count == 1 { "" } else { "s" }, line_policy.min_count, line_policy.max_count
+            ))
+        }
+    }
+
+    fn constraint(&self, line_policy: &PasswordPolicy) -> Option<Constraint> {
+        let (min, max) = (line_policy.min_count as usize, line_policy.max_count as usize);
+        Some(Constraint::Count{ set : CharSet::Char(line_policy.ch), positions : None, min, max : Some(max) })
+    }
+}
+
+impl fmt::Display for CountRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "count")
+    }
+}
+
+/// How many of the positions of a `Positions` rule must hold the character.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionMode {
+    /// One position holds the character, and the other doesn't.
+    ExactlyOne,
+
+    /// Both positions hold the character.
+    All,
+
+    /// Neither position holds the character.
+    None
+}
+
+impl PositionMode {
+    fn name(self) -> &'static str {
+        match self {
+            PositionMode::ExactlyOne => "exactly-one",
+            PositionMode::All => "all",
+            PositionMode::None => "none"
+        }
+    }
+}
+
+/// The character of the line policy must be at exactly one, both, or
+/// neither of the one-based positions min and max. Both positions must be
+/// within the password.
+pub struct Positions {
+    /// How many of the positions must hold the character.
+    pub mode : PositionMode,
+
+    /// What the positions count.
+    pub indexing : Indexing
+}
+
+impl Policy for Positions {
+    fn check(&self, line_policy: &PasswordPolicy, password: &str) -> Result<(), String> {
+        let ch = line_policy.ch;
+        let (i, j) = (line_policy.min_count, line_policy.max_count);
+
+        // Positions are one-based, so 0 is out of range, like a position
+        // past the end.
+        let units = units_at(password, self.indexing, &[(i as usize).wrapping_sub(1), (j as usize).wrapping_sub(1)]);
+        let mut buf = [0; 4];
+        let ch_text : &str = ch.encode_utf8(&mut buf);
+        let at = |k : usize, pos : u32| match units[k] {
+            Some(unit) => Ok(unit == ch_text),
+            None => Err(format!("position {} is past the end of the password", pos))
+        };
+        let (a, b) = (at(0, i)?, at(1, j)?);
+        match self.mode {
+            PositionMode::ExactlyOne if a && b => Err(format!("both positions {} and {} are '{}'", i, j, ch)),
+            PositionMode::ExactlyOne | PositionMode::All if !a && !b => Err(format!("neither position {} nor {} is '{}'", i, j, ch)),
+            PositionMode::All if !a || !b => Err(format!("position {} is not '{}'", if a { j } else { i }, ch)),
+            PositionMode::None if a || b => Err(format!("position {} is '{}'", if a { i } else { j }, ch)),
+            _ => Ok(())
+        }
+    }
+
+    fn constraint(&self, line_policy: &PasswordPolicy) -> Option<Constraint> {
+        // The generator builds passwords a character at a time, so it can
+        // only count positions in characters.
+        if self.indexing != Indexing::Chars {
+            return None;
+        }
+        let position = |pos : u32| (pos as usize).wrapping_sub(1);
+        let positions = vec![position(line_policy.min_count), position(line_policy.max_count)];
+        let count = match self.mode {
+            PositionMode::ExactlyOne => 1,
+            PositionMode::All => 2,
+            PositionMode::None => 0
+        };
+        Some(Constraint::Count{ set : CharSet::Char(line_policy.ch), positions : Some(positions), min : count, max : Some(count) })
+    }
+}
+
+impl fmt::Display for Positions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "positions {}", self.mode.name())?;
+        if self.indexing != Indexing::Chars {
+            write!(f, " {}", self.indexing.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// The password must not contain any of the words.
+pub struct Forbidden {
+    /// The words, which are case-sensitive.
+    pub words : Vec<String>
+}
+
+impl Policy for Forbidden {
+    fn check(&self, _line_policy: &PasswordPolicy, password: &str) -> Result<(), String> {
+        match self.words.iter().find(|word| password.contains(word.as_str())) {
+            Some(word) => Err(format!("contains '{}'", word)),
+            None => Ok(())
+        }
+    }
+
+    fn constraint(&self, _line_policy: &PasswordPolicy) -> Option<Constraint> {
+        Some(Constraint::Forbid(self.words.clone()))
+    }
+}
+
+impl fmt::Display for Forbidden {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "forbid {}", self.words.join(" "))
+    }
+}
+
+/// A class of characters that a `Require` rule counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharClass {
+    /// Lowercase letters.
+    Lower,
+
+    /// Uppercase letters.
+    Upper,
+
+    /// Letters of any case, or none.
+    Letter,
+
+    /// The ASCII digits.
+    Digit,
+
+    /// Characters other than letters, digits and whitespace.
+    Symbol
+}
+
+impl CharClass {
+    const ALL : [CharClass; 5] = [CharClass::Lower, CharClass::Upper, CharClass::Letter, CharClass::Digit, CharClass::Symbol];
+
+    /// Returns true if the character is in the class.
+    pub fn contains(self, ch : char) -> bool {
+        match self {
+            CharClass::Lower => ch.is_lowercase(),
+            CharClass::Upper => ch.is_uppercase(),
+            CharClass::Letter => ch.is_alphabetic(),
+            CharClass::Digit => ch.is_ascii_digit(),
+            CharClass::Symbol => !ch.is_alphanumeric() && !ch.is_whitespace()
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CharClass::Lower => "lower",
+            CharClass::Upper => "upper",
+            CharClass::Letter => "letter",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol"
+        }
+    }
+}
+
+/// The password must have at least count characters of the class.
+pub struct Require {
+    /// The least number of characters of the class.
+    pub count : usize,
+
+    /// The class of characters counted.
+    pub class : CharClass
+}
+
+impl Policy for Require {
+    fn check(&self, _line_policy: &PasswordPolicy, password: &str) -> Result<(), String> {
+        let count = password.chars().filter(|&ch| self.class.contains(ch)).count();
+        if count >= self.count {
+            Ok(())
+        }
+        else {
+            Err(format!("has {} {} character{}, needs at least {}", count, self.class.name(), if count == 1 { "" } else { "s" }, self.count))
+        }
+    }
+
+    fn constraint(&self, _line_policy: &PasswordPolicy) -> Option<Constraint> {
+        Some(Constraint::Count{ set : CharSet::Class(self.class), positions : None, min : self.count, max : None })
+    }
+}
+
+impl fmt::Display for Require {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "require {} {}", self.count, self.class.name())
+    }
+}
+
+/// The password must have at least the specified number of characters.
+pub struct MinLength(pub usize);
+
+impl Policy for MinLength {
+    fn check(&self, _line_policy: &PasswordPolicy, password: &str) -> Result<(), String> {
+        let len = password.chars().count();
+        if len >= self.0 {
+            Ok(())
+        }
+        else {
+            Err(format!("is {} character{} long, needs at least {}", len, if len == 1 { "" } else { "s" }, self.0))
+        }
+    }
+
+    fn constraint(&self, _line_policy: &PasswordPolicy) -> Option<Constraint> {
+        Some(Constraint::Count{ set : CharSet::Any, positions : None, min : self.0, max : None })
+    }
+}
+
+impl fmt::Display for MinLength {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "min-length {}", self.0)
+    }
+}
+
+/// The rules of a policy file, all of which a password must follow.
+pub struct PolicySet {
+    policies : Vec<Box<dyn Policy>>
+}
+
+impl PolicySet {
+    /// Creates a set of rules.
+    pub fn new(policies : Vec<Box<dyn Policy>>) -> PolicySet {
+        PolicySet{ policies }
+    }
+
+    /// Returns the rules of the puzzle: `count`, then `positions exactly-one`.
+    pub fn puzzle() -> PolicySet {
+        PolicySet::new(vec![boxed(CountRange), boxed(Positions{ mode : PositionMode::ExactlyOne, indexing : Indexing::Chars })])
+    }
+
+    /// Parses the lines of a policy file.
+    pub fn parse(lines : &[String], diag : &mut Diagnostics) -> Result<PolicySet, ParseError> {
+        let mut policies = Vec::new();
+        for (i, s) in lines.iter().enumerate() {
+            let line = s.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match parse_all(line, policy()) {
+                Ok(policy) => policies.push(policy),
+                Err(e) => diag.report(e.at_line(i + 1))?
+            }
+        }
+        Ok(PolicySet{ policies })
+    }
+
+    /// Loads a policy file. Any malformed line is an error, as is a file
+    /// with no rules, which would allow every password.
+    pub fn load(path : &str) -> std::io::Result<PolicySet> {
+        let lines = read_file(path)?;
+        let mut diag = Diagnostics::new(ParseMode::Strict, Some(path));
+        let set = PolicySet::parse(&lines, &mut diag).map_err(|e| diag.locate(e))?;
+        if set.policies.is_empty() {
+            return Err(make_error(&format!("{} has no rules.", path)));
+        }
+        Ok(set)
+    }
+
+    /// Returns the rules in the order they were defined.
+    pub fn policies(&self) -> &[Box<dyn Policy>] {
+        &self.policies
+    }
+
+    /// Returns true if the password follows every rule.
+    pub fn is_valid(&self, line_policy : &PasswordPolicy, password : &str) -> bool {
+        self.policies.iter().all(|policy| policy.check(line_policy, password).is_ok())
+    }
+}
+
+// Parses the rule on one line of a policy file.
+fn policy<'a>() -> impl Parser<'a, Box<dyn Policy>> {
+    let count = map(literal("count"), |_| boxed(CountRange));
+    let positions = preceded(literal("positions"), cut(map(
+        pair(token(position_mode()), optional(token(indexing()))),
+        |(mode, indexing)| boxed(Positions{ mode, indexing : indexing.unwrap_or(Indexing::Chars) })
+    )));
+    let word = map(token(take_while1(|ch : char| !ch.is_whitespace(), "a word")), str::to_string);
+    let forbid = preceded(literal("forbid"), cut(map(many1(word), |words| boxed(Forbidden{ words }))));
+    let require = preceded(literal("require"), cut(map(
+        pair(token(unsigned("a number")), token(char_class())),
+        |(count, class)| boxed(Require{ count, class })
+    )));
+    let min_length = preceded(literal("min-length"), cut(map(token(unsigned("a number")), |n| boxed(MinLength(n)))));
+    alt(count, alt(positions, alt(forbid, alt(require, min_length))))
+}
+
+fn boxed<P: Policy + 'static>(policy : P) -> Box<dyn Policy> {
+    Box::new(policy)
+}
+
+fn position_mode<'a>() -> impl Parser<'a, PositionMode> {
+    let modes = [PositionMode::ExactlyOne, PositionMode::All, PositionMode::None];
+    keyword(move |name| modes.iter().copied().find(|mode| mode.name() == name), "exactly-one, all or none")
+}
+
+fn indexing<'a>() -> impl Parser<'a, Indexing> {
+    keyword(Indexing::from_name, "bytes, chars or graphemes")
+}
+
+fn char_class<'a>() -> impl Parser<'a, CharClass> {
+    keyword(|name| CharClass::ALL.iter().copied().find(|class| class.name() == name), "lower, upper, letter, digit or symbol")
+}
+
+// Matches a word that lookup recognizes, such as the name of a mode.
+fn keyword<'a, T, F>(lookup : F, expected : &'static str) -> impl Parser<'a, T>
+where F: Fn(&str) -> Option<T> {
+    let word = take_while1(|ch : char| ch.is_ascii_alphabetic() || ch == '-', expected);
+    move |input : Cursor<'a>| {
+        let (name, next) = word.parse(input)?;
+        match lookup(name) {
+            Some(value) => Ok((value, next)),
+            None => Err(Failure::new(input.span_to(next), expected))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::parse_line;
+
+    fn load(text : &str) -> Result<PolicySet, ParseError> {
+        let lines : Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        PolicySet::parse(&lines, &mut Diagnostics::new(ParseMode::Strict, None))
+    }
+
+    fn check(rule : &str, line : &str) -> Result<(), String> {
+        let set = load(rule).unwrap();
+        let (line_policy, password) = parse_line(line).unwrap();
+        set.policies()[0].check(&line_policy, password)
+    }
+
+    #[test]
+    fn checks_rules() {
+        assert_eq!(check("count", "1-3 a: abcde"), Ok(()));
+        assert_eq!(check("count", "1-3 b: cdefg"), Err("'b' appears 0 times, allowed 1-3".to_string()));
+        assert_eq!(check("positions exactly-one", "1-3 a: abcde"), Ok(()));
+        assert_eq!(check("positions exactly-one", "2-9 c: ccccccccc"), Err("both positions 2 and 9 are 'c'".to_string()));
+        assert_eq!(check("positions all", "2-9 c: ccccccccc"), Ok(()));
+        assert_eq!(check("positions all", "1-3 a: abcde"), Err("position 3 is not 'a'".to_string()));
+        assert_eq!(check("positions none", "1-3 b: cdefg"), Ok(()));
+        assert_eq!(check("positions none", "1-6 a: abcde"), Err("position 6 is past the end of the password".to_string()));
+        assert_eq!(check("forbid 1234 pass", "1-3 a: mypassword"), Err("contains 'pass'".to_string()));
+        assert_eq!(check("require 2 digit", "1-3 a: a1b"), Err("has 1 digit character, needs at least 2".to_string()));
+        assert_eq!(check("require 1 symbol", "1-3 a: a!b"), Ok(()));
+        assert_eq!(check("min-length 8", "1-3 a: abcde"), Err("is 5 characters long, needs at least 8".to_string()));
+    }
+
+    #[test]
+    fn indexes_positions() {
+        // An e with a combining accent is two characters, but one grapheme.
+        let line = "1-2 a: e\u{301}ab";
+        assert_eq!(check("positions exactly-one", line), Err("neither position 1 nor 2 is 'a'".to_string()));
+        assert_eq!(check("positions exactly-one chars", line), Err("neither position 1 nor 2 is 'a'".to_string()));
+        assert_eq!(check("positions exactly-one graphemes", line), Ok(()));
+        assert_eq!(check("positions exactly-one bytes", "4-5 a: e\u{301}ab"), Ok(()));
+
+        // Precomposed characters take two bytes.
+        assert_eq!(check("positions all", "1-3 \u{e9}: \u{e9}t\u{e9}"), Ok(()));
+        assert_eq!(check("positions all bytes", "1-4 \u{e9}: \u{e9}t\u{e9}"), Ok(()));
+        assert_eq!(check("positions none bytes", "2-5 \u{e9}: \u{e9}t\u{e9}"), Ok(()));
+        assert_eq!(check("positions none bytes", "1-6 t: \u{e9}t\u{e9}"), Err("position 6 is past the end of the password".to_string()));
+        assert_eq!(check("positions none", "0-1 t: t"), Err("position 0 is past the end of the password".to_string()));
+
+        let names : Vec<String> = load("positions all chars\npositions none graphemes").unwrap().policies().iter().map(|policy| policy.to_string()).collect();
+        assert_eq!(names, vec!["positions all", "positions none graphemes"]);
+    }
+
+    #[test]
+    fn parses_policy_files() {
+        let set = load("# Both parts\ncount\n\n  positions exactly-one\nforbid a b\nrequire 1 upper\nmin-length 3").unwrap();
+        let names : Vec<String> = set.policies().iter().map(|policy| policy.to_string()).collect();
+        assert_eq!(names, vec!["count", "positions exactly-one", "forbid a b", "require 1 upper", "min-length 3"]);
+
+        let (line_policy, password) = parse_line("1-3 a: abcde").unwrap();
+        assert!(!set.is_valid(&line_policy, password));
+        assert!(load("count\npositions exactly-one").unwrap().is_valid(&line_policy, password));
+
+        let e = load("count\npositions some").err().unwrap();
+        assert_eq!((e.line, e.column, e.expected.as_str(), e.found.as_str()), (2, 11, "exactly-one, all or none", "some"));
+        let e = load("minimum 3").err().unwrap();
+        assert_eq!((e.line, e.column), (1, 1));
+    }
+}
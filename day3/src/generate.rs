@@ -0,0 +1,12 @@
+use aoc_common::{Generated, Rng};
+
+// Width of generated maps, the same as the puzzle input.
+const WIDTH : usize = 31;
+
+// Generates a map with size rows.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let lines = (0..size)
+        .map(|_| (0..WIDTH).map(|_| if rng.chance(0.2) { '#' } else { '.' }).collect())
+        .collect();
+    Generated::new(lines)
+}
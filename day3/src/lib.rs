@@ -0,0 +1,66 @@
+//! Day 3: Toboggan Trajectory. Counts the trees hit sliding down a map
+//! that repeats to the right, along various slopes.
+
+#![warn(missing_docs)]
+
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Grid, Rng};
+
+mod generate;
+
+/// The solution for day 3.
+pub struct Day3;
+
+impl Solution for Day3 {
+    // Map cells, which are true for trees.
+    type Input = Grid<bool>;
+    const DAY: u32 = 3;
+    const INPUT_FILE: &'static str = "day3-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Self::Input, ParseError> {
+        parse_map(lines, diag)
+    }
+
+    fn part1(&self, map: &Self::Input) -> std::io::Result<Answer> {
+        Ok(Answer::from(count_trees(map, 3, 1)))
+    }
+
+    fn part2(&self, map: &Self::Input) -> std::io::Result<Answer> {
+        let inputs = [ (1,1), (3,1), (5,1), (7,1), (1,2) ];
+
+        let mut product = 1i64;
+        for (x, y) in &inputs {
+            product *= count_trees(map, *x, *y) as i64;
+        }
+        Ok(Answer::from(product))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Counts the trees hit moving right dx and down dy at a time from the
+/// top left of the map, which repeats to the right.
+pub fn count_trees(map: &Grid<bool>, dx : usize, dy : usize) -> i32 {
+    let mut x : usize = 0;
+    let mut y : usize = 0;
+    let mut tree_count = 0;
+
+    while y < map.height() {
+        if *map.get_wrapped(x as isize, y as isize) {
+            tree_count += 1;
+        }
+        x += dx;
+        y += dy;
+    }
+    tree_count
+}
+
+/// Parses a map of '#' (tree) and '.' characters.
+pub fn parse_map(lines: &[String], diag: &mut Diagnostics) -> Result<Grid<bool>, ParseError> {
+    Grid::parse(lines, diag, "'#' or '.'", |ch| match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None
+    })
+}
@@ -0,0 +1,108 @@
+use aoc_common::{Answer, Generated, Rng};
+
+const REQUIRED_FIELDS : [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+const EYE_COLORS : [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+// Generates size passports. Each passport is missing a required field, has
+// an invalid value for one field, or is valid, which determines the answers.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut lines = Vec::new();
+    let mut present_count = 0;
+    let mut valid_count = 0;
+
+    for i in 0..size {
+        let mut fields : Vec<(String, String)> = REQUIRED_FIELDS.iter()
+            .map(|&name| (name.to_string(), valid_value(rng, name)))
+            .collect();
+        if rng.chance(0.5) {
+            fields.push(("cid".to_string(), rng.range(100, 350).to_string()));
+        }
+
+        match rng.below(3) {
+            0 => {
+                // Remove a required field.
+                let j = rng.index(REQUIRED_FIELDS.len());
+                fields.remove(j);
+            },
+            1 => {
+                // Make one of the required fields invalid.
+                let j = rng.index(REQUIRED_FIELDS.len());
+                fields[j].1 = invalid_value(rng, REQUIRED_FIELDS[j]);
+                present_count += 1;
+            },
+            _ => {
+                present_count += 1;
+                valid_count += 1;
+            }
+        }
+        rng.shuffle(&mut fields);
+
+        // Split the fields into lines at random.
+        if i != 0 {
+            lines.push(String::new());
+        }
+        let mut line = String::new();
+        for (name, value) in fields {
+            if !line.is_empty() {
+                if rng.chance(0.3) {
+                    lines.push(line);
+                    line = String::new();
+                }
+                else {
+                    line.push(' ');
+                }
+            }
+            line.push_str(&format!("{}:{}", name, value));
+        }
+        lines.push(line);
+    }
+
+    Generated::new(lines).with_answers(
+        Some(Answer::from(present_count)),
+        Some(Answer::from(valid_count))
+    )
+}
+
+fn valid_value(rng : &mut Rng, name : &str) -> String {
+    match name {
+        "byr" => rng.range(1920, 2002).to_string(),
+        "iyr" => rng.range(2010, 2020).to_string(),
+        "eyr" => rng.range(2020, 2030).to_string(),
+        "hgt" => {
+            if rng.chance(0.5) {
+                format!("{}cm", rng.range(150, 193))
+            }
+            else {
+                format!("{}in", rng.range(59, 76))
+            }
+        },
+        "hcl" => format!("#{:06x}", rng.below(0x1000000)),
+        "ecl" => rng.choose(&EYE_COLORS).to_string(),
+        _ => format!("{:09}", rng.below(1_000_000_000))
+    }
+}
+
+fn invalid_value(rng : &mut Rng, name : &str) -> String {
+    match name {
+        "byr" => rng.range(2003, 2030).to_string(),
+        "iyr" => rng.range(1990, 2009).to_string(),
+        "eyr" => rng.range(2031, 2050).to_string(),
+        "hgt" => {
+            match rng.below(3) {
+                0 => format!("{}cm", rng.range(100, 149)),
+                1 => format!("{}in", rng.range(77, 99)),
+                _ => rng.range(59, 193).to_string()
+            }
+        },
+        "hcl" => {
+            if rng.chance(0.5) {
+                format!("{:06x}", rng.below(0x1000000))
+            }
+            else {
+                format!("#{:05x}z", rng.below(0x100000))
+            }
+        },
+        "ecl" => rng.choose(&["xry", "zzz", "gmt", "utc"]).to_string(),
+        _ => format!("{:010}", rng.below(10_000_000_000))
+    }
+}
@@ -0,0 +1,215 @@
+//! Day 4: Passport Processing. Counts the passports with the required
+//! fields, and those whose fields are also valid.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, split_records};
+
+mod generate;
+
+/// The solution for day 4.
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Record>;
+    const DAY: u32 = 4;
+    const INPUT_FILE: &'static str = "day4-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<Record>, ParseError> {
+        parse_passports(lines, diag)
+    }
+
+    fn part1(&self, v: &Vec<Record>) -> std::io::Result<Answer> {
+        Answer::try_from(v.iter().filter(|rec| rec.has_required_fields()).count())
+    }
+
+    fn part2(&self, v: &Vec<Record>) -> std::io::Result<Answer> {
+        Answer::try_from(v.iter().filter(|rec| rec.is_valid()).count())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+// Flag bits for each Record field.
+const FIELD_BYR : u8 = 0x01; // birth year
+const FIELD_IYR : u8 = 0x02; // issue year
+const FIELD_EYR : u8 = 0x04; // expiration year
+const FIELD_HGT : u8 = 0x08; // height
+const FIELD_HCL : u8 = 0x10; // hair color
+const FIELD_ECL : u8 = 0x20; // eye color
+const FIELD_PID : u8 = 0x40; // passport id
+const FIELD_CID : u8 = 0x80; // country id
+
+// Masks of all fields and required fields.
+const FIELD_ALL : u8 = 0xFF;
+const FIELD_REQUIRED : u8 = FIELD_ALL ^ FIELD_CID;
+
+/// The fields present in a passport, and which of them are valid.
+pub struct Record {
+    // Fields that are present, whether or not their values are valid.
+    present_mask : u8,
+
+    // Fields that are present and have valid values.
+    field_mask : u8
+}
+
+impl Record {
+    fn is_empty(&self) -> bool {
+        self.present_mask == 0
+    }
+    /// Returns true if all the required fields are present.
+    pub fn has_required_fields(&self) -> bool {
+        (self.present_mask & FIELD_REQUIRED) == FIELD_REQUIRED
+    }
+    /// Returns true if all the required fields are present and valid.
+    pub fn is_valid(&self) -> bool {
+        (self.field_mask & FIELD_REQUIRED) == FIELD_REQUIRED
+    }
+    fn new() -> Record {
+        Record{ present_mask : 0, field_mask : 0 }
+    }
+
+    // Sets a field, returning false if the name is not a known field.
+    fn set_field(&mut self, name: &str, value: &str) -> bool {
+        self.present_mask |= match name {
+            "byr" => FIELD_BYR,
+            "iyr" => FIELD_IYR,
+            "eyr" => FIELD_EYR,
+            "hgt" => FIELD_HGT,
+            "hcl" => FIELD_HCL,
+            "ecl" => FIELD_ECL,
+            "pid" => FIELD_PID,
+            "cid" => FIELD_CID,
+            _ => 0
+        };
+        match name {
+            "byr" => {
+                if is_valid_number(value, 1920, 2002) {
+                    self.field_mask |= FIELD_BYR;
+                }
+            },
+            "iyr" => {
+                if is_valid_number(value, 2010, 2020) {
+                    self.field_mask |= FIELD_IYR;
+                }
+            },
+            "eyr" => {
+                if is_valid_number(value, 2020, 2030) {
+                    self.field_mask |= FIELD_EYR;
+                }
+            },
+            "hgt" => {
+                if is_valid_height(value) {
+                    self.field_mask |= FIELD_HGT;
+                }
+            },
+            "hcl" => {
+                if is_valid_hex_color(value) {
+                    self.field_mask |= FIELD_HCL;
+                }
+            },
+            "ecl" => {
+                if is_valid_eye_color(value) {
+                    self.field_mask |= FIELD_ECL;
+                }
+            },
+            "pid" => {
+                if is_valid_passport_id(value) {
+                    self.field_mask |= FIELD_PID;
+                }
+            },
+            "cid" => {
+                self.field_mask |= FIELD_CID;
+            },
+            _ => {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn is_valid_number(s: &str, min_value: u32, max_value: u32) -> bool {
+    if let Ok(n) = s.parse::<u32>() {
+        return n >= min_value && n <= max_value;
+    }
+    false
+}
+
+fn is_valid_passport_id(s: &str) -> bool {
+    let mut digits = 0;
+    for ch in s.chars() {
+        if ch.is_ascii_digit() {
+            digits += 1;
+        }
+        else {
+            return false;
+        }
+    }
+    digits == 9
+}
+
+fn is_valid_height(s: &str) -> bool {
+    if s.len() > 2 {
+        let i = s.len() - 2;
+        let units = &s[i..];
+        let value = &s[..i];
+        return match units {
+            "cm" => is_valid_number(value, 150, 193),
+            "in" => is_valid_number(value, 59, 76),
+            _ => false
+        };
+    }
+    false
+}
+
+fn is_hex_digit(ch: char) -> bool {
+    ch.is_ascii_digit() || ('a'..='f').contains(&ch)
+}
+
+fn is_valid_hex_color(s: &str) -> bool {
+    if s.len() != 7 { return false; }
+    if &s[..1] != "#" { return false; }
+    for ch in s[1..].chars() {
+        if !is_hex_digit(ch) { return false; }
+    }
+    true
+}
+
+fn is_valid_eye_color(s: &str) -> bool {
+    matches!(s, "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth")
+}
+
+/// Parses passports, which are separated by blank lines and consist of
+/// whitespace-separated `<name>:<value>` fields.
+pub fn parse_passports(lines: &[String], diag: &mut Diagnostics) -> Result<Vec::<Record>, ParseError> {
+    let mut v = Vec::<Record>::new();
+    for record in split_records(lines) {
+        let mut rec = Record::new();
+        for (i, s) in record.lines.iter().enumerate() {
+            for field in s.split_whitespace() {
+                let result = match field.find(':') {
+                    Some(j) => {
+                        if rec.set_field(&field[..j], &field[j + 1..]) {
+                            Ok(())
+                        }
+                        else {
+                            Err(ParseError::at_field(s, &field[..j], "a passport field name"))
+                        }
+                    },
+                    None => Err(ParseError::at_field(s, field, "<name>:<value>"))
+                };
+                if let Err(e) = result {
+                    diag.report(e.at_line(record.line_number(i)))?;
+                }
+            }
+        }
+        if !rec.is_empty() {
+            v.push(rec);
+        }
+    }
+    Ok(v)
+}
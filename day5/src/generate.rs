@@ -0,0 +1,35 @@
+use aoc_common::{Answer, Generated, Rng};
+
+// Generates about size boarding passes with a contiguous range of seat ids,
+// except for one empty seat.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    // There are 1024 seats, and the range must have room for the empty
+    // seat and the seats on either side.
+    let count = size.clamp(3, 1000) as u32;
+    let first_id = rng.range(1, (1023 - count) as i64) as u32;
+    let last_id = first_id + count;
+    let empty_id = rng.range(first_id as i64 + 1, last_id as i64 - 1) as u32;
+
+    let mut ids : Vec<u32> = (first_id..=last_id).filter(|&id| id != empty_id).collect();
+    rng.shuffle(&mut ids);
+
+    let lines = ids.iter().map(|&id| boarding_pass(id)).collect();
+    Generated::new(lines).with_answers(
+        Some(Answer::from(last_id)),
+        Some(Answer::from(empty_id))
+    )
+}
+
+// Encodes a seat id as a boarding pass, where the first 7 characters are
+// the bits of the row (B = 1) and the last 3 are the column (R = 1).
+fn boarding_pass(id : u32) -> String {
+    (0..10).rev().map(|bit| {
+        let is_set = (id >> bit) & 1 != 0;
+        match (bit >= 3, is_set) {
+            (true, false) => 'F',
+            (true, true) => 'B',
+            (false, false) => 'L',
+            (false, true) => 'R'
+        }
+    }).collect()
+}
@@ -0,0 +1,120 @@
+//! Day 5: Binary Boarding. Decodes boarding passes into seat IDs, and
+//! finds the one empty seat between occupied ones.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, make_error};
+
+mod generate;
+
+/// The solution for day 5.
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<BoardingPass>;
+    const DAY: u32 = 5;
+    const INPUT_FILE: &'static str = "day5-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<BoardingPass>, ParseError> {
+        parse_boarding_passes(lines, diag)
+    }
+
+    fn part1(&self, passes: &Vec<BoardingPass>) -> std::io::Result<Answer> {
+        Ok(Answer::from(max_id(passes)))
+    }
+
+    fn part2(&self, passes: &Vec<BoardingPass>) -> std::io::Result<Answer> {
+        match find_empty_seat(passes) {
+            Some(id) => Answer::try_from(id),
+            None => Err(make_error("No empty seat found."))
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Returns the highest seat ID of the boarding passes, or 0 if none.
+pub fn max_id(passes: &[BoardingPass]) -> u32 {
+    // Iterate over the passes once to determine the max id.
+    let mut max_id : u32 = 0;
+    for pass in passes {
+        let id = pass.get_id();
+        if id > max_id { max_id = id; }
+    }
+    max_id
+}
+
+/// Finds the ID of the empty seat whose neighbours on either side are both
+/// occupied.
+pub fn find_empty_seat(passes: &[BoardingPass]) -> Option<usize> {
+    let max_id = max_id(passes);
+
+    // Create a vector of bool to keep track of used seats.
+    let mut used_seats : Vec<bool> = vec![false; (max_id + 1) as usize];
+    for pass in passes {
+        used_seats[pass.get_id() as usize] = true;
+    }
+
+    // Find the empty seat using the following criteria.
+    //  - The seat with id is not used
+    //  - The seats in front (id - 1) and behind (id + 1) are used
+    (1..(max_id as usize)).find(|&id| !used_seats[id] && used_seats[id - 1] && used_seats[id + 1])
+}
+
+/// A decoded boarding pass.
+pub struct BoardingPass {
+    id : u32
+}
+
+impl BoardingPass {
+    /// Returns the seat ID, which is the row times 8 plus the column.
+    pub fn get_id(&self) -> u32 { self.id }
+    //fn get_row(&self) -> u32 { self.id >> 8 }
+    //fn get_col(&self) -> u32 { self.id & 7 }
+}
+
+/// Parses a boarding pass of 7 'F' or 'B' row characters followed by 3 'L'
+/// or 'R' column characters.
+pub fn parse_boarding_pass(s: &str) -> Result<BoardingPass, ParseError> {
+    let mut row_min : u32 = 0;
+    let mut row_lim : u32 = 128;
+    let mut col_min : u32 = 0;
+    let mut col_lim : u32 = 8;
+
+    // A boarding pass is 7 row characters followed by 3 column characters.
+    for (col, (i, ch)) in s.char_indices().enumerate() {
+        match (col, ch) {
+            (0..=6, 'F') => { row_lim = (row_min + row_lim) >> 1; },
+            (0..=6, 'B') => { row_min = (row_min + row_lim) >> 1; },
+            (7..=9, 'L') => { col_lim = (col_min + col_lim) >> 1; },
+            (7..=9, 'R') => { col_min = (col_min + col_lim) >> 1; },
+            (0..=6, _) => { return Err(ParseError::new(col + 1, "'F' or 'B'", &s[i..i + ch.len_utf8()])); },
+            (7..=9, _) => { return Err(ParseError::new(col + 1, "'L' or 'R'", &s[i..i + ch.len_utf8()])); },
+            _ => { return Err(ParseError::new(col + 1, "end of line", &s[i..])); }
+        }
+    }
+
+    if row_min + 1 != row_lim {
+        return Err(ParseError::end_of_line(s, "'F' or 'B'"));
+    }
+    if col_min + 1 != col_lim {
+        return Err(ParseError::end_of_line(s, "'L' or 'R'"));
+    }
+
+    Ok(BoardingPass{ id: (row_min << 3) | col_min })
+}
+
+/// Parses boarding passes, one per line, reporting malformed lines to diag.
+pub fn parse_boarding_passes(lines: &[String], diag: &mut Diagnostics) -> Result<Vec::<BoardingPass>, ParseError> {
+    let mut v = Vec::<BoardingPass>::new();
+    for (i, s) in lines.iter().enumerate() {
+        match parse_boarding_pass(s) {
+            Ok(pass) => v.push(pass),
+            Err(e) => diag.report(e.at_line(i + 1))?
+        }
+    }
+    Ok(v)
+}
@@ -0,0 +1,29 @@
+use aoc_common::{Generated, Rng};
+
+// Generates size groups of one to five people, each answering "yes" to a
+// random set of questions.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut lines = Vec::new();
+    for i in 0..size {
+        if i != 0 {
+            lines.push(String::new());
+        }
+
+        // Questions that most of the group answered "yes" to, so that some
+        // are answered by everyone.
+        let common : Vec<bool> = (0..26).map(|_| rng.chance(0.3)).collect();
+        for _ in 0..rng.range(1, 5) {
+            let answers : String = (0..26u8)
+                .filter(|&q| if common[q as usize] { rng.chance(0.9) } else { rng.chance(0.1) })
+                .map(|q| (b'a' + q) as char)
+                .collect();
+            if answers.is_empty() {
+                lines.push("a".to_string());
+            }
+            else {
+                lines.push(answers);
+            }
+        }
+    }
+    Generated::new(lines)
+}
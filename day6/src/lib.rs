@@ -0,0 +1,110 @@
+//! Day 6: Custom Customs. Counts the questions that anyone, and that
+//! everyone, in each group answered "yes" to.
+
+#![warn(missing_docs)]
+
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, split_records};
+
+mod generate;
+
+/// The solution for day 6.
+pub struct Day6;
+
+/// Answer bits for each group, combined with OR (anyone answered "yes")
+/// and AND (everyone answered "yes").
+pub struct Groups {
+    groups_or : Vec<u32>,
+    groups_and : Vec<u32>
+}
+
+impl Solution for Day6 {
+    type Input = Groups;
+    const DAY: u32 = 6;
+    const INPUT_FILE: &'static str = "day6-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Groups, ParseError> {
+        parse_groups(lines, diag)
+    }
+
+    // Total for part 1 (*any*).
+    fn part1(&self, groups: &Groups) -> std::io::Result<Answer> {
+        Ok(Answer::from(count_answers(groups.any())))
+    }
+
+    // Total for part 2 (*all*).
+    fn part2(&self, groups: &Groups) -> std::io::Result<Answer> {
+        Ok(Answer::from(count_answers(groups.all())))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+impl Groups {
+    /// Returns the questions anyone in each group answered, as bitmasks
+    /// where 'a' is bit 0.
+    pub fn any(&self) -> &[u32] {
+        &self.groups_or
+    }
+
+    /// Returns the questions everyone in each group answered, as bitmasks
+    /// where 'a' is bit 0.
+    pub fn all(&self) -> &[u32] {
+        &self.groups_and
+    }
+}
+
+/// Parses the answers of each group, where groups are separated by blank
+/// lines and each line is one person's answers.
+pub fn parse_groups(lines: &[String], diag: &mut Diagnostics) -> Result<Groups, ParseError> {
+    let mut groups_or = Vec::<u32>::new();
+    let mut groups_and = Vec::<u32>::new();
+
+    // Each record (group of lines) is one group's answers.
+    for group in split_records(lines) {
+        let mut answers_and : u32 = 0xFFFFFFFF;
+        let mut answers_or : u32 = 0;
+
+        for (line, s) in group.lines.iter().enumerate() {
+            let mut answer_bits : u32 = 0;
+
+            // Set the bit corresponding to each answer, where 'a' is bit 0, etc.
+            for (col, ch) in s.chars().enumerate() {
+                if ch.is_ascii_lowercase() {
+                    let i = (ch as i32) - ('a' as i32);
+                    answer_bits |= 1u32 << i;
+                }
+                else if !ch.is_whitespace() {
+                    let e = ParseError::new(col + 1, "a question from 'a' to 'z'", &ch.to_string());
+                    diag.report(e.at_line(group.line_number(line)))?;
+                }
+            }
+
+            answers_or |= answer_bits;
+            answers_and &= answer_bits;
+        }
+
+        groups_or.push(answers_or);
+        groups_and.push(answers_and);
+    }
+
+    Ok(Groups{ groups_or, groups_and })
+}
+
+/// Returns the total number of answers in the bitmasks.
+pub fn count_answers(groups: &[u32]) -> u32 {
+    groups.iter()
+        .map(|n| count_bits(*n))
+        .sum()
+}
+
+fn count_bits(n : u32) -> u32 {
+    let mut bits = n;
+    let mut c = 0;
+    while bits != 0 {
+        c += 1;
+        bits &= bits - 1;
+    }
+    c
+}
\ No newline at end of file
@@ -1,5 +1,8 @@
-use std::fs;
-use std::io::{prelude::*, BufReader};
+//! Parsing and querying the rules that say which bags contain which
+//! other bags.
+
+use aoc_common::{ParseError, Diagnostics};
+use aoc_common::parser::{Parser, parse_all, literal, take_until, unsigned, map, pair, terminated, alt, optional, separated_list1};
 
 const INVALID_INDEX : u32 = 0xFFFFFFFF;
 
@@ -25,7 +28,7 @@ struct ColorDef {
     first_child : u32
 }
 
-// Public wrapper for a color definition.
+/// A bag color with a rule in a `RuleList`.
 pub struct BagColor<'a> {
     rule_list : &'a RuleList,
     color_def : &'a ColorDef,
@@ -33,14 +36,17 @@ pub struct BagColor<'a> {
 }
 
 impl<'a> BagColor<'a> {
+    /// Returns the index of the color in its `RuleList`.
     pub fn index(&self) -> u32 {
         self.index
     }
 
+    /// Returns the name of the color, such as "shiny gold".
     pub fn name(&self) -> &'a str {
         &self.color_def.color_name
     }
 
+    /// Returns an iterator over the bags a bag of this color must contain.
     pub fn children(&self) -> ChildBagIterator<'a> {
         ChildBagIterator{ 
             rule_list : self.rule_list,
@@ -48,6 +54,8 @@ impl<'a> BagColor<'a> {
         }
     }
 
+    /// Returns true if a bag of this color contains a bag of the color with
+    /// the specified index, directly or indirectly.
     pub fn contains_color(&self, other_index : u32) -> bool {
         for child in self.children() {
             let color = child.get_color();
@@ -66,28 +74,30 @@ struct ChildNode {
     child_color : u32
 }
 
-// Public wrapper for a child bag.
+/// A color and number of bags contained in a bag of another color.
 pub struct ChildBag<'a> {
     rule_list : &'a RuleList,
     node : &'a ChildNode
 }
 
 impl<'a> ChildBag<'a> {
+    /// Returns the number of bags of this color.
     pub fn get_count(&self) -> u32 {
         self.node.child_count
     }
 
+    /// Returns the color of the contained bags.
     pub fn get_color(&self) -> BagColor<'a> {
         let index = self.node.child_color;
         BagColor{
-            rule_list : &self.rule_list,
+            rule_list : self.rule_list,
             color_def : &self.rule_list.color_defs[index as usize],
-            index : index
+            index
         }
     }
 }
 
-// Iterator for linked list of child bags.
+/// Iterator over the child bags of a color.
 pub struct ChildBagIterator<'a> {
     rule_list : &'a RuleList,
     child_id : u32
@@ -102,43 +112,46 @@ impl<'a> Iterator for ChildBagIterator<'a> {
         else {
             let node = &self.rule_list.child_nodes[self.child_id as usize];
             self.child_id = node.next_child;
-            Some(ChildBag{ rule_list : self.rule_list, node : &node })
+            Some(ChildBag{ rule_list : self.rule_list, node })
         }
     }
 }
 
+/// The list of bag rules, one per color.
 pub struct RuleList {
     color_defs : Vec<ColorDef>,
     child_nodes : Vec<ChildNode>
 }
 
 impl RuleList {
-    pub fn new(path : &str) -> std::io::Result<RuleList> {
+    /// Parses the rules, one per line, reporting malformed lines to diag.
+    pub fn new(lines : &[String], diag : &mut Diagnostics) -> Result<RuleList, ParseError> {
         let mut rules = RuleList{ color_defs : Vec::new(), child_nodes : Vec::new() };
-        for line in BufReader::new(fs::File::open(path)?).lines() {
-            rules.add_rule(&line?);
+        for (i, line) in lines.iter().enumerate() {
+            if let Err(e) = rules.add_rule(line) {
+                diag.report(e.at_line(i + 1))?;
+            }
         }
         Ok(rules)
     }
 
-    pub fn find_color(&self, color_name : &str) -> Option<BagColor> {
-        if let Some(index) = self.find_color_id(color_name) {
-            Some(self.get_color(index))
-        }
-        else {
-            None
-        }
+    /// Finds the color with the specified name, if it has a rule.
+    pub fn find_color(&self, color_name : &str) -> Option<BagColor<'_>> {
+        self.find_color_id(color_name).map(|index| self.get_color(index))
     }
 
+    /// Returns the number of colors.
     pub fn count(&self) -> u32 {
         self.color_defs.len() as u32
     }
 
-    pub fn get_color(&self, index : u32) -> BagColor {
+    /// Returns the color with the specified index, which must be less
+    /// than `count()`.
+    pub fn get_color(&self, index : u32) -> BagColor<'_> {
         BagColor{ 
             rule_list : self, 
             color_def : &self.color_defs[index as usize], 
-            index : index 
+            index 
         }
     }
 
@@ -162,54 +175,73 @@ impl RuleList {
         }
     }
 
-    fn add_rule(&mut self, line : &str) {
-        // Parse a rule with the following BNF:
-        //
-        //      <rule> = <color_name> " bags contain " <tail>
-        //      <tail> = <content> ( ", " <content> )* "."
-        //      <content> = <number> " " <color_name> " bag" ["s"] | "no other bags"
-        //
-        // First separate <color_name> from <tail>.
-        if let Some((color_name, mut tail)) = split2(line, " bags contain ") {
-            let color_id = self.get_color_id(color_name);
-
-            // Remove trailing period from the tail.
-            if let Some(i) = tail.find('.') {
-                tail = &tail[0..i];
-            }
-            
-            // Split the tail into comma-delimited content productions.
-            for content in tail.split(", ") {
-                
-                // Split the first word (number) from the color name.
-                if let Some((number, child_color_name)) = split2(content, " ") {
-
-                    // Parse the number; this will fail in the case of "no other bags".
-                    if let Ok(child_count) = number.parse::<u32>() {
-
-                        // Insert a new ChildNode at the head of the list for this ColorDef.
-                        let child_id = self.child_nodes.len() as u32;
-                        let child_color_id = self.get_color_id(trim_from(child_color_name, " bag"));
-                        let color_def = &mut self.color_defs[color_id as usize];
-                        self.child_nodes.push(ChildNode{ next_child : color_def.first_child, child_count : child_count, child_color : child_color_id });
-                        color_def.first_child = child_id;
-                    }
-                }
-            }
+    fn add_rule(&mut self, line : &str) -> Result<(), ParseError> {
+        // Parse the whole rule before changing anything, so a malformed
+        // rule is not partially added.
+        let (color_name, contents) = parse_all(line, rule())?;
+
+        let color_id = self.get_color_id(color_name);
+        for (child_count, child_color_name) in contents {
+
+            // Insert a new ChildNode at the head of the list for this ColorDef.
+            let child_id = self.child_nodes.len() as u32;
+            let child_color_id = self.get_color_id(child_color_name);
+            let color_def = &mut self.color_defs[color_id as usize];
+            self.child_nodes.push(ChildNode{ next_child : color_def.first_child, child_count, child_color : child_color_id });
+            color_def.first_child = child_id;
         }
+        Ok(())
     }
 }
 
-fn trim_from<'a>(s : &'a str, suffix : &str) -> &'a str {
-    match s.find(suffix) {
-        Some(i) => &s[0..i],
-        None => s
-    }
+// Returns a parser for a rule with the following BNF:
+//
+//      <rule> = <color_name> " bags contain " <tail>
+//      <tail> = ( "no other bags" | <content> ( ", " <content> )* ) "."
+//      <content> = <number> " " <color_name> " bag" ["s"]
+//
+fn rule<'a>() -> impl Parser<'a, (&'a str, Vec<(u32, &'a str)>)> {
+    let content = pair(
+        terminated(unsigned("a number"), literal(" ")),
+        terminated(take_until(" bag"), pair(literal(" bag"), optional(literal("s"))))
+    );
+    let tail = alt(
+        map(literal("no other bags"), |_| Vec::new()),
+        separated_list1(content, literal(", "))
+    );
+    pair(
+        terminated(take_until(" bags contain "), literal(" bags contain ")),
+        terminated(tail, literal("."))
+    )
 }
 
-fn split2<'a>(s : &'a str, delim : &str) -> Option<(&'a str, &'a str)> {
-    match s.find(delim) {
-        Some(i) => Some((&s[..i], &s[i + delim.len()..])),
-        None => None
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::ParseMode;
+
+    fn rules(text : &str) -> Result<RuleList, ParseError> {
+        let lines : Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        RuleList::new(&lines, &mut Diagnostics::new(ParseMode::Strict, None))
+    }
+
+    #[test]
+    fn parses_rules() {
+        let rules = rules("light red bags contain 1 bright white bag, 2 muted yellow bags.\nbright white bags contain no other bags.").unwrap();
+        let red = rules.find_color("light red").unwrap();
+        let children : Vec<(u32, &str)> = red.children().map(|child| (child.get_count(), child.get_color().name())).collect();
+        assert_eq!(children.len(), 2);
+        assert!(children.contains(&(1, "bright white")) && children.contains(&(2, "muted yellow")));
+        assert_eq!(rules.find_color("bright white").unwrap().children().count(), 0);
+    }
+
+    #[test]
+    fn reports_malformed_rules() {
+        let e = rules("light red bags contain 1 bright white bag, two muted yellow bags.").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str(), e.found.as_str()), (44, "a number", "t"));
+        let e = rules("light red bags hold no other bags.").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (35, "' bags contain '"));
+        let e = rules("light red bags contain no other bags").err().unwrap();
+        assert_eq!((e.column, e.expected.as_str()), (37, "'.'"));
     }
 }
@@ -0,0 +1,91 @@
+use std::convert::TryFrom;
+use aoc_common::{Answer, Generated, Rng};
+use super::MY_COLOR;
+
+const ADJECTIVES : [&str; 32] = [
+    "light", "dark", "bright", "muted", "faded", "dotted", "vibrant", "dull",
+    "pale", "clear", "drab", "dim", "mirrored", "plaid", "posh", "wavy",
+    "striped", "shiny", "dusty", "glossy", "matte", "vivid", "soft", "deep",
+    "hazy", "misty", "frosted", "speckled", "checkered", "dappled", "smoky", "pastel"
+];
+
+// Gold is left out, so no generated color is shiny gold.
+const COLORS : [&str; 32] = [
+    "red", "orange", "yellow", "green", "blue", "indigo", "violet", "white",
+    "black", "gray", "tan", "teal", "olive", "plum", "salmon", "crimson",
+    "aqua", "beige", "bronze", "brown", "chartreuse", "coral", "cyan", "fuchsia",
+    "lavender", "lime", "magenta", "maroon", "purple", "silver", "tomato", "turquoise"
+];
+
+// Largest answer for part 2, which is computed as a u32.
+const MAX_CONTAINED : u64 = 1_000_000_000;
+
+// Generates rules for size colors (including shiny gold), up to the number
+// of adjective and color combinations. Each color may only contain colors
+// after it in a random order, so there are no cycles. Shiny gold is in the
+// middle of the order, and some of the colors before it contain it directly.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let mut names : Vec<String> = Vec::new();
+    for adj in &ADJECTIVES {
+        for color in &COLORS {
+            names.push(format!("{} {}", adj, color));
+        }
+    }
+    rng.shuffle(&mut names);
+    let count = size.clamp(2, names.len());
+    names.truncate(count - 1);
+    let my_index = rng.range((count / 4) as i64, (count / 2) as i64) as usize;
+    names.insert(my_index, MY_COLOR.to_string());
+
+    // Generate the contents of each color as (count, index) pairs, working
+    // backward from the last color, which contains no others. Also count the
+    // bags in each color, skipping children that would make the count for
+    // any color too large.
+    let mut contents : Vec<Vec<(u64, usize)>> = vec![Vec::new(); count];
+    let mut contained = vec![0u64; count];
+    for i in (0..count.saturating_sub(1)).rev() {
+        let mut children = Vec::new();
+        if i < my_index && rng.chance(0.1) {
+            children.push(my_index);
+        }
+        if rng.chance(0.8) {
+            for _ in 0..rng.range(1, 4) {
+                children.push(rng.range((i + 1) as i64, (count - 1) as i64) as usize);
+            }
+        }
+        for child in children {
+            let n = rng.range(1, 5) as u64;
+            let total = contained[i] + n * (1 + contained[child]);
+            if total <= MAX_CONTAINED && !contents[i].iter().any(|&(_, c)| c == child) {
+                contents[i].push((n, child));
+                contained[i] = total;
+            }
+        }
+    }
+
+    // Find the colors that can contain shiny gold, working backward from
+    // shiny gold, as only colors before it can contain it.
+    let mut containing = vec![false; count];
+    for i in (0..my_index).rev() {
+        containing[i] = contents[i].iter()
+            .any(|&(_, child)| child == my_index || containing[child]);
+    }
+    let containing_count = containing.iter().filter(|&&b| b).count();
+
+    let mut lines : Vec<String> = (0..count).map(|i| rule(&names, i, &contents[i])).collect();
+    rng.shuffle(&mut lines);
+    Generated::new(lines).with_answers(
+        Answer::try_from(containing_count).ok(),
+        Answer::try_from(contained[my_index]).ok()
+    )
+}
+
+fn rule(names : &[String], index : usize, children : &[(u64, usize)]) -> String {
+    if children.is_empty() {
+        return format!("{} bags contain no other bags.", names[index]);
+    }
+    let contents : Vec<String> = children.iter()
+        .map(|&(n, child)| format!("{} {} bag{}", n, names[child], if n == 1 { "" } else { "s" }))
+        .collect();
+    format!("{} bags contain {}.", names[index], contents.join(", "))
+}
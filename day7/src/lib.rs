@@ -0,0 +1,82 @@
+//! Day 7: Handy Haversacks. Counts the bags that can contain a shiny
+//! gold bag, and the bags a shiny gold bag must contain.
+
+#![warn(missing_docs)]
+
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, Json, make_error};
+
+pub mod bag_rules;
+mod generate;
+
+/// The color of my bag.
+pub const MY_COLOR : &str = "shiny gold";
+
+/// The solution for day 7.
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = bag_rules::RuleList;
+    const DAY: u32 = 7;
+    const INPUT_FILE: &'static str = "day7-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<bag_rules::RuleList, ParseError> {
+        // Load the rule definitions.
+        bag_rules::RuleList::new(lines, diag)
+    }
+
+    fn part1(&self, rules: &bag_rules::RuleList) -> std::io::Result<Answer> {
+        let color = find_my_color(rules)?;
+        Ok(Answer::from(count_containing_colors(rules, &color)))
+    }
+
+    fn part2(&self, rules: &bag_rules::RuleList) -> std::io::Result<Answer> {
+        // Recursively count the child bags of this color and its contents.
+        let color = find_my_color(rules)?;
+        Ok(Answer::from(count_children(&color)))
+    }
+
+    // Number of colors with rules, and the counts for both parts.
+    fn details(&self, rules: &bag_rules::RuleList) -> Option<Json> {
+        let color = find_my_color(rules).ok()?;
+        Some(Json::object()
+            .with("color", MY_COLOR.into())
+            .with("color_count", rules.count().into())
+            .with("containing_colors", count_containing_colors(rules, &color).into())
+            .with("contained_bags", count_children(&color).into()))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+fn find_my_color(rules : &bag_rules::RuleList) -> std::io::Result<bag_rules::BagColor<'_>> {
+    rules.find_color(MY_COLOR).ok_or_else(
+        || make_error(&format!("No rule for {} bags.", MY_COLOR))
+    )
+}
+
+/// Returns how many other colors contain the specified color.
+pub fn count_containing_colors(rules : &bag_rules::RuleList, color : &bag_rules::BagColor) -> u32 {
+    let mut contains_count = 0;
+    for index in 0..rules.count() {
+        if rules.get_color(index).contains_color(color.index()) {
+            contains_count += 1;
+        }
+    }
+    contains_count
+}
+
+/// Returns the total number of bags inside a bag of the specified color.
+pub fn count_children(color : &bag_rules::BagColor) -> u32 {
+    let mut count = 0;
+    for child in color.children() {
+
+        // For each child bag, count the bag itself plus all the bags it contains.
+        let per_child_count = 1 + count_children(&child.get_color());
+
+        // Muliply this by the number of child bags.
+        count += per_child_count * child.get_count();
+    }
+    count
+}
@@ -1,10 +1,17 @@
-use std::fs;
-use std::io::{prelude::*, BufReader};
+//! A computer that runs programs of acc, jmp and nop instructions.
 
+use aoc_common::{ParseError, Diagnostics, parse_field};
+
+/// The operation of an instruction.
 #[derive(Copy, Clone, PartialEq)]
 pub enum OpCode {
+    /// Adds the operand to the accumulator.
     Acc,
+
+    /// Jumps by the operand, relative to the instruction.
     Jmp,
+
+    /// Does nothing.
     Nop
 }
 
@@ -26,14 +33,21 @@ struct Instruction {
 }
 
 impl Instruction {
-    fn parse(line : &str) -> Option<Instruction> {
-        let i = line.find(' ')?;
-        let op_code = OpCode::parse(&line[0..i])?;
-        let operand = line[i + 1..].parse::<i32>().ok()?;
-        Some(Instruction{ op_code : op_code, operand : operand })
+    fn parse(line : &str) -> Result<Instruction, ParseError> {
+        let i = line.find(' ').unwrap_or(line.len());
+        let op_code = OpCode::parse(&line[0..i]).ok_or_else(
+            || ParseError::at_field(line, &line[0..i], "'acc', 'jmp' or 'nop'")
+        )?;
+        if i == line.len() {
+            return Err(ParseError::end_of_line(line, "' '"));
+        }
+        let operand = parse_field::<i32>(line, &line[i + 1..], "a signed number")?;
+        Ok(Instruction{ op_code, operand })
     }
 }
 
+/// A program, with the state of the computer running it.
+#[derive(Clone)]
 pub struct Computer {
     instructions : Vec<Instruction>,
     instruction_index : i32,
@@ -41,37 +55,45 @@ pub struct Computer {
 }
 
 impl Computer {
-    pub fn load_program(path : &str) -> std::io::Result<Computer> {
+    /// Parses a program, one instruction per line, reporting malformed
+    /// lines to diag.
+    pub fn load_program(lines : &[String], diag : &mut Diagnostics) -> Result<Computer, ParseError> {
         let mut instructions = Vec::new();
-        for line in BufReader::new(fs::File::open(path)?).lines() {
-            if let Some(instruction) = Instruction::parse(&line?) {
-                instructions.push(instruction);
+        for (i, line) in lines.iter().enumerate() {
+            match Instruction::parse(line) {
+                Ok(instruction) => instructions.push(instruction),
+                Err(e) => diag.report(e.at_line(i + 1))?
             }
         }
         Ok(Computer{ 
-            instructions : instructions, 
+            instructions, 
             instruction_index : 0, 
             accumulator : 0 
         })
     }
 
+    /// Returns the number of instructions in the program.
     pub fn get_instruction_count(&self) -> usize {
         self.instructions.len()
     }
 
+    /// Returns the index of the next instruction to run.
     pub fn get_instruction_index(&self) -> usize {
         self.instruction_index as usize
     }
 
+    /// Returns the value of the accumulator.
     pub fn get_accumulator(&self) -> i32 {
         self.accumulator
     }
 
+    /// Returns true if the next instruction is in the program.
     pub fn in_bounds(&self) -> bool {
         let index = self.instruction_index;
         index >= 0 && (index as usize) < self.instructions.len()
     }
 
+    /// Runs the next instruction, if it is in the program.
     pub fn step(&mut self) {
         if self.in_bounds() {
             let index = self.instruction_index;
@@ -84,7 +106,7 @@ impl Computer {
         }
     }
 
-    // Runs until the program loops or is out of bounds.
+    /// Runs until the program loops or is out of bounds.
     pub fn run(&mut self) {
         let mut visited = Vec::new();
         visited.resize(self.get_instruction_count(), false);
@@ -94,16 +116,52 @@ impl Computer {
         }
     }
 
+    /// Returns the op code of the instruction with the specified index.
     pub fn get_op_code(&self, index : usize) -> OpCode {
         self.instructions[index].op_code
     }
 
+    /// Changes the op code of the instruction with the specified index.
     pub fn set_op_code(&mut self, index : usize, op_code : OpCode) {
         self.instructions[index].op_code = op_code;
     }
 
+    /// Resets the computer to the start of the program.
     pub fn reset(&mut self) {
         self.instruction_index = 0;
         self.accumulator = 0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::ParseMode;
+
+    fn load(program : &str) -> Computer {
+        let lines : Vec<String> = program.lines().map(|s| s.to_string()).collect();
+        Computer::load_program(&lines, &mut Diagnostics::new(ParseMode::Strict, None)).unwrap()
+    }
+
+    #[test]
+    fn runs_until_loop() {
+        let mut computer = load("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6");
+        computer.run();
+        assert!(computer.in_bounds());
+        assert_eq!(computer.get_accumulator(), 5);
+    }
+
+    #[test]
+    fn runs_until_out_of_bounds() {
+        let mut computer = load("acc +2\njmp +2\nacc +10\nacc -1");
+        computer.run();
+        assert!(!computer.in_bounds());
+        assert_eq!(computer.get_instruction_index(), 4);
+        assert_eq!(computer.get_accumulator(), 1);
+
+        computer.set_op_code(1, OpCode::Nop);
+        computer.reset();
+        computer.run();
+        assert_eq!(computer.get_accumulator(), 11);
+    }
+}
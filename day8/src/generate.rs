@@ -0,0 +1,105 @@
+use aoc_common::{Answer, Generated, Rng};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op { Acc, Jmp, Nop }
+
+// Generates a program of about size instructions that loops, but that
+// terminates if exactly one nop is changed to a jmp. The answers are found
+// by running the program before and after the fix.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let count = size.max(4);
+    loop {
+        // Generate a program that terminates, which executes some of the
+        // instructions in order and jumps forward over the others.
+        let mut program : Vec<(Op, i32)> = Vec::new();
+        let mut path = Vec::new();
+        while program.len() < count {
+            path.push(program.len());
+            let remaining = (count - program.len()) as i64;
+            match rng.below(3) {
+                0 => program.push((Op::Acc, rng.range(-50, 50) as i32)),
+                1 if remaining > 1 => {
+                    // Jump over some instructions, which are never executed.
+                    let skip = rng.range(1, 3.min(remaining - 1));
+                    program.push((Op::Jmp, skip as i32 + 1));
+                    for _ in 0..skip {
+                        program.push(random_instruction(rng));
+                    }
+                },
+                _ => program.push((Op::Nop, rng.range(-50, 50) as i32))
+            }
+        }
+
+        // Break the program by making a nop on the path jump backward to an
+        // earlier instruction on the path, so the program loops.
+        let nops : Vec<usize> = path.iter().copied().filter(|&i| program[i].0 == Op::Nop).collect();
+        if nops.is_empty() {
+            continue;
+        }
+        let fixed_index = *rng.choose(&nops);
+        let target = *rng.choose(&path[..path.iter().position(|&i| i == fixed_index).unwrap() + 1]);
+        program[fixed_index].1 = target as i32 - fixed_index as i32;
+        let fixed_acc = match execute(&program) {
+            (true, acc) => acc,
+            _ => continue
+        };
+
+        let mut broken = program.clone();
+        broken[fixed_index].0 = Op::Jmp;
+        let loop_acc = execute(&broken).1;
+
+        // Make sure no other change fixes the program.
+        if (0..count).any(|i| i != fixed_index && flip(&broken, i).is_some_and(|p| execute(&p).0)) {
+            continue;
+        }
+
+        let lines = broken.iter().map(|&(op, n)| {
+            let name = match op { Op::Acc => "acc", Op::Jmp => "jmp", Op::Nop => "nop" };
+            format!("{} {:+}", name, n)
+        }).collect();
+        return Generated::new(lines).with_answers(
+            Some(Answer::from(loop_acc)),
+            Some(Answer::from(fixed_acc))
+        );
+    }
+}
+
+fn random_instruction(rng : &mut Rng) -> (Op, i32) {
+    let op = match rng.below(3) { 0 => Op::Acc, 1 => Op::Jmp, _ => Op::Nop };
+    (op, rng.range(-50, 50) as i32)
+}
+
+// Returns a copy of the program with a jmp changed to a nop or vice versa,
+// or None if the instruction is an acc.
+fn flip(program : &[(Op, i32)], index : usize) -> Option<Vec<(Op, i32)>> {
+    let mut p = program.to_vec();
+    p[index].0 = match p[index].0 {
+        Op::Acc => { return None; },
+        Op::Jmp => Op::Nop,
+        Op::Nop => Op::Jmp
+    };
+    Some(p)
+}
+
+// Runs a program until it terminates (by going to the instruction after the
+// last) or loops. Returns whether it terminated, and the accumulator value.
+fn execute(program : &[(Op, i32)]) -> (bool, i64) {
+    let mut visited = vec![false; program.len()];
+    let mut index = 0i64;
+    let mut acc = 0i64;
+    loop {
+        if index == program.len() as i64 {
+            return (true, acc);
+        }
+        if index < 0 || index > program.len() as i64 || visited[index as usize] {
+            return (false, acc);
+        }
+        visited[index as usize] = true;
+        let (op, n) = program[index as usize];
+        match op {
+            Op::Acc => { acc += n as i64; index += 1; },
+            Op::Jmp => { index += n as i64; },
+            Op::Nop => { index += 1; }
+        }
+    }
+}
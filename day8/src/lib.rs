@@ -0,0 +1,88 @@
+//! Day 8: Handheld Halting. Finds where a boot program starts looping,
+//! and the single instruction to change so that it terminates.
+
+#![warn(missing_docs)]
+
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, Json, make_error};
+
+pub mod cpu;
+mod generate;
+
+/// The solution for day 8.
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = cpu::Computer;
+    const DAY: u32 = 8;
+    const INPUT_FILE: &'static str = "day8-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<cpu::Computer, ParseError> {
+        cpu::Computer::load_program(lines, diag)
+    }
+
+    // Accumulator value at the start of the infinite loop.
+    fn part1(&self, program: &cpu::Computer) -> std::io::Result<Answer> {
+        let mut computer = program.clone();
+        computer.run();
+        if computer.in_bounds() {
+            Ok(Answer::from(computer.get_accumulator()))
+        }
+        else {
+            Err(make_error("Program terminated without looping."))
+        }
+    }
+
+    // Final accumulator value after fixing the program.
+    fn part2(&self, program: &cpu::Computer) -> std::io::Result<Answer> {
+        match find_fix(program) {
+            Some((_index, computer)) => Ok(Answer::from(computer.get_accumulator())),
+            None => Err(make_error("No single instruction change fixes the program."))
+        }
+    }
+
+    // Index and new op code of the instruction changed to fix the program.
+    fn details(&self, program: &cpu::Computer) -> Option<Json> {
+        let (index, computer) = find_fix(program)?;
+        let op_code = match computer.get_op_code(index) {
+            cpu::OpCode::Acc => "acc",
+            cpu::OpCode::Jmp => "jmp",
+            cpu::OpCode::Nop => "nop"
+        };
+        Some(Json::object()
+            .with("fixed_instruction_index", index.into())
+            .with("fixed_op_code", op_code.into()))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+/// Finds the instruction that makes the program terminate when changed
+/// from jmp to nop or vice versa. Returns its index and the fixed program
+/// after it has run.
+pub fn find_fix(program: &cpu::Computer) -> Option<(usize, cpu::Computer)> {
+    let mut computer = program.clone();
+    for index in 0..computer.get_instruction_count() {
+        let old_op = computer.get_op_code(index);
+        let new_op = match old_op {
+            cpu::OpCode::Acc => cpu::OpCode::Acc,   // unchanged
+            cpu::OpCode::Jmp => cpu::OpCode::Nop,
+            cpu::OpCode::Nop => cpu::OpCode::Jmp
+        };
+
+        if new_op != old_op {
+            computer.set_op_code(index, new_op);
+
+            computer.reset();
+            computer.run();
+
+            if computer.get_instruction_index() == computer.get_instruction_count() {
+                return Some((index, computer));
+            }
+
+            computer.set_op_code(index, old_op);
+        }
+    }
+    None
+}
@@ -0,0 +1,68 @@
+use std::convert::TryFrom;
+use aoc_common::{Answer, Generated, Rng};
+use super::PREAMBLE_LENGTH;
+
+// Generates size numbers after the preamble, where each number is the sum
+// of two of the previous 25 numbers, except for one invalid number that is
+// the sum of a contiguous run of earlier numbers.
+pub fn generate(rng : &mut Rng, size : usize) -> Generated {
+    let count = PREAMBLE_LENGTH + size.clamp(10, 2000);
+    'outer: loop {
+        let mut numbers : Vec<u64> = Vec::new();
+        while numbers.len() < PREAMBLE_LENGTH {
+            let n = rng.range(1, 100) as u64;
+            if !numbers.contains(&n) {
+                numbers.push(n);
+            }
+        }
+
+        // The invalid number is somewhere in the second half.
+        let invalid_index = rng.range((count / 2).max(PREAMBLE_LENGTH + 2) as i64, (count - 1) as i64) as usize;
+        while numbers.len() < count {
+            let i = numbers.len();
+            let window = &numbers[i - PREAMBLE_LENGTH..];
+            if i == invalid_index {
+                // Sum a run of two to four numbers before the window that
+                // is not the sum of two numbers in the window. Start over
+                // if there is no such run.
+                let sums : Vec<u64> = (0..i - PREAMBLE_LENGTH - 1)
+                    .flat_map(|begin| (begin + 2..=(begin + 4).min(i - PREAMBLE_LENGTH))
+                        .map(move |end| (begin, end)))
+                    .map(|(begin, end)| numbers[begin..end].iter().sum())
+                    .filter(|&sum| !is_pair_sum(window, sum))
+                    .collect();
+                if sums.is_empty() {
+                    continue 'outer;
+                }
+                numbers.push(*rng.choose(&sums));
+            }
+            else {
+                // Add two of the smaller numbers in the window, so the
+                // numbers grow slowly.
+                let mut sorted = window.to_vec();
+                sorted.sort_unstable();
+                let a = rng.index(PREAMBLE_LENGTH / 2);
+                let b = rng.index(PREAMBLE_LENGTH / 2);
+                if sorted[a] != sorted[b] {
+                    numbers.push(sorted[a] + sorted[b]);
+                }
+            }
+        }
+
+        // The invalid number must be the first invalid number, so make sure
+        // none of the numbers before it happen to be invalid.
+        let first_invalid = (PREAMBLE_LENGTH..count)
+            .find(|&i| !is_pair_sum(&numbers[i - PREAMBLE_LENGTH..i], numbers[i]));
+        if first_invalid != Some(invalid_index) {
+            continue;
+        }
+
+        let lines = numbers.iter().map(|n| n.to_string()).collect();
+        return Generated::new(lines).with_answers(Answer::try_from(numbers[invalid_index]).ok(), None);
+    }
+}
+
+// Returns true if n is the sum of two different numbers in the window.
+fn is_pair_sum(window : &[u64], n : u64) -> bool {
+    window.iter().any(|&a| a < n && a * 2 != n && window.contains(&(n - a)))
+}
@@ -0,0 +1,118 @@
+//! Day 9: Encoding Error. Finds the first number that isn't the sum of
+//! two of the 25 before it, and a run of numbers that adds up to it.
+
+#![warn(missing_docs)]
+
+use std::convert::TryFrom;
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, Generated, Rng, find_k_sum, parse_numbers, make_error};
+
+mod generate;
+
+/// Number of previous numbers that each number must be a sum of two of.
+pub const PREAMBLE_LENGTH : usize = 25;
+
+/// The solution for day 9.
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<u64>;
+    const DAY: u32 = 9;
+    const INPUT_FILE: &'static str = "day9-input.txt";
+
+    fn parse(&self, lines: &[String], diag: &mut Diagnostics) -> Result<Vec<u64>, ParseError> {
+        parse_numbers::<u64>(lines, diag)
+    }
+
+    // Find the first invalid number -- one which is not the sume of any
+    // two of the previous 25 numbers.
+    fn part1(&self, numbers: &Vec<u64>) -> std::io::Result<Answer> {
+        Answer::try_from(invalid_num(numbers)?)
+    }
+
+    fn part2(&self, numbers: &Vec<u64>) -> std::io::Result<Answer> {
+        let invalid = invalid_num(numbers)?;
+
+        // Find a contiguous sequence of two or more numbers that sum to invalid.
+        if let Some((begin, end)) = find_sequence(numbers, invalid) {
+            let sequence = &numbers[begin..end];
+
+            // The sum of the minimum and maximum values in this sequence
+            // is the answer to part 2.
+            let min = find_min(sequence);
+            let max = find_max(sequence);
+            Answer::try_from(min + max)
+        }
+        else {
+            Err(make_error("No sequence found."))
+        }
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+}
+
+fn invalid_num(numbers: &[u64]) -> std::io::Result<u64> {
+    find_invalid_num(numbers).ok_or_else(|| make_error("No invalid number found."))
+}
+
+fn find_min(numbers: &[u64]) -> u64 {
+    let mut best = u64::MAX;
+    for n in numbers {
+        if *n < best {
+            best = *n;
+        }
+    }
+    best
+}
+
+fn find_max(numbers: &[u64]) -> u64 {
+    let mut best = 0;
+    for n in numbers {
+        if *n > best {
+            best = *n;
+        }
+    }
+    best
+}
+
+/// Finds the first number after the preamble that isn't the sum of two
+/// different numbers among the `PREAMBLE_LENGTH` before it.
+pub fn find_invalid_num(numbers: &[u64]) -> Option<u64> {
+    for i in PREAMBLE_LENGTH..numbers.len() {
+        if !is_valid(&numbers[i-PREAMBLE_LENGTH .. i], numbers[i]) {
+            return Some(numbers[i]);
+        }
+    }
+    None
+}
+
+/// Finds a run of two or more numbers that adds up to target_sum, and
+/// returns the range of its indices.
+pub fn find_sequence(numbers: &[u64], target_sum : u64) -> Option<(usize, usize)> {
+    for i in 1..numbers.len() {
+        let mut sum = numbers[i - 1];
+        if sum < target_sum {
+            for (j, &n) in numbers.iter().enumerate().skip(i) {
+                sum += n;
+                if sum == target_sum {
+                    return Some((i - 1, j));
+                }
+                if sum > target_sum {
+                    break;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns true if target_sum is the sum of two different numbers in the
+/// preamble.
+pub fn is_valid(preamble : &[u64], target_sum : u64) -> bool {
+    // Remove duplicates, so the pair can't be the same number twice.
+    let mut numbers = preamble.to_vec();
+    numbers.sort_unstable();
+    numbers.dedup();
+    find_k_sum(&numbers, 2, target_sum).is_some()
+}
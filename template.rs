@@ -1,18 +1,25 @@
-use std::fs;
-use std::io::{prelude::*, BufReader};
+//! DayN: <puzzle title>. <What the solution finds.>
 
-fn main() -> std::io::Result<()> {
-    let mut _input = read_file("input.txt");
+#![warn(missing_docs)]
 
-    // TODO
+use aoc_common::{Answer, Solution, ParseError, Diagnostics, make_error};
 
-    Ok(())
-}
+/// The solution for DayN.
+pub struct DayN;
+
+impl Solution for DayN {
+    type Input = Vec<String>;
+    const DAY: u32 = 0;
+
+    fn parse(&self, lines: &[String], _diag: &mut Diagnostics) -> Result<Vec<String>, ParseError> {
+        Ok(lines.to_vec())
+    }
+
+    fn part1(&self, _input: &Vec<String>) -> std::io::Result<Answer> {
+        Err(make_error("Not implemented."))
+    }
 
-fn read_file(path: &str) -> std::io::Result<Vec::<String>> {
-    let mut v = Vec::new();
-    for line in BufReader::new(fs::File::open(path)?).lines() {
-        v.push(line?);
+    fn part2(&self, _input: &Vec<String>) -> std::io::Result<Answer> {
+        Err(make_error("Not implemented."))
     }
-    Ok(v)
 }